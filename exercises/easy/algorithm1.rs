@@ -7,7 +7,10 @@
     这个问题要求你将两个有序的单链表合并为一个有序的单链表
 */
 
+use std::cmp::Ordering;
 use std::fmt::{self, Display, Formatter};
+use std::iter::FromIterator;
+use std::marker::PhantomData;
 use std::ptr::NonNull;
 
 #[derive(Debug)]
@@ -24,9 +27,11 @@ impl<T> Node<T> {
 
 #[derive(Debug)]
 struct LinkedList<T> {
-    length: u32,
+    length: usize,
     start: Option<NonNull<Node<T>>>,
     end: Option<NonNull<Node<T>>>,
+    // 告诉编译器链表拥有这些节点，drop 检查时按 Box<Node<T>> 处理
+    marker: PhantomData<Box<Node<T>>>,
 }
 
 impl<T> Default for LinkedList<T> {
@@ -41,9 +46,18 @@ impl<T> LinkedList<T> {
             length: 0,
             start: None,
             end: None,
+            marker: PhantomData,
         }
     }
 
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
     pub fn add(&mut self, obj: T) {
         // 新建一个节点并初始化，节点的 next 指针默认为 None
         let mut node = Box::new(Node::new(obj));
@@ -61,56 +75,375 @@ impl<T> LinkedList<T> {
         self.length += 1;
     }
 
-    pub fn get(&mut self, index: i32) -> Option<&T> {
-        self.get_ith_node(self.start, index)
+    pub fn push_front(&mut self, obj: T) {
+        // 新节点指向原来的头节点
+        let mut node = Box::new(Node::new(obj));
+        node.next = self.start;
+        let node_ptr = Some(unsafe { NonNull::new_unchecked(Box::into_raw(node)) });
+        // 原来为空链表时新节点同时也是尾节点
+        if self.end.is_none() {
+            self.end = node_ptr;
+        }
+        self.start = node_ptr;
+        self.length += 1;
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        self.start.map(|start_ptr| {
+            // 取回头节点的所有权，离开作用域时释放节点内存
+            let node = unsafe { Box::from_raw(start_ptr.as_ptr()) };
+            self.start = node.next;
+            if self.start.is_none() {
+                self.end = None;
+            }
+            self.length -= 1;
+            node.val
+        })
     }
 
-    fn get_ith_node(&mut self, node: Option<NonNull<Node<T>>>, index: i32) -> Option<&T> {
-        // 如果当前节点为空，则说明索引超出范围，返回 None
-        match node {
-            None => None,
-            Some(current_ptr) => match index {
-                // 当 index 为 0 时，返回当前节点的值
-                0 => Some(unsafe { &(*current_ptr.as_ptr()).val }),
-                // 否则递归查找下一个节点，索引递减
-                _ => self.get_ith_node(unsafe { (*current_ptr.as_ptr()).next }, index - 1),
-            },
+    // 在 index 处插入元素，index 等于长度时追加到末尾，超过长度时 panic
+    pub fn insert(&mut self, index: usize, obj: T) {
+        assert!(
+            index <= self.length,
+            "insertion index (is {}) should be <= len (is {})",
+            index,
+            self.length
+        );
+        if index == 0 {
+            return self.push_front(obj);
+        }
+        if index == self.length {
+            return self.add(obj);
+        }
+        // 找到插入位置的前一个节点，把新节点接在它后面
+        let prev_ptr = self.node_at(index - 1).unwrap();
+        let mut node = Box::new(Node::new(obj));
+        unsafe {
+            node.next = (*prev_ptr.as_ptr()).next;
+            (*prev_ptr.as_ptr()).next = Some(NonNull::new_unchecked(Box::into_raw(node)));
+        }
+        self.length += 1;
+    }
+
+    // 删除并返回 index 处的元素，越界时返回 None
+    pub fn remove(&mut self, index: usize) -> Option<T> {
+        if index >= self.length {
+            return None;
+        }
+        if index == 0 {
+            return self.pop_front();
+        }
+        let prev_ptr = self.node_at(index - 1)?;
+        unsafe {
+            // 让前一个节点跳过被删除的节点
+            let node = Box::from_raw((*prev_ptr.as_ptr()).next?.as_ptr());
+            (*prev_ptr.as_ptr()).next = node.next;
+            // 删除的是尾节点时，前一个节点成为新的尾节点
+            if node.next.is_none() {
+                self.end = Some(prev_ptr);
+            }
+            self.length -= 1;
+            Some(node.val)
+        }
+    }
+
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.node_at(index)
+            .map(|node_ptr| unsafe { &(*node_ptr.as_ptr()).val })
+    }
+
+    // 从头节点开始向后走 index 步，越界时返回 None
+    fn node_at(&self, index: usize) -> Option<NonNull<Node<T>>> {
+        if index >= self.length {
+            return None;
+        }
+        let mut current = self.start;
+        for _ in 0..index {
+            current = unsafe { (*current?.as_ptr()).next };
+        }
+        current
+    }
+
+    pub fn contains(&self, obj: &T) -> bool
+    where
+        T: PartialEq,
+    {
+        self.iter().any(|val| val == obj)
+    }
+
+    // 释放所有节点
+    pub fn clear(&mut self) {
+        while self.pop_front().is_some() {}
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            next: self.start,
+            remaining: self.length,
+            marker: PhantomData,
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut {
+            next: self.start,
+            remaining: self.length,
+            marker: PhantomData,
         }
     }
 
     pub fn merge(list_a: LinkedList<T>, list_b: LinkedList<T>) -> Self
     where
-        T: Clone + PartialOrd,
+        T: PartialOrd,
     {
-        // 创建存放合并结果的新链表
-        let mut new_list = LinkedList::<T>::new();
-        let mut a_ptr = list_a.start;
-        let mut b_ptr = list_b.start;
+        Self::merge_k(vec![list_a, list_b])
+    }
 
-        unsafe {
-            // 逐个比较两个链表中的节点，将较小的元素加入 new_list 中
-            while let (Some(a), Some(b)) = (a_ptr, b_ptr) {
-                if (*a.as_ptr()).val <= (*b.as_ptr()).val {
-                    new_list.add((*a.as_ptr()).val.clone());
-                    a_ptr = (*a.as_ptr()).next;
-                } else {
-                    new_list.add((*b.as_ptr()).val.clone());
-                    b_ptr = (*b.as_ptr()).next;
-                }
+    // 合并 k 条有序链表：每轮把相邻的两条两两合并，共 log k 轮、每轮 O(n)
+    // 相等的元素保持它们所在链表的先后顺序
+    pub fn merge_k(lists: Vec<LinkedList<T>>) -> Self
+    where
+        T: PartialOrd,
+    {
+        let mut length = 0;
+        let mut runs: Vec<Option<NonNull<Node<T>>>> = lists
+            .into_iter()
+            .map(|list| {
+                length += list.length;
+                list.into_nodes()
+            })
+            .collect();
+        while runs.len() > 1 {
+            let mut merged = Vec::with_capacity(runs.len().div_ceil(2));
+            let mut pairs = runs.into_iter();
+            while let Some(a) = pairs.next() {
+                merged.push(match pairs.next() {
+                    Some(b) => Self::merge_runs(a, b, &mut |x: &T, y: &T| x < y),
+                    None => a,
+                });
             }
-            // 如果 list_a 中还有剩余节点，则将它们全部添加到 new_list 中
-            while let Some(a) = a_ptr {
-                new_list.add((*a.as_ptr()).val.clone());
-                a_ptr = (*a.as_ptr()).next;
+            runs = merged;
+        }
+
+        let mut new_list = LinkedList::new();
+        new_list.relink(runs.pop().flatten(), length);
+        new_list
+    }
+
+    pub fn sort(&mut self)
+    where
+        T: Ord,
+    {
+        self.sort_by(T::cmp);
+    }
+
+    // 自底向上的稳定归并排序，只修改节点的 next 指针，不移动或复制元素
+    pub fn sort_by<F>(&mut self, mut compare: F)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        let length = self.length;
+        // 先让链表放弃节点，比较函数 panic 时最多泄漏节点而不会留下悬垂指针
+        let mut current = std::mem::take(self).into_nodes();
+        let mut is_less = |a: &T, b: &T| compare(a, b) == Ordering::Less;
+
+        // bins[i] 为空或是一段长度为 2^i 的有序链，编号越大的链中的元素在原链表中越靠前
+        let mut bins: Vec<Option<NonNull<Node<T>>>> = Vec::new();
+        while let Some(node_ptr) = current {
+            unsafe {
+                current = (*node_ptr.as_ptr()).next.take();
             }
-            // 如果 list_b 中还有剩余节点，则将它们全部添加到 new_list 中
-            while let Some(b) = b_ptr {
-                new_list.add((*b.as_ptr()).val.clone());
-                b_ptr = (*b.as_ptr()).next;
+            let mut carry = Some(node_ptr);
+            let mut i = 0;
+            while i < bins.len() && bins[i].is_some() {
+                carry = Self::merge_runs(bins[i].take(), carry, &mut is_less);
+                i += 1;
+            }
+            if i == bins.len() {
+                bins.push(carry);
+            } else {
+                bins[i] = carry;
             }
         }
-        // 返回合并后的链表
-        new_list
+
+        let mut sorted = None;
+        for bin in bins {
+            sorted = Self::merge_runs(bin, sorted, &mut is_less);
+        }
+        self.relink(sorted, length);
+    }
+
+    // 合并两段有序链，a 中的元素在原顺序中位于 b 之前；只有 b 的元素严格更小时才先取 b，保证稳定
+    fn merge_runs<F>(
+        mut a: Option<NonNull<Node<T>>>,
+        mut b: Option<NonNull<Node<T>>>,
+        is_less: &mut F,
+    ) -> Option<NonNull<Node<T>>>
+    where
+        F: FnMut(&T, &T) -> bool,
+    {
+        let mut head = None;
+        let mut tail: Option<NonNull<Node<T>>> = None;
+        unsafe {
+            loop {
+                let next = match (a, b) {
+                    (Some(a_ptr), Some(b_ptr)) => {
+                        if is_less(&(*b_ptr.as_ptr()).val, &(*a_ptr.as_ptr()).val) {
+                            b = (*b_ptr.as_ptr()).next;
+                            b_ptr
+                        } else {
+                            a = (*a_ptr.as_ptr()).next;
+                            a_ptr
+                        }
+                    }
+                    // 一边取完后，另一边剩余的部分整体接到末尾
+                    (rest, None) | (None, rest) => {
+                        match tail {
+                            None => head = rest,
+                            Some(tail_ptr) => (*tail_ptr.as_ptr()).next = rest,
+                        }
+                        return head;
+                    }
+                };
+                match tail {
+                    None => head = Some(next),
+                    Some(tail_ptr) => (*tail_ptr.as_ptr()).next = Some(next),
+                }
+                tail = Some(next);
+            }
+        }
+    }
+
+    // 交出全部节点的所有权，链表变为空，之后 drop 不会再释放这些节点
+    fn into_nodes(mut self) -> Option<NonNull<Node<T>>> {
+        self.end = None;
+        self.length = 0;
+        self.start.take()
+    }
+
+    // 以 head 开始、共 length 个节点的链作为链表内容，并重新找到尾节点
+    fn relink(&mut self, head: Option<NonNull<Node<T>>>, length: usize) {
+        self.start = head;
+        self.end = head;
+        self.length = length;
+        while let Some(node_ptr) = self
+            .end
+            .and_then(|end_ptr| unsafe { (*end_ptr.as_ptr()).next })
+        {
+            self.end = Some(node_ptr);
+        }
+    }
+}
+
+impl<T> Drop for LinkedList<T> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+// 按从头到尾的顺序借用元素
+struct Iter<'a, T> {
+    next: Option<NonNull<Node<T>>>,
+    remaining: usize,
+    marker: PhantomData<&'a Node<T>>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        self.next.map(|node_ptr| {
+            let node = unsafe { &*node_ptr.as_ptr() };
+            self.next = node.next;
+            self.remaining -= 1;
+            &node.val
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+// 按从头到尾的顺序可变借用元素，每个节点只会被访问一次
+struct IterMut<'a, T> {
+    next: Option<NonNull<Node<T>>>,
+    remaining: usize,
+    marker: PhantomData<&'a mut Node<T>>,
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<&'a mut T> {
+        self.next.map(|node_ptr| {
+            let node = unsafe { &mut *node_ptr.as_ptr() };
+            self.next = node.next;
+            self.remaining -= 1;
+            &mut node.val
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+// 按值遍历，未取出的元素随迭代器一起释放
+struct IntoIter<T>(LinkedList<T>);
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.0.pop_front()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.0.length, Some(self.0.length))
+    }
+}
+
+impl<T> IntoIterator for LinkedList<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter(self)
+    }
+}
+
+impl<'a, T> IntoIterator for &'a LinkedList<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut LinkedList<T> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> IterMut<'a, T> {
+        self.iter_mut()
+    }
+}
+
+impl<T> FromIterator<T> for LinkedList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = LinkedList::new();
+        list.extend(iter);
+        list
+    }
+}
+
+impl<T> Extend<T> for LinkedList<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for obj in iter {
+            self.add(obj);
+        }
     }
 }
 
@@ -138,9 +471,50 @@ where
     }
 }
 
+// 测试用的伪随机数生成器与其他习题共用，位于同目录下的 test_rng.rs
+#[cfg(test)]
+#[path = "test_rng.rs"]
+mod test_rng;
+
 #[cfg(test)]
 mod tests {
+    use super::test_rng::pseudo_random;
     use super::LinkedList;
+    use std::alloc::{GlobalAlloc, Layout, System};
+    use std::cell::Cell;
+
+    // 统计当前线程尚未释放的堆分配次数，用于检查链表是否泄漏
+    struct CountingAllocator;
+
+    thread_local! {
+        static LIVE_ALLOCATIONS: Cell<isize> = const { Cell::new(0) };
+    }
+
+    unsafe impl GlobalAlloc for CountingAllocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            let ptr = unsafe { System.alloc(layout) };
+            if !ptr.is_null() {
+                let _ = LIVE_ALLOCATIONS.try_with(|live| live.set(live.get() + 1));
+            }
+            ptr
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            unsafe { System.dealloc(ptr, layout) };
+            let _ = LIVE_ALLOCATIONS.try_with(|live| live.set(live.get() - 1));
+        }
+    }
+
+    #[global_allocator]
+    static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+    fn live_allocations() -> isize {
+        LIVE_ALLOCATIONS.with(Cell::get)
+    }
+
+    fn to_vec<T: Clone>(list: &LinkedList<T>) -> Vec<T> {
+        list.iter().cloned().collect()
+    }
 
     #[test]
     fn create_numeric_list() {
@@ -177,10 +551,10 @@ mod tests {
             list_b.add(val);
         }
         println!("list a {} list b {}", list_a, list_b);
-        let mut list_c = LinkedList::<i32>::merge(list_a, list_b);
+        let list_c = LinkedList::<i32>::merge(list_a, list_b);
         println!("merged List is {}", list_c);
         for (idx, &val) in target_vec.iter().enumerate() {
-            assert_eq!(val, *list_c.get(idx as i32).unwrap());
+            assert_eq!(val, *list_c.get(idx).unwrap());
         }
     }
 
@@ -199,10 +573,249 @@ mod tests {
             list_b.add(val);
         }
         println!("list a {} list b {}", list_a, list_b);
-        let mut list_c = LinkedList::<i32>::merge(list_a, list_b);
+        let list_c = LinkedList::<i32>::merge(list_a, list_b);
         println!("merged List is {}", list_c);
         for (idx, &val) in target_vec.iter().enumerate() {
-            assert_eq!(val, *list_c.get(idx as i32).unwrap());
+            assert_eq!(val, *list_c.get(idx).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_push_and_pop_front() {
+        let mut list = LinkedList::new();
+        assert!(list.is_empty());
+        assert_eq!(list.pop_front(), None);
+        list.push_front(2);
+        list.push_front(1);
+        list.add(3);
+        assert_eq!(to_vec(&list), vec![1, 2, 3]);
+        assert_eq!(list.pop_front(), Some(1));
+        assert_eq!(list.pop_front(), Some(2));
+        assert_eq!(list.pop_front(), Some(3));
+        assert_eq!(list.pop_front(), None);
+        assert!(list.is_empty());
+        // 清空后尾指针也要复位
+        list.add(4);
+        list.push_front(5);
+        assert_eq!(to_vec(&list), vec![5, 4]);
+    }
+
+    #[test]
+    fn test_insert_and_remove() {
+        let mut list: LinkedList<i32> = (1..=3).collect();
+        list.insert(0, 0);
+        list.insert(2, 10);
+        list.insert(5, 4);
+        assert_eq!(to_vec(&list), vec![0, 1, 10, 2, 3, 4]);
+        assert_eq!(list.len(), 6);
+
+        assert_eq!(list.remove(6), None);
+        assert_eq!(list.remove(2), Some(10));
+        assert_eq!(list.remove(0), Some(0));
+        // 删除尾节点后仍能正确追加
+        assert_eq!(list.remove(3), Some(4));
+        list.add(5);
+        assert_eq!(to_vec(&list), vec![1, 2, 3, 5]);
+        assert_eq!(list.get(3), Some(&5));
+        assert_eq!(list.get(4), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "insertion index (is 2) should be <= len (is 1)")]
+    fn test_insert_out_of_bounds() {
+        let mut list = LinkedList::new();
+        list.add(1);
+        list.insert(2, 2);
+    }
+
+    #[test]
+    fn test_iterators() {
+        let mut list: LinkedList<i32> = vec![1, 2, 3].into_iter().collect();
+        assert_eq!(list.iter().size_hint(), (3, Some(3)));
+        for val in list.iter_mut() {
+            *val *= 10;
+        }
+        for val in &mut list {
+            *val += 1;
+        }
+        let borrowed: Vec<&i32> = (&list).into_iter().collect();
+        assert_eq!(borrowed, vec![&11, &21, &31]);
+        list.extend(vec![41, 51]);
+        let owned: Vec<i32> = list.into_iter().collect();
+        assert_eq!(owned, vec![11, 21, 31, 41, 51]);
+    }
+
+    #[test]
+    fn test_len_clear_and_contains() {
+        let mut list: LinkedList<String> = ["a", "b", "c"].iter().map(|s| s.to_string()).collect();
+        assert_eq!(list.len(), 3);
+        assert!(list.contains(&"b".to_string()));
+        assert!(!list.contains(&"d".to_string()));
+        list.clear();
+        assert_eq!(list.len(), 0);
+        assert!(list.is_empty());
+        assert!(!list.contains(&"b".to_string()));
+        assert_eq!(list.to_string(), "");
+    }
+
+    #[test]
+    fn test_merge_moves_values_without_clone() {
+        // Box<i32> 不实现 Copy，合并时只能移动
+        let list_a: LinkedList<Box<i32>> = vec![Box::new(1), Box::new(4)].into_iter().collect();
+        let list_b: LinkedList<Box<i32>> = vec![Box::new(2), Box::new(3), Box::new(5)]
+            .into_iter()
+            .collect();
+        let merged: Vec<i32> = LinkedList::merge(list_a, list_b)
+            .into_iter()
+            .map(|val| *val)
+            .collect();
+        assert_eq!(merged, vec![1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn test_no_leaks() {
+        let before = live_allocations();
+        {
+            let mut list: LinkedList<String> = (0..100).map(|i| i.to_string()).collect();
+            list.push_front("front".to_string());
+            list.insert(50, "middle".to_string());
+            drop(list.remove(10));
+            drop(list.pop_front());
+            list.clear();
+            list.extend((0..10).map(|i| i.to_string()));
+
+            // 只消费一部分的 IntoIter 也要释放剩余节点
+            let mut partial = list.into_iter();
+            drop(partial.next());
+
+            let list_a: LinkedList<String> = ["a", "c"].iter().map(|s| s.to_string()).collect();
+            let list_b: LinkedList<String> = ["b"].iter().map(|s| s.to_string()).collect();
+            let merged = LinkedList::merge(list_a, list_b);
+            assert_eq!(merged.len(), 3);
+        }
+        assert_eq!(live_allocations(), before);
+    }
+
+    #[test]
+    fn test_sort_matches_vec_sort() {
+        let mut seed = 42;
+        for len in [0, 1, 2, 3, 7, 8, 9, 100, 1000] {
+            let values: Vec<u64> = (0..len).map(|_| pseudo_random(&mut seed) % 50).collect();
+            let mut list: LinkedList<u64> = values.iter().copied().collect();
+            list.sort();
+            let mut expected = values;
+            expected.sort();
+            assert_eq!(to_vec(&list), expected);
+            assert_eq!(list.len(), len);
+            // 排序后尾指针正确，仍可在末尾追加
+            list.add(u64::MAX);
+            assert_eq!(list.iter().last(), Some(&u64::MAX));
+        }
+    }
+
+    #[test]
+    fn test_sort_by_is_stable() {
+        let mut seed = 7;
+        let records: Vec<(u64, usize)> = (0..500)
+            .map(|i| (pseudo_random(&mut seed) % 10, i))
+            .collect();
+        let mut list: LinkedList<(u64, usize)> = records.iter().copied().collect();
+        list.sort_by(|a, b| b.0.cmp(&a.0));
+        let mut expected = records;
+        expected.sort_by_key(|a| std::cmp::Reverse(a.0));
+        assert_eq!(to_vec(&list), expected);
+    }
+
+    #[test]
+    fn test_sort_relinks_nodes_without_moving_values() {
+        // 不实现 Clone 的元素也能排序，并且每个元素的地址保持不变
+        #[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+        struct Record(u32);
+
+        let mut list: LinkedList<Record> = [3, 1, 2].iter().map(|&n| Record(n)).collect();
+        let addresses: Vec<(u32, *const Record)> =
+            list.iter().map(|r| (r.0, r as *const Record)).collect();
+        list.sort();
+        let mut sorted: Vec<(u32, *const Record)> =
+            list.iter().map(|r| (r.0, r as *const Record)).collect();
+        assert_eq!(
+            sorted.iter().map(|&(n, _)| n).collect::<Vec<_>>(),
+            vec![1, 2, 3]
+        );
+        sorted.sort_by_key(|&(_, address)| address);
+        let mut original = addresses;
+        original.sort_by_key(|&(_, address)| address);
+        assert_eq!(sorted, original);
+    }
+
+    #[test]
+    fn test_merge_k() {
+        let mut seed = 3;
+        let mut expected = Vec::new();
+        let lists: Vec<LinkedList<(u64, usize)>> = (0..9)
+            .map(|list_index| {
+                let mut values: Vec<(u64, usize)> = (0..list_index * 3)
+                    .map(|_| (pseudo_random(&mut seed) % 20, list_index))
+                    .collect();
+                values.sort();
+                expected.extend(values.iter().copied());
+                values.into_iter().collect()
+            })
+            .collect();
+        // 只比较第一个分量，第二个分量记录元素来自哪条链表
+        let mut merged = LinkedList::merge_k(lists);
+        expected.sort_by_key(|&(value, _)| value);
+        let keys: Vec<u64> = merged.iter().map(|&(value, _)| value).collect();
+        assert_eq!(
+            keys,
+            expected.iter().map(|&(value, _)| value).collect::<Vec<_>>()
+        );
+        assert_eq!(merged.len(), expected.len());
+        merged.add((100, 0));
+        assert_eq!(merged.iter().last(), Some(&(100, 0)));
+
+        assert!(LinkedList::<i32>::merge_k(Vec::new()).is_empty());
+    }
+
+    #[test]
+    fn test_merge_k_is_stable_across_lists() {
+        // 值相同的元素按所在链表的顺序排列
+        #[derive(Debug, PartialEq)]
+        struct Keyed(u32, &'static str);
+
+        impl PartialOrd for Keyed {
+            fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+                self.0.partial_cmp(&other.0)
+            }
+        }
+
+        let lists = vec![
+            vec![Keyed(1, "a"), Keyed(2, "a")].into_iter().collect(),
+            LinkedList::new(),
+            vec![Keyed(1, "c"), Keyed(2, "c")].into_iter().collect(),
+            vec![Keyed(2, "d")].into_iter().collect(),
+        ];
+        let merged: Vec<&str> = LinkedList::merge_k(lists)
+            .into_iter()
+            .map(|keyed| keyed.1)
+            .collect();
+        assert_eq!(merged, vec!["a", "c", "a", "c", "d"]);
+    }
+
+    #[test]
+    fn test_sort_and_merge_k_do_not_leak() {
+        let before = live_allocations();
+        {
+            let mut list: LinkedList<String> =
+                (0..200).map(|i| ((i * 37) % 200).to_string()).collect();
+            list.sort();
+            list.sort_by(|a, b| b.len().cmp(&a.len()));
+            let lists: Vec<LinkedList<String>> = (0..5)
+                .map(|i| (0..i).map(|j| j.to_string()).collect())
+                .collect();
+            let merged = LinkedList::merge_k(lists);
+            assert_eq!(merged.len(), 10);
         }
+        assert_eq!(live_allocations(), before);
     }
 }
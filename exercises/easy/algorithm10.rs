@@ -7,7 +7,8 @@
     这个问题要求你实现一个基本的图功能
 */
 
-use std::collections::{HashMap, HashSet};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
 use std::fmt;
 
 #[derive(Debug, Clone)]
@@ -19,6 +20,33 @@ impl fmt::Display for NodeNotInGraph {
     }
 }
 
+// Dijkstra 与 shortest_path 的错误
+#[derive(Debug, Clone, PartialEq)]
+pub enum DijkstraError {
+    NodeNotInGraph,
+    // 从起点可达的负权边 (起点, 终点, 权重)
+    NegativeWeight(String, String, i32),
+}
+
+impl From<NodeNotInGraph> for DijkstraError {
+    fn from(_: NodeNotInGraph) -> Self {
+        DijkstraError::NodeNotInGraph
+    }
+}
+
+impl fmt::Display for DijkstraError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DijkstraError::NodeNotInGraph => write!(f, "{}", NodeNotInGraph),
+            DijkstraError::NegativeWeight(from, to, weight) => write!(
+                f,
+                "Dijkstra requires non-negative edge weights, found {} -> {} with weight {}",
+                from, to, weight
+            ),
+        }
+    }
+}
+
 pub struct UndirectedGraph {
     adjacency_table: HashMap<String, Vec<(String, i32)>>,
 }
@@ -64,6 +92,67 @@ impl Graph for UndirectedGraph {
     }
 }
 
+// 有向图：只在起点的邻接向量中记录边，用于含负权边的最短路
+pub struct DirectedGraph {
+    adjacency_table: HashMap<String, Vec<(String, i32)>>,
+}
+
+impl Graph for DirectedGraph {
+    fn new() -> DirectedGraph {
+        DirectedGraph {
+            adjacency_table: HashMap::new(),
+        }
+    }
+
+    fn adjacency_table_mutable(&mut self) -> &mut HashMap<String, Vec<(String, i32)>> {
+        &mut self.adjacency_table
+    }
+
+    fn adjacency_table(&self) -> &HashMap<String, Vec<(String, i32)>> {
+        &self.adjacency_table
+    }
+
+    fn add_edge(&mut self, edge: (&str, &str, i32)) {
+        self.add_node(edge.0);
+        self.add_node(edge.1);
+        self.adjacency_table_mutable()
+            .get_mut(edge.0)
+            .unwrap()
+            .push((edge.1.to_string(), edge.2));
+    }
+}
+
+// Bellman-Ford 的结果：从起点可达的各节点的最短距离，或者从起点可达的一个负权环
+#[derive(Debug, PartialEq)]
+pub enum BellmanFord {
+    Distances(HashMap<String, i64>),
+    // 环上的节点按边的方向排列，首尾相连，从名称最小的节点开始
+    NegativeCycle(Vec<String>),
+}
+
+// 节点按名称排序后编号，使结果与 HashMap 的遍历顺序无关
+fn indexed_nodes(
+    table: &HashMap<String, Vec<(String, i32)>>,
+) -> (Vec<&String>, HashMap<&str, usize>) {
+    let mut nodes: Vec<&String> = table.keys().collect();
+    nodes.sort();
+    let index = nodes
+        .iter()
+        .enumerate()
+        .map(|(i, &node)| (node.as_str(), i))
+        .collect();
+    (nodes, index)
+}
+
+// 带路径压缩的并查集查找
+fn find_root(parent: &mut [usize], mut node: usize) -> usize {
+    while parent[node] != node {
+        parent[node] = parent[parent[node]];
+        node = parent[node];
+    }
+    node
+}
+
 pub trait Graph {
     fn new() -> Self;
 
@@ -120,8 +209,233 @@ pub trait Graph {
         }
         edges
     }
+    // 检查节点是否存在，不存在时返回 NodeNotInGraph
+    fn check_node(&self, node: &str) -> Result<(), NodeNotInGraph> {
+        if self.contains(node) {
+            Ok(())
+        } else {
+            Err(NodeNotInGraph)
+        }
+    }
+
+    // Dijkstra 算法，返回从 start 可达的每个节点的 (最短距离, 前驱节点)
+    // 从 start 可达的边权必须非负，否则返回 NegativeWeight；不可达部分的负权边不影响结果
+    fn dijkstra(
+        &self,
+        start: &str,
+    ) -> Result<HashMap<String, (i64, Option<String>)>, DijkstraError> {
+        self.check_node(start)?;
+        let table = self.adjacency_table();
+        let mut settled: HashMap<String, (i64, Option<String>)> = HashMap::new();
+        let mut best: HashMap<&str, i64> = HashMap::from([(start, 0)]);
+        // 堆中为 (距离, 节点, 前驱)，同距离时按名称出堆，保证结果确定
+        let mut heap = BinaryHeap::from([Reverse((0, start, None::<&str>))]);
+        while let Some(Reverse((distance, node, previous))) = heap.pop() {
+            if settled.contains_key(node) {
+                continue;
+            }
+            settled.insert(node.to_string(), (distance, previous.map(str::to_string)));
+            for (neighbour, weight) in &table[node] {
+                if *weight < 0 {
+                    return Err(DijkstraError::NegativeWeight(
+                        node.to_string(),
+                        neighbour.clone(),
+                        *weight,
+                    ));
+                }
+                let candidate = distance + *weight as i64;
+                if best
+                    .get(neighbour.as_str())
+                    .is_none_or(|&known| candidate < known)
+                {
+                    best.insert(neighbour, candidate);
+                    heap.push(Reverse((candidate, neighbour, Some(node))));
+                }
+            }
+        }
+        Ok(settled)
+    }
+
+    // 用 Dijkstra 求 from 到 to 的最短路径，返回 (总权重, 经过的节点)；不可达时为 None
+    fn shortest_path(
+        &self,
+        from: &str,
+        to: &str,
+    ) -> Result<Option<(i64, Vec<String>)>, DijkstraError> {
+        self.check_node(to)?;
+        let tree = self.dijkstra(from)?;
+        let Some(&(distance, _)) = tree.get(to) else {
+            return Ok(None);
+        };
+        let mut path = vec![to.to_string()];
+        while let Some((_, Some(previous))) = tree.get(path.last().unwrap()) {
+            path.push(previous.clone());
+        }
+        path.reverse();
+        Ok(Some((distance, path)))
+    }
+
+    // Bellman-Ford 算法，允许负权边；无向图中的负权边本身就构成负权环
+    fn bellman_ford(&self, start: &str) -> Result<BellmanFord, NodeNotInGraph> {
+        self.check_node(start)?;
+        let table = self.adjacency_table();
+        let (nodes, index) = indexed_nodes(table);
+        let edges: Vec<(usize, usize, i64)> = self
+            .edges()
+            .into_iter()
+            .map(|(from, to, weight)| (index[from.as_str()], index[to.as_str()], weight as i64))
+            .collect();
+        let mut distance: Vec<Option<i64>> = vec![None; nodes.len()];
+        let mut previous: Vec<usize> = (0..nodes.len()).collect();
+        distance[index[start]] = Some(0);
+
+        // 没有负权环时 n - 1 轮松弛就能收敛，某一轮没有更新时提前结束；
+        // 第 n 轮仍有更新说明存在可达的负权环，从最后更新的节点沿前驱回退 n 步后一定落在环上
+        let mut last_relaxed = None;
+        for _ in 0..nodes.len() {
+            last_relaxed = None;
+            for &(from, to, weight) in &edges {
+                let Some(from_distance) = distance[from] else {
+                    continue;
+                };
+                if distance[to].is_none_or(|known| from_distance + weight < known) {
+                    distance[to] = Some(from_distance + weight);
+                    previous[to] = from;
+                    last_relaxed = Some(to);
+                }
+            }
+            if last_relaxed.is_none() {
+                break;
+            }
+        }
+
+        if let Some(relaxed) = last_relaxed {
+            let mut on_cycle = relaxed;
+            for _ in 0..nodes.len() {
+                on_cycle = previous[on_cycle];
+            }
+            let mut cycle = vec![on_cycle];
+            let mut current = previous[on_cycle];
+            while current != on_cycle {
+                cycle.push(current);
+                current = previous[current];
+            }
+            cycle.reverse();
+            // 边的遍历顺序不固定，统一从编号（名称）最小的节点开始列出
+            let smallest = (0..cycle.len()).min_by_key(|&i| cycle[i]).unwrap();
+            cycle.rotate_left(smallest);
+            return Ok(BellmanFord::NegativeCycle(
+                cycle.into_iter().map(|i| nodes[i].clone()).collect(),
+            ));
+        }
+
+        Ok(BellmanFord::Distances(
+            distance
+                .into_iter()
+                .enumerate()
+                .filter_map(|(i, d)| d.map(|d| (nodes[i].clone(), d)))
+                .collect(),
+        ))
+    }
+
+    // Floyd-Warshall 算法，返回所有可达节点对 (起点, 终点) 的最短距离；存在负权环时为 None
+    fn floyd_warshall(&self) -> Option<HashMap<(String, String), i64>> {
+        let (nodes, index) = indexed_nodes(self.adjacency_table());
+        let n = nodes.len();
+        let mut distance: Vec<Vec<Option<i64>>> = vec![vec![None; n]; n];
+        for (i, row) in distance.iter_mut().enumerate() {
+            row[i] = Some(0);
+        }
+        for (from, to, weight) in self.edges() {
+            let cell = &mut distance[index[from.as_str()]][index[to.as_str()]];
+            *cell = Some(cell.map_or(weight as i64, |known| known.min(weight as i64)));
+        }
+
+        for k in 0..n {
+            // 第 k 行在这一轮中不会变小（除非有负权环，此时结果本就作废），可以先复制出来
+            let through_k = distance[k].clone();
+            for row in distance.iter_mut() {
+                let Some(to_k) = row[k] else {
+                    continue;
+                };
+                for (cell, from_k) in row.iter_mut().zip(&through_k) {
+                    if let Some(from_k) = from_k {
+                        if cell.is_none_or(|known| to_k + from_k < known) {
+                            *cell = Some(to_k + from_k);
+                        }
+                    }
+                }
+            }
+            // 出现负权环后距离每轮都可能翻倍地减小，继续下去会溢出 i64，因此立即返回
+            if (0..n).any(|i| distance[i][i] < Some(0)) {
+                return None;
+            }
+        }
+
+        let mut result = HashMap::new();
+        for (i, row) in distance.into_iter().enumerate() {
+            for (j, d) in row.into_iter().enumerate() {
+                if let Some(d) = d {
+                    result.insert((nodes[i].clone(), nodes[j].clone()), d);
+                }
+            }
+        }
+        Some(result)
+    }
+}
+
+// 最小生成树只对无向图有意义，因此不放在 Graph 中，DirectedGraph 不实现此 trait
+pub trait SpanningTree: Graph {
+    // Prim 算法，返回 start 所在连通分量的最小生成树的边 (树中已有的端点, 新加入的端点, 权重)
+    fn prim(&self, start: &str) -> Result<Vec<(String, String, i32)>, NodeNotInGraph> {
+        self.check_node(start)?;
+        let table = self.adjacency_table();
+        let mut in_tree: HashSet<&str> = HashSet::from([start]);
+        let mut tree = Vec::new();
+        let mut heap: BinaryHeap<Reverse<(i32, &str, &str)>> = table[start]
+            .iter()
+            .map(|(to, weight)| Reverse((*weight, to.as_str(), start)))
+            .collect();
+        while let Some(Reverse((weight, node, from))) = heap.pop() {
+            if !in_tree.insert(node) {
+                continue;
+            }
+            tree.push((from.to_string(), node.to_string(), weight));
+            for (to, weight) in &table[node] {
+                if !in_tree.contains(to.as_str()) {
+                    heap.push(Reverse((*weight, to, node)));
+                }
+            }
+        }
+        Ok(tree)
+    }
+
+    // Kruskal 算法，返回最小生成森林的边 (起点, 终点, 权重)，按权重升序
+    fn kruskal(&self) -> Vec<(String, String, i32)> {
+        let (nodes, index) = indexed_nodes(self.adjacency_table());
+        let mut edges = self.edges();
+        edges.sort_by(|a, b| (a.2, a.0, a.1).cmp(&(b.2, b.0, b.1)));
+        let mut parent: Vec<usize> = (0..nodes.len()).collect();
+        let mut forest = Vec::new();
+        for (from, to, weight) in edges {
+            let from_root = find_root(&mut parent, index[from.as_str()]);
+            let to_root = find_root(&mut parent, index[to.as_str()]);
+            if from_root != to_root {
+                parent[from_root] = to_root;
+                forest.push((from.clone(), to.clone(), weight));
+            }
+        }
+        forest
+    }
 }
 
+impl SpanningTree for UndirectedGraph {}
+
+// 测试用的伪随机数生成器与其他习题共用，位于同目录下的 test_rng.rs
+#[cfg(test)]
+#[path = "test_rng.rs"]
+mod test_rng;
+
 #[cfg(test)]
 mod test_undirected_graph {
     use super::Graph;
@@ -146,3 +460,299 @@ mod test_undirected_graph {
         }
     }
 }
+
+#[cfg(test)]
+mod test_graph_algorithms {
+    use super::test_rng::pseudo_random;
+    use super::*;
+
+    fn undirected(edges: &[(&str, &str, i32)]) -> UndirectedGraph {
+        let mut graph = UndirectedGraph::new();
+        for &edge in edges {
+            graph.add_edge(edge);
+        }
+        graph
+    }
+
+    fn directed(edges: &[(&str, &str, i32)]) -> DirectedGraph {
+        let mut graph = DirectedGraph::new();
+        for &edge in edges {
+            graph.add_edge(edge);
+        }
+        graph
+    }
+
+    fn total_weight(tree: &[(String, String, i32)]) -> i32 {
+        tree.iter().map(|&(_, _, weight)| weight).sum()
+    }
+
+    #[test]
+    fn test_dijkstra_and_shortest_path() {
+        let mut graph = undirected(&[
+            ("a", "b", 4),
+            ("a", "c", 1),
+            ("c", "b", 2),
+            ("b", "d", 1),
+            ("c", "d", 5),
+        ]);
+        graph.add_node("e");
+
+        let tree = graph.dijkstra("a").unwrap();
+        assert_eq!(tree["a"], (0, None));
+        assert_eq!(tree["b"], (3, Some("c".to_string())));
+        assert_eq!(tree["d"], (4, Some("b".to_string())));
+        assert!(!tree.contains_key("e"));
+
+        assert_eq!(
+            graph.shortest_path("a", "d").unwrap(),
+            Some((4, vec!["a".into(), "c".into(), "b".into(), "d".into()]))
+        );
+        assert_eq!(
+            graph.shortest_path("d", "d").unwrap(),
+            Some((0, vec!["d".into()]))
+        );
+        assert_eq!(graph.shortest_path("a", "e").unwrap(), None);
+    }
+
+    #[test]
+    fn test_missing_nodes_are_errors() {
+        let graph = undirected(&[("a", "b", 1)]);
+        assert!(graph.dijkstra("x").is_err());
+        assert!(graph.shortest_path("a", "x").is_err());
+        assert!(graph.shortest_path("x", "a").is_err());
+        assert!(graph.bellman_ford("x").is_err());
+        assert!(graph.prim("x").is_err());
+        assert_eq!(
+            graph.prim("x").unwrap_err().to_string(),
+            "accessing a node that is not in the graph"
+        );
+    }
+
+    #[test]
+    fn test_dijkstra_rejects_reachable_negative_weights() {
+        let graph = directed(&[("a", "b", 2), ("b", "c", -1), ("d", "a", -5)]);
+        assert_eq!(
+            graph.dijkstra("a").unwrap_err(),
+            DijkstraError::NegativeWeight("b".into(), "c".into(), -1)
+        );
+        assert_eq!(
+            graph.shortest_path("a", "b").unwrap_err().to_string(),
+            "Dijkstra requires non-negative edge weights, found b -> c with weight -1"
+        );
+        // 从 c 出发碰不到任何负权边
+        assert_eq!(graph.dijkstra("c").unwrap().len(), 1);
+        assert_eq!(
+            graph.shortest_path("x", "a").unwrap_err(),
+            DijkstraError::NodeNotInGraph
+        );
+    }
+
+    #[test]
+    fn test_bellman_ford() {
+        let graph = directed(&[
+            ("s", "a", 4),
+            ("s", "b", 5),
+            ("a", "c", -3),
+            ("b", "a", -2),
+            ("c", "d", 2),
+            ("x", "s", 1),
+        ]);
+        let expected: HashMap<String, i64> = [("s", 0), ("a", 3), ("b", 5), ("c", 0), ("d", 2)]
+            .iter()
+            .map(|&(node, distance)| (node.to_string(), distance))
+            .collect();
+        assert_eq!(
+            graph.bellman_ford("s").unwrap(),
+            BellmanFord::Distances(expected)
+        );
+
+        // 负权环只有从起点可达时才会被报告
+        let graph = directed(&[
+            ("s", "a", 1),
+            ("a", "b", 2),
+            ("b", "c", -4),
+            ("c", "a", 1),
+            ("x", "y", -5),
+            ("y", "x", 1),
+        ]);
+        assert_eq!(
+            graph.bellman_ford("s").unwrap(),
+            BellmanFord::NegativeCycle(vec!["a".into(), "b".into(), "c".into()])
+        );
+        assert!(matches!(
+            graph.bellman_ford("c").unwrap(),
+            BellmanFord::NegativeCycle(_)
+        ));
+        assert!(matches!(
+            graph.bellman_ford("y"),
+            Ok(BellmanFord::NegativeCycle(_))
+        ));
+
+        // 无向图中的负权边来回走就是负权环
+        let graph = undirected(&[("a", "b", 3), ("b", "c", -1)]);
+        assert_eq!(
+            graph.bellman_ford("a").unwrap(),
+            BellmanFord::NegativeCycle(vec!["b".into(), "c".into()])
+        );
+    }
+
+    #[test]
+    fn test_floyd_warshall() {
+        let graph = directed(&[("a", "b", 3), ("b", "c", -2), ("a", "c", 2), ("c", "d", 1)]);
+        let distances = graph.floyd_warshall().unwrap();
+        let get =
+            |from: &str, to: &str| distances.get(&(from.to_string(), to.to_string())).copied();
+        assert_eq!(get("a", "c"), Some(1));
+        assert_eq!(get("a", "d"), Some(2));
+        assert_eq!(get("d", "d"), Some(0));
+        assert_eq!(get("d", "a"), None);
+
+        assert_eq!(
+            directed(&[("a", "b", 1), ("b", "a", -2)]).floyd_warshall(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_floyd_warshall_stops_at_negative_cycle_before_overflow() {
+        let names: Vec<String> = (0..70).map(|i| format!("n{}", i)).collect();
+        let mut graph = DirectedGraph::new();
+        for from in &names {
+            for to in &names {
+                if from != to {
+                    graph.add_edge((from, to, -1_000_000));
+                }
+            }
+        }
+        assert_eq!(graph.floyd_warshall(), None);
+    }
+
+    #[test]
+    fn test_minimum_spanning_tree() {
+        let mut graph = undirected(&[
+            ("a", "b", 7),
+            ("a", "d", 5),
+            ("b", "c", 8),
+            ("b", "d", 9),
+            ("b", "e", 7),
+            ("c", "e", 5),
+            ("d", "e", 15),
+            ("d", "f", 6),
+            ("e", "f", 8),
+            ("e", "g", 9),
+            ("f", "g", 11),
+        ]);
+        let prim = graph.prim("a").unwrap();
+        assert_eq!(prim.len(), 6);
+        assert_eq!(total_weight(&prim), 39);
+        // Prim 的每条边都把一个新节点接到已有的树上
+        let mut reached = HashSet::from(["a".to_string()]);
+        for (from, to, _) in &prim {
+            assert!(reached.contains(from));
+            assert!(reached.insert(to.clone()));
+        }
+
+        let kruskal = graph.kruskal();
+        assert_eq!(total_weight(&kruskal), 39);
+        assert!(kruskal.windows(2).all(|pair| pair[0].2 <= pair[1].2));
+
+        // 不连通时 Kruskal 给出生成森林，Prim 只覆盖起点所在的分量
+        graph.add_edge(("x", "y", 2));
+        assert_eq!(graph.kruskal().len(), 7);
+        assert_eq!(total_weight(&graph.kruskal()), 41);
+        assert_eq!(graph.prim("y").unwrap(), vec![("y".into(), "x".into(), 2)]);
+    }
+
+    #[test]
+    fn test_algorithms_agree_on_random_graphs() {
+        let mut seed: u64 = 11;
+        let names: Vec<String> = (0..7).map(|i| format!("n{}", i)).collect();
+        for _ in 0..100 {
+            let edges: Vec<(usize, usize, i32)> = (0..pseudo_random(&mut seed) % 15)
+                .map(|_| {
+                    let from = (pseudo_random(&mut seed) % 7) as usize;
+                    let to = (pseudo_random(&mut seed) % 7) as usize;
+                    (from, to, (pseudo_random(&mut seed) % 20) as i32)
+                })
+                .collect();
+            let mut graph = UndirectedGraph::new();
+            let mut shifted = DirectedGraph::new();
+            for name in &names {
+                graph.add_node(name);
+                shifted.add_node(name);
+            }
+            for &(from, to, weight) in &edges {
+                graph.add_edge((&names[from], &names[to], weight));
+                // 有向图中引入负权边，但 from < to 时才为负，保证无环
+                let weight = if from < to { weight - 10 } else { weight };
+                shifted.add_edge((&names[from], &names[to], weight));
+            }
+
+            // 非负权时 Dijkstra、Bellman-Ford 与 Floyd-Warshall 的结果一致
+            let all_pairs = graph.floyd_warshall().unwrap();
+            for start in &names {
+                let tree = graph.dijkstra(start).unwrap();
+                let BellmanFord::Distances(distances) = graph.bellman_ford(start).unwrap() else {
+                    panic!("unexpected negative cycle");
+                };
+                for end in &names {
+                    let expected = all_pairs.get(&(start.clone(), end.clone())).copied();
+                    assert_eq!(tree.get(end).map(|&(distance, _)| distance), expected);
+                    assert_eq!(distances.get(end).copied(), expected);
+                }
+            }
+
+            // 有向图中 Dijkstra 只在碰到负权边时拒绝，否则与 Bellman-Ford 一致
+            for start in &names {
+                match (
+                    shifted.dijkstra(start),
+                    shifted.bellman_ford(start).unwrap(),
+                ) {
+                    (Ok(tree), BellmanFord::Distances(distances)) => {
+                        for end in &names {
+                            assert_eq!(
+                                tree.get(end).map(|&(distance, _)| distance),
+                                distances.get(end).copied()
+                            );
+                        }
+                    }
+                    (Err(DijkstraError::NegativeWeight(_, _, weight)), _) => assert!(weight < 0),
+                    (result, _) => panic!("unexpected Dijkstra result {:?}", result),
+                }
+            }
+
+            // 有向图可能含负权环，Bellman-Ford 与 Floyd-Warshall 需要对是否有环达成一致
+            match shifted.floyd_warshall() {
+                Some(all_pairs) => {
+                    for start in &names {
+                        let BellmanFord::Distances(distances) =
+                            shifted.bellman_ford(start).unwrap()
+                        else {
+                            panic!("unexpected negative cycle");
+                        };
+                        for end in &names {
+                            assert_eq!(
+                                distances.get(end).copied(),
+                                all_pairs.get(&(start.clone(), end.clone())).copied()
+                            );
+                        }
+                    }
+                }
+                None => assert!(names.iter().any(|start| matches!(
+                    shifted.bellman_ford(start),
+                    Ok(BellmanFord::NegativeCycle(_))
+                ))),
+            }
+
+            // 两种最小生成树算法的总权重相同
+            let forest = graph.kruskal();
+            let components = names.len() - forest.len();
+            if components == 1 {
+                assert_eq!(
+                    total_weight(&graph.prim("n0").unwrap()),
+                    total_weight(&forest)
+                );
+            }
+        }
+    }
+}
@@ -7,7 +7,11 @@
     这个问题要求你反转一个双向链表
 */
 
+use std::collections::HashMap;
 use std::fmt::{self, Display, Formatter};
+use std::hash::Hash;
+use std::iter::FromIterator;
+use std::marker::PhantomData;
 use std::ptr::NonNull;
 
 #[derive(Debug)]
@@ -29,9 +33,11 @@ impl<T> Node<T> {
 
 #[derive(Debug)]
 struct LinkedList<T> {
-    length: u32,
+    length: usize,
     start: Option<NonNull<Node<T>>>,
     end: Option<NonNull<Node<T>>>,
+    // 链表拥有这些节点，drop 检查时按 Box<Node<T>> 处理
+    marker: PhantomData<Box<Node<T>>>,
 }
 
 impl<T> Default for LinkedList<T> {
@@ -46,9 +52,18 @@ impl<T> LinkedList<T> {
             length: 0,
             start: None,
             end: None,
+            marker: PhantomData,
         }
     }
 
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
     pub fn add(&mut self, obj: T) {
         // 创建一个新节点，并存储传入的对象
         let mut node = Box::new(Node::new(obj));
@@ -68,6 +83,51 @@ impl<T> LinkedList<T> {
         self.length += 1;
     }
 
+    pub fn push_back(&mut self, obj: T) {
+        self.add(obj);
+    }
+
+    pub fn push_front(&mut self, obj: T) {
+        // 新节点的 next 指向原来的头节点
+        let mut node = Box::new(Node::new(obj));
+        node.next = self.start;
+        let node_ptr = Some(unsafe { NonNull::new_unchecked(Box::into_raw(node)) });
+        // 链表为空时新节点同时是末尾节点，否则原头节点的 prev 指向新节点
+        match self.start {
+            None => self.end = node_ptr,
+            Some(start_ptr) => unsafe { (*start_ptr.as_ptr()).prev = node_ptr },
+        }
+        self.start = node_ptr;
+        self.length += 1;
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        self.start.map(|start_ptr| unsafe {
+            // 取回头节点的所有权，离开作用域时释放节点内存
+            let node = Box::from_raw(start_ptr.as_ptr());
+            self.start = node.next;
+            match self.start {
+                None => self.end = None,
+                Some(next_ptr) => (*next_ptr.as_ptr()).prev = None,
+            }
+            self.length -= 1;
+            node.val
+        })
+    }
+
+    pub fn pop_back(&mut self) -> Option<T> {
+        self.end.map(|end_ptr| unsafe {
+            let node = Box::from_raw(end_ptr.as_ptr());
+            self.end = node.prev;
+            match self.end {
+                None => self.start = None,
+                Some(prev_ptr) => (*prev_ptr.as_ptr()).next = None,
+            }
+            self.length -= 1;
+            node.val
+        })
+    }
+
     pub fn get(&mut self, index: i32) -> Option<&T> {
         self.get_ith_node(self.start, index)
     }
@@ -82,6 +142,131 @@ impl<T> LinkedList<T> {
         }
     }
 
+    // 只需共享借用的按下标访问，从较近的一端开始走
+    pub fn nth(&self, index: usize) -> Option<&T> {
+        if index >= self.length {
+            None
+        } else if index <= self.length / 2 {
+            self.iter().nth(index)
+        } else {
+            self.iter().nth_back(self.length - 1 - index)
+        }
+    }
+
+    // 把节点从链表中摘下，节点内存仍然保留；调用者需保证 node_ptr 属于本链表
+    unsafe fn unlink(&mut self, node_ptr: NonNull<Node<T>>) {
+        let node = node_ptr.as_ptr();
+        match (*node).prev {
+            None => self.start = (*node).next,
+            Some(prev_ptr) => (*prev_ptr.as_ptr()).next = (*node).next,
+        }
+        match (*node).next {
+            None => self.end = (*node).prev,
+            Some(next_ptr) => (*next_ptr.as_ptr()).prev = (*node).prev,
+        }
+        (*node).prev = None;
+        (*node).next = None;
+        self.length -= 1;
+    }
+
+    // 删除节点并返回其中的元素，O(1)
+    unsafe fn remove_node(&mut self, node_ptr: NonNull<Node<T>>) -> T {
+        self.unlink(node_ptr);
+        Box::from_raw(node_ptr.as_ptr()).val
+    }
+
+    // 把节点移到链表头部，节点地址不变，O(1)
+    unsafe fn move_to_front(&mut self, node_ptr: NonNull<Node<T>>) {
+        self.unlink(node_ptr);
+        (*node_ptr.as_ptr()).next = self.start;
+        match self.start {
+            None => self.end = Some(node_ptr),
+            Some(start_ptr) => (*start_ptr.as_ptr()).prev = Some(node_ptr),
+        }
+        self.start = Some(node_ptr);
+        self.length += 1;
+    }
+
+    // 把 other 的全部节点接到末尾，只修改首尾指针，O(1)
+    pub fn append(&mut self, other: &mut LinkedList<T>) {
+        let Some(other_start) = other.start.take() else {
+            return;
+        };
+        match self.end {
+            None => self.start = Some(other_start),
+            Some(end_ptr) => unsafe {
+                (*end_ptr.as_ptr()).next = Some(other_start);
+                (*other_start.as_ptr()).prev = Some(end_ptr);
+            },
+        }
+        self.end = other.end.take();
+        self.length += other.length;
+        other.length = 0;
+    }
+
+    // 从 at 处断开，返回 [at, len) 部分；从较近的一端走到断点，断开本身是 O(1)
+    pub fn split_off(&mut self, at: usize) -> LinkedList<T> {
+        assert!(
+            at <= self.length,
+            "cannot split off at a nonexistent index (is {}, len {})",
+            at,
+            self.length
+        );
+        if at == self.length {
+            return LinkedList::new();
+        }
+        let mut cursor = if at <= self.length / 2 {
+            let mut cursor = self.cursor_front_mut();
+            for _ in 0..at {
+                cursor.move_next();
+            }
+            cursor
+        } else {
+            let mut cursor = self.cursor_back_mut();
+            for _ in at..cursor.list.length - 1 {
+                cursor.move_prev();
+            }
+            cursor
+        };
+        let front = cursor.split_before();
+        std::mem::replace(self, front)
+    }
+
+    // 指向头节点的游标，链表为空时指向“幽灵”位置
+    pub fn cursor_front_mut(&mut self) -> CursorMut<'_, T> {
+        CursorMut {
+            index: 0,
+            current: self.start,
+            list: self,
+        }
+    }
+
+    pub fn cursor_back_mut(&mut self) -> CursorMut<'_, T> {
+        CursorMut {
+            index: self.length.saturating_sub(1),
+            current: self.end,
+            list: self,
+        }
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            head: self.start,
+            tail: self.end,
+            remaining: self.length,
+            marker: PhantomData,
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut {
+            head: self.start,
+            tail: self.end,
+            remaining: self.length,
+            marker: PhantomData,
+        }
+    }
+
     pub fn reverse(&mut self) {
         let mut current = self.start;
         while let Some(mut node_ptr) = current {
@@ -98,6 +283,431 @@ impl<T> LinkedList<T> {
     }
 }
 
+impl<T> Drop for LinkedList<T> {
+    fn drop(&mut self) {
+        while self.pop_front().is_some() {}
+    }
+}
+
+// 可在链表中前后移动并在当前位置增删节点的游标
+// 除了各个元素，游标还可以停在链表首尾之间的“幽灵”位置（current 为 None），此时 index 等于链表长度
+struct CursorMut<'a, T> {
+    index: usize,
+    current: Option<NonNull<Node<T>>>,
+    list: &'a mut LinkedList<T>,
+}
+
+impl<T> CursorMut<'_, T> {
+    // 当前元素的下标，位于幽灵位置时为 None
+    pub fn index(&self) -> Option<usize> {
+        self.current.map(|_| self.index)
+    }
+
+    // 移动到下一个元素；从末尾元素移到幽灵位置，从幽灵位置移到头节点
+    pub fn move_next(&mut self) {
+        match self.current {
+            None => {
+                self.current = self.list.start;
+                self.index = 0;
+            }
+            Some(current_ptr) => {
+                self.current = unsafe { (*current_ptr.as_ptr()).next };
+                self.index += 1;
+            }
+        }
+    }
+
+    pub fn move_prev(&mut self) {
+        match self.current {
+            None => {
+                self.current = self.list.end;
+                self.index = self.list.length.saturating_sub(1);
+            }
+            Some(current_ptr) => {
+                self.current = unsafe { (*current_ptr.as_ptr()).prev };
+                self.index = self.index.checked_sub(1).unwrap_or(self.list.length);
+            }
+        }
+    }
+
+    pub fn current(&mut self) -> Option<&mut T> {
+        self.current
+            .map(|current_ptr| unsafe { &mut (*current_ptr.as_ptr()).val })
+    }
+
+    pub fn peek_next(&mut self) -> Option<&mut T> {
+        let next = match self.current {
+            None => self.list.start,
+            Some(current_ptr) => unsafe { (*current_ptr.as_ptr()).next },
+        };
+        next.map(|next_ptr| unsafe { &mut (*next_ptr.as_ptr()).val })
+    }
+
+    pub fn peek_prev(&mut self) -> Option<&mut T> {
+        let prev = match self.current {
+            None => self.list.end,
+            Some(current_ptr) => unsafe { (*current_ptr.as_ptr()).prev },
+        };
+        prev.map(|prev_ptr| unsafe { &mut (*prev_ptr.as_ptr()).val })
+    }
+
+    // 在当前元素之后插入；位于幽灵位置时插入到链表头部
+    pub fn insert_after(&mut self, obj: T) {
+        let next = match self.current {
+            None => self.list.start,
+            Some(current_ptr) => unsafe { (*current_ptr.as_ptr()).next },
+        };
+        self.link_between(self.current, next, obj);
+        if self.current.is_none() {
+            self.index = self.list.length;
+        }
+    }
+
+    // 在当前元素之前插入；位于幽灵位置时插入到链表尾部
+    pub fn insert_before(&mut self, obj: T) {
+        let prev = match self.current {
+            None => self.list.end,
+            Some(current_ptr) => unsafe { (*current_ptr.as_ptr()).prev },
+        };
+        self.link_between(prev, self.current, obj);
+        self.index += 1;
+    }
+
+    // 删除并返回当前元素，游标移到下一个元素
+    pub fn remove_current(&mut self) -> Option<T> {
+        let current_ptr = self.current?;
+        unsafe {
+            self.current = (*current_ptr.as_ptr()).next;
+            Some(self.list.remove_node(current_ptr))
+        }
+    }
+
+    // 断开并返回当前元素之后的部分，O(1)；位于幽灵位置时返回整个链表
+    pub fn split_after(&mut self) -> LinkedList<T> {
+        let (split_start, split_length) = match self.current {
+            None => (self.list.start, self.list.length),
+            Some(current_ptr) => unsafe {
+                (
+                    (*current_ptr.as_ptr()).next,
+                    self.list.length - self.index - 1,
+                )
+            },
+        };
+        let mut split = LinkedList::new();
+        let Some(split_start_ptr) = split_start else {
+            return split;
+        };
+        unsafe {
+            (*split_start_ptr.as_ptr()).prev = None;
+            match self.current {
+                None => self.list.start = None,
+                Some(current_ptr) => (*current_ptr.as_ptr()).next = None,
+            }
+        }
+        split.start = split_start;
+        split.end = std::mem::replace(&mut self.list.end, self.current);
+        split.length = split_length;
+        self.list.length -= split_length;
+        if self.current.is_none() {
+            self.index = 0;
+        }
+        split
+    }
+
+    // 断开并返回当前元素之前的部分，O(1)；位于幽灵位置时返回整个链表
+    pub fn split_before(&mut self) -> LinkedList<T> {
+        let (split_end, split_length) = match self.current {
+            None => (self.list.end, self.list.length),
+            Some(current_ptr) => unsafe { ((*current_ptr.as_ptr()).prev, self.index) },
+        };
+        let mut split = LinkedList::new();
+        let Some(split_end_ptr) = split_end else {
+            return split;
+        };
+        unsafe {
+            (*split_end_ptr.as_ptr()).next = None;
+            match self.current {
+                None => self.list.end = None,
+                Some(current_ptr) => (*current_ptr.as_ptr()).prev = None,
+            }
+        }
+        split.start = std::mem::replace(&mut self.list.start, self.current);
+        split.end = split_end;
+        split.length = split_length;
+        self.list.length -= split_length;
+        self.index = 0;
+        split
+    }
+
+    // 在 prev 与 next 两个相邻位置之间插入新节点，None 表示链表的头或尾
+    fn link_between(
+        &mut self,
+        prev: Option<NonNull<Node<T>>>,
+        next: Option<NonNull<Node<T>>>,
+        obj: T,
+    ) {
+        let mut node = Box::new(Node::new(obj));
+        node.prev = prev;
+        node.next = next;
+        let node_ptr = Some(unsafe { NonNull::new_unchecked(Box::into_raw(node)) });
+        unsafe {
+            match prev {
+                None => self.list.start = node_ptr,
+                Some(prev_ptr) => (*prev_ptr.as_ptr()).next = node_ptr,
+            }
+            match next {
+                None => self.list.end = node_ptr,
+                Some(next_ptr) => (*next_ptr.as_ptr()).prev = node_ptr,
+            }
+        }
+        self.list.length += 1;
+    }
+}
+
+// 双端迭代器，从两端取出的元素总数不超过 remaining，因此不会重复访问节点
+struct Iter<'a, T> {
+    head: Option<NonNull<Node<T>>>,
+    tail: Option<NonNull<Node<T>>>,
+    remaining: usize,
+    marker: PhantomData<&'a Node<T>>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.head.map(|head_ptr| unsafe {
+            self.remaining -= 1;
+            self.head = (*head_ptr.as_ptr()).next;
+            &(*head_ptr.as_ptr()).val
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
+    fn next_back(&mut self) -> Option<&'a T> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.tail.map(|tail_ptr| unsafe {
+            self.remaining -= 1;
+            self.tail = (*tail_ptr.as_ptr()).prev;
+            &(*tail_ptr.as_ptr()).val
+        })
+    }
+}
+
+struct IterMut<'a, T> {
+    head: Option<NonNull<Node<T>>>,
+    tail: Option<NonNull<Node<T>>>,
+    remaining: usize,
+    marker: PhantomData<&'a mut Node<T>>,
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<&'a mut T> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.head.map(|head_ptr| unsafe {
+            self.remaining -= 1;
+            self.head = (*head_ptr.as_ptr()).next;
+            &mut (*head_ptr.as_ptr()).val
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for IterMut<'a, T> {
+    fn next_back(&mut self) -> Option<&'a mut T> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.tail.map(|tail_ptr| unsafe {
+            self.remaining -= 1;
+            self.tail = (*tail_ptr.as_ptr()).prev;
+            &mut (*tail_ptr.as_ptr()).val
+        })
+    }
+}
+
+// 按值遍历，未取出的元素随迭代器一起释放
+struct IntoIter<T>(LinkedList<T>);
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.0.pop_front()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.0.length, Some(self.0.length))
+    }
+}
+
+impl<T> DoubleEndedIterator for IntoIter<T> {
+    fn next_back(&mut self) -> Option<T> {
+        self.0.pop_back()
+    }
+}
+
+impl<T> IntoIterator for LinkedList<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter(self)
+    }
+}
+
+impl<'a, T> IntoIterator for &'a LinkedList<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut LinkedList<T> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> IterMut<'a, T> {
+        self.iter_mut()
+    }
+}
+
+impl<T> FromIterator<T> for LinkedList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = LinkedList::new();
+        list.extend(iter);
+        list
+    }
+}
+
+impl<T> Extend<T> for LinkedList<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for obj in iter {
+            self.push_back(obj);
+        }
+    }
+}
+
+// 最近最少使用（LRU）缓存：链表按使用时间排列（头部最新），哈希表记录每个键所在的节点
+struct LruCache<K, V> {
+    capacity: usize,
+    order: LinkedList<(K, V)>,
+    nodes: HashMap<K, NonNull<Node<(K, V)>>>,
+    // 因容量不足被淘汰的键值对交给回调处理
+    on_evict: Option<Box<dyn FnMut(K, V)>>,
+    hits: u64,
+    misses: u64,
+}
+
+impl<K: Clone + Eq + Hash, V> LruCache<K, V> {
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "LRU cache capacity must be positive");
+        Self {
+            capacity,
+            order: LinkedList::new(),
+            nodes: HashMap::with_capacity(capacity),
+            on_evict: None,
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    pub fn with_eviction_callback(mut self, on_evict: impl FnMut(K, V) + 'static) -> Self {
+        self.on_evict = Some(Box::new(on_evict));
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.order.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.order.is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn hits(&self) -> u64 {
+        self.hits
+    }
+
+    pub fn misses(&self) -> u64 {
+        self.misses
+    }
+
+    // 查找并把该键标记为最近使用，同时更新命中/未命中计数
+    pub fn get(&mut self, key: &K) -> Option<&V> {
+        match self.nodes.get(key) {
+            Some(&node_ptr) => {
+                self.hits += 1;
+                unsafe {
+                    self.order.move_to_front(node_ptr);
+                    Some(&(*node_ptr.as_ptr()).val.1)
+                }
+            }
+            None => {
+                self.misses += 1;
+                None
+            }
+        }
+    }
+
+    // 查找但不改变使用顺序，也不计入命中统计
+    pub fn peek(&self, key: &K) -> Option<&V> {
+        self.nodes
+            .get(key)
+            .map(|node_ptr| unsafe { &(*node_ptr.as_ptr()).val.1 })
+    }
+
+    // 插入或更新键值对并标记为最近使用，返回旧值；超出容量时淘汰最久未使用的一项
+    pub fn put(&mut self, key: K, value: V) -> Option<V> {
+        if let Some(&node_ptr) = self.nodes.get(&key) {
+            unsafe {
+                self.order.move_to_front(node_ptr);
+                return Some(std::mem::replace(&mut (*node_ptr.as_ptr()).val.1, value));
+            }
+        }
+
+        self.order.push_front((key.clone(), value));
+        self.nodes.insert(key, self.order.start.unwrap());
+        if self.order.len() > self.capacity {
+            if let Some((evicted_key, evicted_value)) = self.order.pop_back() {
+                self.nodes.remove(&evicted_key);
+                if let Some(on_evict) = self.on_evict.as_mut() {
+                    on_evict(evicted_key, evicted_value);
+                }
+            }
+        }
+        None
+    }
+
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let node_ptr = self.nodes.remove(key)?;
+        Some(unsafe { self.order.remove_node(node_ptr) }.1)
+    }
+}
+
 impl<T> Display for LinkedList<T>
 where
     T: Display,
@@ -122,9 +732,32 @@ where
     }
 }
 
+// 测试用的伪随机数生成器与其他习题共用，位于同目录下的 test_rng.rs
+#[cfg(test)]
+#[path = "test_rng.rs"]
+mod test_rng;
+
 #[cfg(test)]
 mod tests {
-    use super::LinkedList;
+    // 这些测试规模都很小，可以用 cargo +nightly miri test --test algorithm2 检查指针操作是否合法
+    use super::test_rng::pseudo_random;
+    use super::{LinkedList, LruCache};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    fn to_vec<T: Clone>(list: &LinkedList<T>) -> Vec<T> {
+        list.iter().cloned().collect()
+    }
+
+    // 从两个方向各遍历一遍，检查 next 与 prev 指针是否一致
+    fn assert_links<T: Clone + PartialEq + std::fmt::Debug>(list: &LinkedList<T>, expected: &[T]) {
+        assert_eq!(list.len(), expected.len());
+        assert_eq!(to_vec(list), expected);
+        let backwards: Vec<T> = list.iter().rev().cloned().collect();
+        let mut reversed = expected.to_vec();
+        reversed.reverse();
+        assert_eq!(backwards, reversed);
+    }
 
     #[test]
     fn create_numeric_list() {
@@ -177,4 +810,314 @@ mod tests {
             assert_eq!(expected, *list.get(i as i32).unwrap())
         }
     }
+
+    #[test]
+    fn test_push_and_pop_both_ends() {
+        let mut list = LinkedList::new();
+        assert!(list.is_empty());
+        assert_eq!(list.pop_back(), None);
+        list.push_back(2);
+        list.push_front(1);
+        list.push_back(3);
+        assert_links(&list, &[1, 2, 3]);
+        assert_eq!(list.pop_back(), Some(3));
+        assert_eq!(list.pop_front(), Some(1));
+        assert_links(&list, &[2]);
+        assert_eq!(list.pop_back(), Some(2));
+        assert_eq!(list.pop_front(), None);
+        assert_links(&list, &[]);
+        list.push_front(4);
+        assert_links(&list, &[4]);
+    }
+
+    #[test]
+    fn test_cursor_moves_both_ways() {
+        let mut list: LinkedList<i32> = (1..=3).collect();
+        let mut cursor = list.cursor_front_mut();
+        assert_eq!(cursor.index(), Some(0));
+        assert_eq!(cursor.current(), Some(&mut 1));
+        assert_eq!(cursor.peek_prev(), None);
+        cursor.move_next();
+        cursor.move_next();
+        assert_eq!(cursor.current(), Some(&mut 3));
+        assert_eq!(cursor.peek_next(), None);
+        // 越过末尾进入幽灵位置，再前进回到头部
+        cursor.move_next();
+        assert_eq!(cursor.index(), None);
+        assert_eq!(cursor.current(), None);
+        assert_eq!(cursor.peek_next(), Some(&mut 1));
+        assert_eq!(cursor.peek_prev(), Some(&mut 3));
+        cursor.move_next();
+        assert_eq!(cursor.index(), Some(0));
+        cursor.move_prev();
+        assert_eq!(cursor.index(), None);
+        cursor.move_prev();
+        assert_eq!(cursor.index(), Some(2));
+        if let Some(val) = cursor.current() {
+            *val = 30;
+        }
+        assert_links(&list, &[1, 2, 30]);
+    }
+
+    #[test]
+    fn test_cursor_insert_and_remove() {
+        let mut list: LinkedList<i32> = vec![1, 3].into_iter().collect();
+        let mut cursor = list.cursor_front_mut();
+        cursor.insert_after(2);
+        cursor.insert_before(0);
+        assert_eq!(cursor.index(), Some(1));
+        assert_eq!(cursor.remove_current(), Some(1));
+        assert_eq!(cursor.current(), Some(&mut 2));
+        cursor.move_next();
+        assert_eq!(cursor.remove_current(), Some(3));
+        assert_eq!(cursor.index(), None);
+        assert_eq!(cursor.remove_current(), None);
+        // 幽灵位置：insert_after 插入头部，insert_before 插入尾部
+        cursor.insert_after(-1);
+        cursor.insert_before(4);
+        assert_eq!(cursor.index(), None);
+        assert_links(&list, &[-1, 0, 2, 4]);
+
+        let mut single: LinkedList<i32> = vec![5].into_iter().collect();
+        let mut cursor = single.cursor_back_mut();
+        assert_eq!(cursor.remove_current(), Some(5));
+        assert_links(&single, &[]);
+    }
+
+    #[test]
+    fn test_cursor_split() {
+        let mut list: LinkedList<i32> = (0..6).collect();
+        let mut cursor = list.cursor_front_mut();
+        cursor.move_next();
+        cursor.move_next();
+        let after = cursor.split_after();
+        assert_eq!(cursor.index(), Some(2));
+        let before = cursor.split_before();
+        assert_eq!(cursor.index(), Some(0));
+        assert_links(&before, &[0, 1]);
+        assert_links(&after, &[3, 4, 5]);
+        assert_links(&list, &[2]);
+
+        let mut cursor = list.cursor_front_mut();
+        cursor.move_prev();
+        let everything = cursor.split_after();
+        assert_links(&everything, &[2]);
+        assert_links(&list, &[]);
+    }
+
+    #[test]
+    fn test_append_and_split_off() {
+        let mut list: LinkedList<i32> = (0..3).collect();
+        let mut other: LinkedList<i32> = (3..6).collect();
+        list.append(&mut other);
+        assert_links(&list, &[0, 1, 2, 3, 4, 5]);
+        assert_links(&other, &[]);
+        // 追加空链表，或向空链表追加
+        list.append(&mut other);
+        other.append(&mut list);
+        assert_links(&list, &[]);
+        assert_links(&other, &[0, 1, 2, 3, 4, 5]);
+
+        // 断点靠前与靠后时分别从两端走过去
+        let tail = other.split_off(4);
+        assert_links(&other, &[0, 1, 2, 3]);
+        assert_links(&tail, &[4, 5]);
+        let tail = other.split_off(1);
+        assert_links(&other, &[0]);
+        assert_links(&tail, &[1, 2, 3]);
+        assert_links(&other.split_off(1), &[]);
+        assert_links(&other.split_off(0), &[0]);
+        assert_links(&other, &[]);
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot split off at a nonexistent index")]
+    fn test_split_off_out_of_bounds() {
+        let mut list: LinkedList<i32> = (0..2).collect();
+        list.split_off(3);
+    }
+
+    #[test]
+    fn test_double_ended_iteration() {
+        let mut list: LinkedList<i32> = (1..=5).collect();
+        let mut iter = list.iter();
+        assert_eq!(iter.next(), Some(&1));
+        assert_eq!(iter.next_back(), Some(&5));
+        assert_eq!(iter.size_hint(), (3, Some(3)));
+        assert_eq!(iter.collect::<Vec<_>>(), vec![&2, &3, &4]);
+
+        // 两端交替取可变引用，中间相遇时停止
+        let mut iter_mut = list.iter_mut();
+        while let (Some(front), Some(back)) = (iter_mut.next(), iter_mut.next_back()) {
+            std::mem::swap(front, back);
+        }
+        for val in &mut list {
+            *val *= 10;
+        }
+        assert_links(&list, &[50, 40, 30, 20, 10]);
+        assert_eq!((&list).into_iter().next_back(), Some(&10));
+        let shared = &list;
+        assert_eq!(shared.nth(1), Some(&40));
+        assert_eq!(shared.nth(3), Some(&20));
+        assert_eq!(shared.nth(5), None);
+
+        let mut into_iter = list.into_iter();
+        assert_eq!(into_iter.next_back(), Some(10));
+        assert_eq!(into_iter.next(), Some(50));
+        assert_eq!(into_iter.size_hint(), (3, Some(3)));
+        assert_eq!(into_iter.rev().collect::<Vec<_>>(), vec![20, 30, 40]);
+    }
+
+    #[test]
+    fn test_drop_releases_every_value() {
+        let value = Rc::new(());
+        {
+            let mut list: LinkedList<Rc<()>> = (0..4).map(|_| Rc::clone(&value)).collect();
+            let mut tail = list.split_off(2);
+            tail.cursor_front_mut().insert_after(Rc::clone(&value));
+            list.append(&mut tail);
+            let mut partial = list.into_iter();
+            partial.next_back();
+            assert_eq!(Rc::strong_count(&value), 5);
+        }
+        assert_eq!(Rc::strong_count(&value), 1);
+    }
+
+    #[test]
+    fn test_lru_cache_evicts_least_recently_used() {
+        let evicted = Rc::new(RefCell::new(Vec::new()));
+        let log = Rc::clone(&evicted);
+        let mut cache = LruCache::new(2)
+            .with_eviction_callback(move |key, value| log.borrow_mut().push((key, value)));
+        assert!(cache.is_empty());
+        assert_eq!(cache.capacity(), 2);
+
+        assert_eq!(cache.put("a", 1), None);
+        assert_eq!(cache.put("b", 2), None);
+        // 访问 a 之后，b 成为最久未使用的一项
+        assert_eq!(cache.get(&"a"), Some(&1));
+        assert_eq!(cache.put("c", 3), None);
+        assert_eq!(*evicted.borrow(), vec![("b", 2)]);
+        assert_eq!(cache.get(&"b"), None);
+
+        // peek 不改变顺序，所以接下来淘汰的是 a
+        assert_eq!(cache.peek(&"a"), Some(&1));
+        assert_eq!(cache.put("c", 30), Some(3));
+        assert_eq!(cache.put("d", 4), None);
+        assert_eq!(*evicted.borrow(), vec![("b", 2), ("a", 1)]);
+
+        assert_eq!(cache.remove(&"c"), Some(30));
+        assert_eq!(cache.remove(&"c"), None);
+        assert_eq!(cache.len(), 1);
+        assert_eq!((cache.hits(), cache.misses()), (1, 1));
+    }
+
+    #[test]
+    fn test_lru_cache_memoises_factorization() {
+        fn factorize(mut n: u64) -> Vec<u64> {
+            let mut factors = Vec::new();
+            let mut p = 2;
+            while p * p <= n {
+                while n.is_multiple_of(p) {
+                    factors.push(p);
+                    n /= p;
+                }
+                p += 1;
+            }
+            if n > 1 {
+                factors.push(n);
+            }
+            factors
+        }
+
+        let mut cache = LruCache::new(4);
+        for n in [360, 97, 360, 1024, 97, 360] {
+            if cache.get(&n).is_none() {
+                cache.put(n, factorize(n));
+            }
+            assert_eq!(cache.peek(&n), Some(&factorize(n)));
+        }
+        assert_eq!((cache.hits(), cache.misses()), (3, 3));
+    }
+
+    #[test]
+    fn test_lru_cache_matches_reference_model() {
+        // 朴素模型：按使用时间排列的键值对，尾部最新，每次操作 O(n)
+        struct Model {
+            capacity: usize,
+            entries: Vec<(u8, u32)>,
+            evicted: Vec<(u8, u32)>,
+        }
+
+        impl Model {
+            fn touch(&mut self, key: u8) -> Option<usize> {
+                let index = self.entries.iter().position(|&(k, _)| k == key)?;
+                let entry = self.entries.remove(index);
+                self.entries.push(entry);
+                Some(self.entries.len() - 1)
+            }
+
+            fn get(&mut self, key: u8) -> Option<u32> {
+                self.touch(key).map(|index| self.entries[index].1)
+            }
+
+            fn put(&mut self, key: u8, value: u32) -> Option<u32> {
+                if let Some(index) = self.touch(key) {
+                    return Some(std::mem::replace(&mut self.entries[index].1, value));
+                }
+                self.entries.push((key, value));
+                if self.entries.len() > self.capacity {
+                    self.evicted.push(self.entries.remove(0));
+                }
+                None
+            }
+
+            fn remove(&mut self, key: u8) -> Option<u32> {
+                let index = self.entries.iter().position(|&(k, _)| k == key)?;
+                Some(self.entries.remove(index).1)
+            }
+        }
+
+        let mut seed: u64 = 2024;
+        for capacity in [1, 2, 3, 5] {
+            let evicted = Rc::new(RefCell::new(Vec::new()));
+            let log = Rc::clone(&evicted);
+            let mut cache = LruCache::new(capacity)
+                .with_eviction_callback(move |key, value| log.borrow_mut().push((key, value)));
+            let mut model = Model {
+                capacity,
+                entries: Vec::new(),
+                evicted: Vec::new(),
+            };
+            let (mut hits, mut misses) = (0, 0);
+            for _ in 0..300 {
+                let key = (pseudo_random(&mut seed) % 8) as u8;
+                match pseudo_random(&mut seed) % 4 {
+                    0 => {
+                        let expected = model.get(key);
+                        match expected {
+                            Some(_) => hits += 1,
+                            None => misses += 1,
+                        }
+                        assert_eq!(cache.get(&key).copied(), expected);
+                    }
+                    1 => {
+                        let expected = model.entries.iter().find(|&&(k, _)| k == key);
+                        assert_eq!(cache.peek(&key), expected.map(|(_, v)| v));
+                    }
+                    2 => assert_eq!(cache.remove(&key), model.remove(key)),
+                    _ => {
+                        let value = (pseudo_random(&mut seed) % 1000) as u32;
+                        assert_eq!(cache.put(key, value), model.put(key, value));
+                    }
+                }
+                assert_eq!(cache.len(), model.entries.len());
+                assert_eq!(*evicted.borrow(), model.evicted);
+                // 链表顺序（头部最新）与模型一致
+                let order: Vec<(u8, u32)> = cache.order.iter().rev().copied().collect();
+                assert_eq!(order, model.entries);
+            }
+            assert_eq!((cache.hits(), cache.misses()), (hits, misses));
+        }
+    }
 }
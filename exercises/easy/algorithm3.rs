@@ -9,46 +9,426 @@
     你可以使用冒泡排序、插入排序、堆排序等。
 */
 
-fn sort<T: Ord + Clone>(array: &mut [T]) {
-    quicksort(array);
+use std::cmp::Ordering;
+use std::thread;
+
+// 不超过该长度的切片直接使用插入排序
+const INSERTION_SORT_THRESHOLD: usize = 16;
+// 并行排序时短于该长度的切片不再分给新线程
+const PARALLEL_SORT_THRESHOLD: usize = 1 << 14;
+
+// 不稳定排序：内省排序，最坏 O(n log n)，不需要额外内存
+fn sort<T: Ord>(array: &mut [T]) {
+    sort_by(array, T::cmp);
+}
+
+fn sort_by<T, F>(array: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    // 递归深度超过 2·log2(n) 说明主元选择持续失败，此时改用堆排序
+    let depth_limit = 2 * (usize::BITS - array.len().leading_zeros());
+    introsort(
+        array,
+        &mut |a, b| compare(a, b) == Ordering::Less,
+        depth_limit,
+    );
+}
+
+fn sort_by_key<T, K, F>(array: &mut [T], mut key: F)
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    sort_by(array, |a, b| key(a).cmp(&key(b)));
+}
+
+// 并行排序：使用全部可用的 CPU 核心，结果与 sort 相同
+fn par_sort<T: Ord + Send + Sync>(array: &mut [T]) {
+    par_sort_by(array, T::cmp);
+}
+
+fn par_sort_by<T, F>(array: &mut [T], compare: F)
+where
+    T: Send + Sync,
+    F: Fn(&T, &T) -> Ordering + Sync,
+{
+    let threads = thread::available_parallelism().map_or(1, |n| n.get());
+    par_sort_by_with_threads(array, threads, compare);
 }
 
-// 使用快速排序递归数组
-fn quicksort<T: Ord + Clone>(arr: &mut [T]) {
-    if arr.len() <= 1 {
+// 最多同时使用 threads 个线程，threads 为 0 或 1 时等同于 sort_by
+// 先把数组切成 threads 段并发做内省排序，再在下标上逐轮两两归并（每次归并也按线程预算拆开并行），
+// 最后按置换环重排元素，与 stable_sort 一样元素只被交换、从不复制
+fn par_sort_by_with_threads<T, F>(array: &mut [T], threads: usize, compare: F)
+where
+    T: Send + Sync,
+    F: Fn(&T, &T) -> Ordering + Sync,
+{
+    let is_less = &|a: &T, b: &T| compare(a, b) == Ordering::Less;
+    if threads <= 1 || array.len() <= PARALLEL_SORT_THRESHOLD {
+        let depth_limit = 2 * (usize::BITS - array.len().leading_zeros());
+        introsort(array, &mut |a, b| is_less(a, b), depth_limit);
         return;
     }
-    // 选最后一个元素为 pivot, 分区并获取 pivot 最终位置
-    let pivot_index = partition(arr);
-    // 分割数组: 左侧为小于等于 pivot 的部分, 右侧为大于 pivot 的部分 (右侧第一个为 pivot)
-    let (left, right) = arr.split_at_mut(pivot_index);
-    quicksort(left);
-    // 右侧若多于一个元素, 排序除 pivot 外的部分
-    if right.len() > 1 {
-        quicksort(&mut right[1..]);
-    }
-}
-
-// 使用最后一个元素作为 pivot, 并调整数组, 返回 pivot 的最终位置
-fn partition<T: Ord + Clone>(arr: &mut [T]) -> usize {
-    let pivot_index = arr.len() - 1;
-    let pivot = arr[pivot_index].clone();
-    let mut i = 0;
-    // 遍历除 pivot 外的所有元素, 小于等于 pivot 的交换到左侧
-    for j in 0..pivot_index {
-        if arr[j] <= pivot {
-            arr.swap(i, j);
+
+    let chunk_len = array.len().div_ceil(threads);
+    thread::scope(|scope| {
+        for chunk in array.chunks_mut(chunk_len) {
+            scope.spawn(move || {
+                let depth_limit = 2 * (usize::BITS - chunk.len().leading_zeros());
+                introsort(chunk, &mut |a, b| is_less(a, b), depth_limit);
+            });
+        }
+    });
+
+    let elements: &[T] = array;
+    let index_less = &|&a: &usize, &b: &usize| is_less(&elements[a], &elements[b]);
+    let mut order: Vec<usize> = (0..elements.len()).collect();
+    let mut buffer = vec![0; elements.len()];
+    let mut run_len = chunk_len;
+    while run_len < order.len() {
+        // 本轮共有 pairs 组归并同时进行，线程预算平均分给每一组
+        let pairs = order.len().div_ceil(2 * run_len);
+        let pair_threads = (threads / pairs).max(1);
+        thread::scope(|scope| {
+            for (runs, out) in order
+                .chunks(2 * run_len)
+                .zip(buffer.chunks_mut(2 * run_len))
+            {
+                let (left, right) = runs.split_at(run_len.min(runs.len()));
+                scope.spawn(move || par_merge(left, right, out, index_less, pair_threads));
+            }
+        });
+        std::mem::swap(&mut order, &mut buffer);
+        run_len *= 2;
+    }
+    apply_order(array, order);
+}
+
+// 把有序的 left 与 right 归并到 out，最多使用 threads 个线程（含当前线程）
+fn par_merge<F>(left: &[usize], right: &[usize], out: &mut [usize], is_less: &F, threads: usize)
+where
+    F: Fn(&usize, &usize) -> bool + Sync,
+{
+    if threads <= 1 || out.len() <= PARALLEL_SORT_THRESHOLD {
+        merge(left, right, out, is_less);
+        return;
+    }
+    // 取较长一段的中点，在另一段中二分出分界，使分界前后的两部分可以各自独立归并
+    let (left_mid, right_mid) = if left.len() >= right.len() {
+        let mid = left.len() / 2;
+        (mid, right.partition_point(|x| is_less(x, &left[mid])))
+    } else {
+        let mid = right.len() / 2;
+        (left.partition_point(|x| !is_less(&right[mid], x)), mid)
+    };
+    let (front, back) = out.split_at_mut(left_mid + right_mid);
+    thread::scope(|scope| {
+        scope.spawn(|| {
+            par_merge(
+                &left[..left_mid],
+                &right[..right_mid],
+                front,
+                is_less,
+                threads / 2,
+            )
+        });
+        par_merge(
+            &left[left_mid..],
+            &right[right_mid..],
+            back,
+            is_less,
+            threads - threads / 2,
+        );
+    });
+}
+
+fn merge<F>(left: &[usize], right: &[usize], out: &mut [usize], is_less: &F)
+where
+    F: Fn(&usize, &usize) -> bool,
+{
+    let (mut i, mut j) = (0, 0);
+    for slot in out.iter_mut() {
+        if j < right.len() && (i == left.len() || is_less(&right[j], &left[i])) {
+            *slot = right[j];
+            j += 1;
+        } else {
+            *slot = left[i];
             i += 1;
         }
     }
+}
+
+// 快速排序，只对较短的一侧递归、较长的一侧循环处理，栈深度为 O(log n)
+fn introsort<T, F>(mut arr: &mut [T], is_less: &mut F, mut depth_limit: u32)
+where
+    F: FnMut(&T, &T) -> bool,
+{
+    loop {
+        if arr.len() <= INSERTION_SORT_THRESHOLD {
+            insertion_sort(arr, is_less);
+            return;
+        }
+        if depth_limit == 0 {
+            heapsort(arr, is_less);
+            return;
+        }
+        depth_limit -= 1;
+
+        let pivot_index = partition(arr, is_less);
+        // 分割数组: 左侧为不大于 pivot 的部分, 右侧第一个为 pivot, 之后为不小于 pivot 的部分
+        let (left, right) = arr.split_at_mut(pivot_index);
+        let right = &mut right[1..];
+        if left.len() < right.len() {
+            introsort(left, is_less, depth_limit);
+            arr = right;
+        } else {
+            introsort(right, is_less, depth_limit);
+            arr = left;
+        }
+    }
+}
+
+// 取首、中、尾三个元素的中位数作为 pivot 并交换到开头，再从两端向中间扫描分区
+// 与 pivot 相等的元素会让两侧扫描都停下并交换，大量重复元素时两侧依然均衡
+fn partition<T, F>(arr: &mut [T], is_less: &mut F) -> usize
+where
+    F: FnMut(&T, &T) -> bool,
+{
+    let (first, mid, last) = (0, arr.len() / 2, arr.len() - 1);
+    if is_less(&arr[mid], &arr[first]) {
+        arr.swap(mid, first);
+    }
+    if is_less(&arr[last], &arr[mid]) {
+        arr.swap(last, mid);
+        if is_less(&arr[mid], &arr[first]) {
+            arr.swap(mid, first);
+        }
+    }
+    arr.swap(0, mid);
+
+    let (mut i, mut j) = (1, last);
+    loop {
+        while i <= j && is_less(&arr[i], &arr[0]) {
+            i += 1;
+        }
+        while i <= j && is_less(&arr[0], &arr[j]) {
+            j -= 1;
+        }
+        if i >= j {
+            break;
+        }
+        arr.swap(i, j);
+        i += 1;
+        j -= 1;
+    }
     // 将 pivot 放到正确位置
-    arr.swap(i, pivot_index);
-    i
+    arr.swap(0, j);
+    j
+}
+
+fn insertion_sort<T, F>(arr: &mut [T], is_less: &mut F)
+where
+    F: FnMut(&T, &T) -> bool,
+{
+    for i in 1..arr.len() {
+        let mut j = i;
+        while j > 0 && is_less(&arr[j], &arr[j - 1]) {
+            arr.swap(j, j - 1);
+            j -= 1;
+        }
+    }
+}
+
+fn heapsort<T, F>(arr: &mut [T], is_less: &mut F)
+where
+    F: FnMut(&T, &T) -> bool,
+{
+    // 建立大顶堆，然后依次把堆顶换到末尾
+    for start in (0..arr.len() / 2).rev() {
+        sift_down(arr, start, is_less);
+    }
+    for end in (1..arr.len()).rev() {
+        arr.swap(0, end);
+        sift_down(&mut arr[..end], 0, is_less);
+    }
+}
+
+fn sift_down<T, F>(heap: &mut [T], mut node: usize, is_less: &mut F)
+where
+    F: FnMut(&T, &T) -> bool,
+{
+    loop {
+        let mut child = 2 * node + 1;
+        if child >= heap.len() {
+            return;
+        }
+        if child + 1 < heap.len() && is_less(&heap[child], &heap[child + 1]) {
+            child += 1;
+        }
+        if !is_less(&heap[node], &heap[child]) {
+            return;
+        }
+        heap.swap(node, child);
+        node = child;
+    }
+}
+
+// 稳定排序：对下标做归并排序得到排序后的顺序，再按置换环交换元素
+// 元素本身只被交换、从不复制，比较函数 panic 时切片保持原样
+fn stable_sort<T: Ord>(array: &mut [T]) {
+    stable_sort_by(array, T::cmp);
+}
+
+fn stable_sort_by<T, F>(array: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let mut order: Vec<usize> = (0..array.len()).collect();
+    let mut buffer = order.clone();
+    merge_sort(&mut order, &mut buffer, &mut |&a: &usize, &b: &usize| {
+        compare(&array[a], &array[b]) == Ordering::Less
+    });
+    apply_order(array, order);
+}
+
+fn stable_sort_by_key<T, K, F>(array: &mut [T], mut key: F)
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    stable_sort_by(array, |a, b| key(a).cmp(&key(b)));
+}
+
+// 自顶向下归并排序，buffer 与 arr 等长，两者轮流作为归并的目标
+fn merge_sort<F>(arr: &mut [usize], buffer: &mut [usize], is_less: &mut F)
+where
+    F: FnMut(&usize, &usize) -> bool,
+{
+    if arr.len() <= INSERTION_SORT_THRESHOLD {
+        // 插入排序只交换严格逆序的相邻元素，是稳定的
+        insertion_sort(arr, is_less);
+        return;
+    }
+    let mid = arr.len() / 2;
+    {
+        let (left, right) = arr.split_at_mut(mid);
+        let (left_buffer, right_buffer) = buffer.split_at_mut(mid);
+        merge_sort(left, left_buffer, is_less);
+        merge_sort(right, right_buffer, is_less);
+    }
+    // 左半部分已有序且不大于右半部分的第一个元素时无需归并
+    if !is_less(&arr[mid], &arr[mid - 1]) {
+        return;
+    }
+    buffer.copy_from_slice(arr);
+    let (left, right) = buffer.split_at(mid);
+    let (mut i, mut j) = (0, 0);
+    for slot in arr.iter_mut() {
+        // 只有右侧严格更小时才先取右侧，相等元素保持原来的先后顺序
+        if j < right.len() && (i == left.len() || is_less(&right[j], &left[i])) {
+            *slot = right[j];
+            j += 1;
+        } else {
+            *slot = left[i];
+            i += 1;
+        }
+    }
+}
+
+// 把 array 重排为 array[order[0]], array[order[1]], ...；沿置换环交换，O(n)
+fn apply_order<T>(array: &mut [T], mut order: Vec<usize>) {
+    for start in 0..array.len() {
+        let mut current = start;
+        while order[current] != start {
+            let next = order[current];
+            array.swap(current, next);
+            order[current] = current;
+            current = next;
+        }
+        order[current] = current;
+    }
+}
+
+// 可用于基数排序的整数键，映射为保持大小顺序的 u64
+trait RadixKey: Copy {
+    fn radix_key(self) -> u64;
+}
+
+macro_rules! radix_key_unsigned {
+    ($($t:ty),*) => {$(
+        impl RadixKey for $t {
+            fn radix_key(self) -> u64 {
+                self as u64
+            }
+        }
+    )*};
 }
 
+macro_rules! radix_key_signed {
+    ($($t:ty),*) => {$(
+        impl RadixKey for $t {
+            // 翻转符号位，使负数排在非负数之前
+            fn radix_key(self) -> u64 {
+                (self as i64 as u64) ^ (1 << 63)
+            }
+        }
+    )*};
+}
+
+radix_key_unsigned!(u8, u16, u32, u64, usize);
+radix_key_signed!(i8, i16, i32, i64, isize);
+
+// 稳定的 LSD 基数排序，每轮按 8 位分桶，所有键在某一字节上相同时跳过该轮
+fn radix_sort<T: RadixKey>(array: &mut [T]) {
+    radix_sort_by_key(array, |&value| value);
+}
+
+fn radix_sort_by_key<T, K, F>(array: &mut [T], mut key: F)
+where
+    K: RadixKey,
+    F: FnMut(&T) -> K,
+{
+    let keys: Vec<u64> = array.iter().map(|item| key(item).radix_key()).collect();
+    let mut order: Vec<usize> = (0..array.len()).collect();
+    let mut next_order = vec![0; array.len()];
+    for shift in (0..64).step_by(8) {
+        let digit = |index: usize| ((keys[index] >> shift) & 0xff) as usize;
+        let mut counts = [0usize; 256];
+        for &index in &order {
+            counts[digit(index)] += 1;
+        }
+        if counts.contains(&array.len()) {
+            continue;
+        }
+        // 计数转换为每个桶的起始位置
+        let mut offset = 0;
+        for count in counts.iter_mut() {
+            let bucket_size = *count;
+            *count = offset;
+            offset += bucket_size;
+        }
+        for &index in &order {
+            let bucket = &mut counts[digit(index)];
+            next_order[*bucket] = index;
+            *bucket += 1;
+        }
+        std::mem::swap(&mut order, &mut next_order);
+    }
+    apply_order(array, order);
+}
+
+// 测试用的伪随机数生成器与其他习题共用，位于同目录下的 test_rng.rs
+#[cfg(test)]
+#[path = "test_rng.rs"]
+mod test_rng;
+
 #[cfg(test)]
 mod tests {
+    use super::test_rng::pseudo_random;
     use super::*;
+    use std::cell::Cell;
 
     #[test]
     fn test_sort_1() {
@@ -68,4 +448,291 @@ mod tests {
         sort(&mut vec);
         assert_eq!(vec, vec![11, 22, 33, 44, 55, 66, 77, 88, 99]);
     }
+
+    // 常见的对快速排序不友好的输入
+    fn adversarial_inputs(len: usize) -> Vec<(&'static str, Vec<i64>)> {
+        let n = len as i64;
+        let mut seed = len as u64;
+        vec![
+            ("sorted", (0..n).collect()),
+            ("reversed", (0..n).rev().collect()),
+            ("all equal", vec![7; len]),
+            ("two values", (0..n).map(|i| i % 2).collect()),
+            ("organ pipe", (0..n).map(|i| i.min(n - i)).collect()),
+            ("sawtooth", (0..n).map(|i| i % 32).collect()),
+            (
+                "sorted with noise",
+                (0..n).map(|i| if i % 100 == 0 { -i } else { i }).collect(),
+            ),
+            (
+                "random",
+                (0..len)
+                    .map(|_| pseudo_random(&mut seed) as i64 % 1000 - 500)
+                    .collect(),
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_every_sort_on_adversarial_inputs() {
+        for len in [0, 1, 2, 3, 16, 17, 100, 1000] {
+            for (name, input) in adversarial_inputs(len) {
+                let mut expected = input.clone();
+                expected.sort();
+
+                let mut introsorted = input.clone();
+                sort(&mut introsorted);
+                assert_eq!(introsorted, expected, "sort on {} ({})", name, len);
+
+                let mut merged = input.clone();
+                stable_sort(&mut merged);
+                assert_eq!(merged, expected, "stable_sort on {} ({})", name, len);
+
+                let mut radix = input.clone();
+                radix_sort(&mut radix);
+                assert_eq!(radix, expected, "radix_sort on {} ({})", name, len);
+            }
+        }
+    }
+
+    #[test]
+    fn test_introsort_comparisons_stay_n_log_n() {
+        let len: usize = 20_000;
+        let bound = 4 * len * (usize::BITS - len.leading_zeros()) as usize;
+        for (name, mut input) in adversarial_inputs(len) {
+            let comparisons = Cell::new(0usize);
+            sort_by(&mut input, |a, b| {
+                comparisons.set(comparisons.get() + 1);
+                a.cmp(b)
+            });
+            assert!(
+                comparisons.get() <= bound,
+                "{} used {} comparisons",
+                name,
+                comparisons.get()
+            );
+        }
+    }
+
+    // McIlroy 的“快速排序杀手”：比较时才决定元素的大小，专门让每次选出的 pivot 都很差
+    #[test]
+    fn test_introsort_survives_mcilroy_adversary() {
+        let len = 5_000;
+        let gas = len;
+        let mut values = vec![gas; len];
+        let mut solid = 0;
+        let mut candidate = 0;
+        let mut comparisons = 0usize;
+        let mut items: Vec<usize> = (0..len).collect();
+        sort_by(&mut items, |&x, &y| {
+            comparisons += 1;
+            if values[x] == gas && values[y] == gas {
+                let frozen = if x == candidate { x } else { y };
+                values[frozen] = solid;
+                solid += 1;
+            }
+            if values[x] == gas {
+                candidate = x;
+            } else if values[y] == gas {
+                candidate = y;
+            }
+            values[x].cmp(&values[y])
+        });
+
+        let bound = 4 * len * (usize::BITS - len.leading_zeros()) as usize;
+        assert!(comparisons <= bound, "{} comparisons", comparisons);
+        // 结果必须与比较过程中确定下来的大小关系一致
+        assert!(items.windows(2).all(|w| values[w[0]] <= values[w[1]]));
+    }
+
+    #[test]
+    fn test_heapsort_fallback() {
+        for (name, mut input) in adversarial_inputs(500) {
+            let mut expected = input.clone();
+            expected.sort();
+            // 深度限制为 0 时直接使用堆排序
+            introsort(&mut input, &mut |a: &i64, b: &i64| a < b, 0);
+            assert_eq!(input, expected, "heapsort on {}", name);
+        }
+    }
+
+    #[test]
+    fn test_deep_sorted_input_does_not_overflow_stack() {
+        let mut input: Vec<u32> = (0..1_000_000).collect();
+        input.swap(0, 999_999);
+        sort(&mut input);
+        assert!(input.windows(2).all(|w| w[0] <= w[1]));
+    }
+
+    #[test]
+    fn test_sort_by_and_sort_by_key() {
+        let mut words = vec!["pear", "fig", "banana", "kiwi", "apple"];
+        sort_by(&mut words, |a, b| b.cmp(a));
+        assert_eq!(words, vec!["pear", "kiwi", "fig", "banana", "apple"]);
+        sort_by_key(&mut words, |word| word.len());
+        assert_eq!(words[0], "fig");
+        assert_eq!(words[4], "banana");
+    }
+
+    #[test]
+    fn test_stable_sorts_keep_equal_elements_in_order() {
+        let mut seed = 11;
+        let records: Vec<(u64, usize)> = (0..2000)
+            .map(|i| (pseudo_random(&mut seed) % 16, i))
+            .collect();
+        let mut expected = records.clone();
+        expected.sort_by_key(|&(key, _)| key);
+
+        let mut merged = records.clone();
+        stable_sort_by_key(&mut merged, |&(key, _)| key);
+        assert_eq!(merged, expected);
+
+        let mut radix = records.clone();
+        radix_sort_by_key(&mut radix, |&(key, _)| key);
+        assert_eq!(radix, expected);
+
+        let mut descending = records;
+        stable_sort_by(&mut descending, |a, b| b.0.cmp(&a.0));
+        expected.sort_by_key(|&(key, _)| std::cmp::Reverse(key));
+        assert_eq!(descending, expected);
+    }
+
+    #[test]
+    fn test_sorting_without_clone() {
+        // 不实现 Clone 的元素也能排序
+        #[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+        struct Ticket(i32, String);
+
+        let make = || -> Vec<Ticket> {
+            [5, -3, 9, 0, -3]
+                .iter()
+                .map(|&n| Ticket(n, n.to_string()))
+                .collect()
+        };
+        let expected = vec![-3, -3, 0, 5, 9];
+
+        let mut tickets = make();
+        sort(&mut tickets);
+        assert_eq!(tickets.iter().map(|t| t.0).collect::<Vec<_>>(), expected);
+        let mut tickets = make();
+        stable_sort(&mut tickets);
+        assert_eq!(tickets.iter().map(|t| t.0).collect::<Vec<_>>(), expected);
+        let mut tickets = make();
+        radix_sort_by_key(&mut tickets, |ticket| ticket.0);
+        assert_eq!(tickets.iter().map(|t| t.0).collect::<Vec<_>>(), expected);
+    }
+
+    #[test]
+    fn test_radix_sort_integer_types() {
+        let mut signed: Vec<i8> = vec![i8::MAX, -1, 0, i8::MIN, 1, -128, 127];
+        radix_sort(&mut signed);
+        assert_eq!(signed, vec![-128, -128, -1, 0, 1, 127, 127]);
+
+        let mut unsigned: Vec<u64> = vec![u64::MAX, 0, 1 << 40, 255, 256, 1 << 40];
+        radix_sort(&mut unsigned);
+        assert_eq!(unsigned, vec![0, 255, 256, 1 << 40, 1 << 40, u64::MAX]);
+
+        let mut wide: Vec<i64> = vec![i64::MIN, i64::MAX, -1, 0, i64::MIN + 1];
+        radix_sort(&mut wide);
+        assert_eq!(wide, vec![i64::MIN, i64::MIN + 1, -1, 0, i64::MAX]);
+    }
+
+    #[test]
+    fn test_panicking_comparator_leaves_stable_sort_input_intact() {
+        let mut input: Vec<String> = (0..50).rev().map(|i| i.to_string()).collect();
+        let original = input.clone();
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            let mut calls = 0;
+            stable_sort_by(&mut input, |a, b| {
+                calls += 1;
+                assert!(calls < 100, "comparator gave up");
+                a.cmp(b)
+            });
+        }));
+        assert!(result.is_err());
+        assert_eq!(input, original);
+    }
+
+    #[test]
+    fn test_par_sort_matches_sort_on_large_random_input() {
+        let mut seed = 99;
+        let input: Vec<u64> = (0..400_000).map(|_| pseudo_random(&mut seed)).collect();
+        let mut expected = input.clone();
+        sort(&mut expected);
+        for threads in [0, 1, 2, 3, 8] {
+            let mut parallel = input.clone();
+            par_sort_by_with_threads(&mut parallel, threads, u64::cmp);
+            assert_eq!(parallel, expected, "{} threads", threads);
+        }
+        let mut parallel = input;
+        par_sort(&mut parallel);
+        assert_eq!(parallel, expected);
+    }
+
+    #[test]
+    fn test_par_sort_with_many_duplicate_keys() {
+        let mut seed = 5;
+        let input: Vec<(u8, u32)> = (0..200_000)
+            .map(|i| ((pseudo_random(&mut seed) % 4) as u8, i % 3))
+            .collect();
+        let mut expected = input.clone();
+        sort(&mut expected);
+        let mut parallel = input.clone();
+        par_sort(&mut parallel);
+        assert_eq!(parallel, expected);
+
+        // 只按键排序时，相同键的元素之间顺序不定，但键的序列必须一致
+        let mut by_key = input;
+        par_sort_by(&mut by_key, |a, b| b.0.cmp(&a.0));
+        assert!(by_key.windows(2).all(|w| w[0].0 >= w[1].0));
+
+        for (name, input) in adversarial_inputs(100_000) {
+            let mut expected = input.clone();
+            sort(&mut expected);
+            let mut parallel = input;
+            par_sort_by_with_threads(&mut parallel, 4, i64::cmp);
+            assert_eq!(parallel, expected, "par_sort on {}", name);
+        }
+    }
+
+    // 并行排序的耗时取决于最忙的线程：4 个线程时任何一个线程的比较次数都应不到串行排序的 30%
+    // 若顶层仍在单个线程上串行分区，该线程承担的比较会超过这一比例
+    #[test]
+    fn test_par_sort_splits_work_across_threads() {
+        use std::collections::HashMap;
+        use std::sync::Mutex;
+
+        let mut seed = 1;
+        let input: Vec<u64> = (0..400_000).map(|_| pseudo_random(&mut seed)).collect();
+        let mut serial = input.clone();
+        let serial_comparisons = Cell::new(0usize);
+        sort_by(&mut serial, |a, b| {
+            serial_comparisons.set(serial_comparisons.get() + 1);
+            a.cmp(b)
+        });
+
+        let mut parallel = input;
+        let per_thread = Mutex::new(HashMap::new());
+        par_sort_by_with_threads(&mut parallel, 4, |a, b| {
+            *per_thread
+                .lock()
+                .unwrap()
+                .entry(std::thread::current().id())
+                .or_insert(0usize) += 1;
+            a.cmp(b)
+        });
+        assert_eq!(parallel, serial);
+        let busiest = per_thread
+            .into_inner()
+            .unwrap()
+            .into_values()
+            .max()
+            .unwrap();
+        assert!(
+            busiest * 10 < serial_comparisons.get() * 3,
+            "busiest thread made {} of {} comparisons",
+            busiest,
+            serial_comparisons.get()
+        );
+    }
 }
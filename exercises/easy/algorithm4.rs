@@ -8,7 +8,9 @@
 */
 
 use std::cmp::Ordering;
+use std::collections::VecDeque;
 use std::fmt::Debug;
+use std::ops::{Bound, RangeBounds};
 
 #[derive(Debug)]
 struct TreeNode<T>
@@ -26,6 +28,7 @@ where
     T: Ord,
 {
     root: Option<Box<TreeNode<T>>>,
+    len: usize,
 }
 
 impl<T> TreeNode<T>
@@ -46,48 +49,230 @@ where
     T: Ord,
 {
     fn new() -> Self {
-        BinarySearchTree { root: None }
+        BinarySearchTree { root: None, len: 0 }
     }
 
     // Insert a value into the BST
     fn insert(&mut self, value: T) {
-        // 若树非空则调用递归插入，否则创建新的根节点
-        match self.root {
+        // 若树非空则沿子树向下插入，否则创建新的根节点
+        let inserted = match self.root {
             Some(ref mut node) => node.insert(value),
-            None => self.root = Some(Box::new(TreeNode::new(value))),
+            None => {
+                self.root = Some(Box::new(TreeNode::new(value)));
+                true
+            }
+        };
+        if inserted {
+            self.len += 1;
         }
     }
 
-    // Search for a value in the BST
-    fn search(&self, value: T) -> bool {
-        // 从根节点开始递归查找目标值
-        fn search_node<T: Ord>(node: &TreeNode<T>, value: &T) -> bool {
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    // 删除值，返回树中是否存在该值
+    fn remove(&mut self, value: &T) -> bool {
+        // 从根开始找到指向目标节点的链接
+        let mut link = &mut self.root;
+        loop {
+            match link.as_ref().map(|node| value.cmp(&node.value)) {
+                None => return false,
+                Some(Ordering::Equal) => break,
+                Some(Ordering::Less) => link = &mut link.as_mut().unwrap().left,
+                Some(Ordering::Greater) => link = &mut link.as_mut().unwrap().right,
+            }
+        }
+
+        let mut node = link.take().unwrap();
+        *link = match (node.left.take(), node.right.take()) {
+            // 叶子节点直接删除
+            (None, None) => None,
+            // 只有一个子节点时由子节点顶替
+            (Some(child), None) | (None, Some(child)) => Some(child),
+            // 有两个子节点时，用右子树中的最小值（中序后继）顶替
+            (Some(left), Some(right)) => {
+                let mut right = Some(right);
+                let mut successor = Self::take_min(&mut right);
+                successor.left = Some(left);
+                successor.right = right;
+                Some(successor)
+            }
+        };
+        self.len -= 1;
+        true
+    }
+
+    // 从非空子树中摘下最小的节点，它的右子树接到原来的位置
+    fn take_min(mut link: &mut Option<Box<TreeNode<T>>>) -> Box<TreeNode<T>> {
+        while link.as_ref().unwrap().left.is_some() {
+            link = &mut link.as_mut().unwrap().left;
+        }
+        let mut min = link.take().unwrap();
+        *link = min.right.take();
+        min
+    }
+
+    fn min(&self) -> Option<&T> {
+        let mut node = self.root.as_deref()?;
+        while let Some(left) = node.left.as_deref() {
+            node = left;
+        }
+        Some(&node.value)
+    }
+
+    fn max(&self) -> Option<&T> {
+        let mut node = self.root.as_deref()?;
+        while let Some(right) = node.right.as_deref() {
+            node = right;
+        }
+        Some(&node.value)
+    }
+
+    // 不大于 value 的最大值
+    fn floor(&self, value: &T) -> Option<&T> {
+        let mut best = None;
+        let mut current = self.root.as_deref();
+        while let Some(node) = current {
             match node.value.cmp(value) {
-                Ordering::Equal => {
-                    // 找到目标值，返回 true
-                    true
-                }
-                Ordering::Greater =>
-                // 当前节点大于目标值，检查左子树
-                {
-                    node.left.as_ref().is_some_and(|n| {
-                        // 左子节点存在，递归查找目标值
-                        search_node(n, value)
-                    })
+                Ordering::Equal => return Some(&node.value),
+                // 当前节点是一个候选，更接近的值只可能在右子树
+                Ordering::Less => {
+                    best = Some(&node.value);
+                    current = node.right.as_deref();
                 }
-                Ordering::Less =>
-                // 当前节点小于目标值，检查右子树
-                {
-                    node.right.as_ref().is_some_and(|n| {
-                        // 右子节点存在，递归查找目标值
-                        search_node(n, value)
-                    })
+                Ordering::Greater => current = node.left.as_deref(),
+            }
+        }
+        best
+    }
+
+    // 不小于 value 的最小值
+    fn ceiling(&self, value: &T) -> Option<&T> {
+        let mut best = None;
+        let mut current = self.root.as_deref();
+        while let Some(node) = current {
+            match node.value.cmp(value) {
+                Ordering::Equal => return Some(&node.value),
+                Ordering::Greater => {
+                    best = Some(&node.value);
+                    current = node.left.as_deref();
                 }
+                Ordering::Less => current = node.right.as_deref(),
+            }
+        }
+        best
+    }
+
+    // 树的层数，空树为 0
+    fn height(&self) -> usize {
+        let mut height = 0;
+        let mut level: Vec<&TreeNode<T>> = self.root.as_deref().into_iter().collect();
+        while !level.is_empty() {
+            height += 1;
+            level = level
+                .iter()
+                .flat_map(|node| {
+                    node.left
+                        .as_deref()
+                        .into_iter()
+                        .chain(node.right.as_deref())
+                })
+                .collect();
+        }
+        height
+    }
+
+    // 按从小到大的顺序遍历
+    fn iter(&self) -> InOrder<'_, T> {
+        self.in_order()
+    }
+
+    fn in_order(&self) -> InOrder<'_, T> {
+        let mut iter = InOrder { stack: Vec::new() };
+        iter.push_left_spine(self.root.as_deref());
+        iter
+    }
+
+    fn pre_order(&self) -> PreOrder<'_, T> {
+        PreOrder {
+            stack: self.root.as_deref().into_iter().collect(),
+        }
+    }
+
+    fn post_order(&self) -> PostOrder<'_, T> {
+        PostOrder {
+            stack: self
+                .root
+                .as_deref()
+                .map(|node| (node, false))
+                .into_iter()
+                .collect(),
+        }
+    }
+
+    fn level_order(&self) -> LevelOrder<'_, T> {
+        LevelOrder {
+            queue: self.root.as_deref().into_iter().collect(),
+        }
+    }
+
+    // 按从小到大的顺序遍历落在 range 内的值，只访问 O(height + 结果个数) 个节点
+    fn range<R: RangeBounds<T>>(&self, range: R) -> Range<'_, T, R> {
+        let mut stack = Vec::new();
+        // 从根向下，只保留不小于下界的节点；小于下界的节点连同左子树一起跳过
+        let mut current = self.root.as_deref();
+        while let Some(node) = current {
+            let above_start = match range.start_bound() {
+                Bound::Included(start) => node.value >= *start,
+                Bound::Excluded(start) => node.value > *start,
+                Bound::Unbounded => true,
+            };
+            if above_start {
+                stack.push(node);
+                current = node.left.as_deref();
+            } else {
+                current = node.right.as_deref();
             }
         }
-        match &self.root {
-            Some(node) => search_node(node, &value),
-            None => false, // 空树直接返回 false
+        Range {
+            in_order: InOrder { stack },
+            range,
+        }
+    }
+
+    // Search for a value in the BST
+    fn search(&self, value: T) -> bool {
+        // 从根节点开始逐层向下查找目标值，空树直接返回 false
+        let mut current = self.root.as_deref();
+        while let Some(node) = current {
+            current = match node.value.cmp(&value) {
+                // 找到目标值，返回 true
+                Ordering::Equal => return true,
+                // 当前节点大于目标值，检查左子树
+                Ordering::Greater => node.left.as_deref(),
+                // 当前节点小于目标值，检查右子树
+                Ordering::Less => node.right.as_deref(),
+            };
+        }
+        false
+    }
+}
+
+// 默认的析构会沿 Box 递归，退化成链的树会栈溢出，这里用显式的栈逐个释放节点
+impl<T> Drop for BinarySearchTree<T>
+where
+    T: Ord,
+{
+    fn drop(&mut self) {
+        let mut stack: Vec<Box<TreeNode<T>>> = self.root.take().into_iter().collect();
+        while let Some(mut node) = stack.pop() {
+            stack.extend(node.left.take());
+            stack.extend(node.right.take());
         }
     }
 }
@@ -96,36 +281,557 @@ impl<T> TreeNode<T>
 where
     T: Ord,
 {
-    // Insert a node into the tree
-    fn insert(&mut self, value: T) {
-        match self.value.cmp(&value) {
-            Ordering::Greater => {
+    // Insert a node into the tree, returns whether the value was new
+    fn insert(&mut self, value: T) -> bool {
+        // 沿子树链接向下走，不递归，退化成链的树也不会栈溢出
+        let mut node = self;
+        loop {
+            let link = match node.value.cmp(&value) {
                 // 当前节点大于待插入值，则尝试插入到左子树中
-                if let Some(ref mut left) = self.left {
-                    left.insert(value);
-                } else {
-                    // 左子树为空，直接创建新节点
-                    self.left = Some(Box::new(TreeNode::new(value)));
-                }
+                Ordering::Greater => &mut node.left,
+                // 当前节点小于待插入值，则尝试插入到右子树中
+                Ordering::Less => &mut node.right,
+                // 值相同，不插入重复节点
+                Ordering::Equal => return false,
+            };
+            if link.is_none() {
+                // 子树为空，直接创建新节点
+                *link = Some(Box::new(TreeNode::new(value)));
+                return true;
+            }
+            node = link.as_mut().unwrap();
+        }
+    }
+}
+
+// 以下遍历都用显式的栈或队列代替递归，树很深时也不会栈溢出
+
+// 中序遍历：栈中保存尚未访问、左子树已入栈的节点
+struct InOrder<'a, T: Ord> {
+    stack: Vec<&'a TreeNode<T>>,
+}
+
+impl<'a, T: Ord> InOrder<'a, T> {
+    fn push_left_spine(&mut self, mut node: Option<&'a TreeNode<T>>) {
+        while let Some(current) = node {
+            self.stack.push(current);
+            node = current.left.as_deref();
+        }
+    }
+}
+
+impl<'a, T: Ord> Iterator for InOrder<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        let node = self.stack.pop()?;
+        self.push_left_spine(node.right.as_deref());
+        Some(&node.value)
+    }
+}
+
+// 前序遍历：先访问节点，再依次访问左、右子树
+struct PreOrder<'a, T: Ord> {
+    stack: Vec<&'a TreeNode<T>>,
+}
+
+impl<'a, T: Ord> Iterator for PreOrder<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        let node = self.stack.pop()?;
+        // 右子节点先入栈，保证左子树先被访问
+        self.stack.extend(node.right.as_deref());
+        self.stack.extend(node.left.as_deref());
+        Some(&node.value)
+    }
+}
+
+// 后序遍历：节点第一次出栈时展开子节点，第二次出栈时才访问
+struct PostOrder<'a, T: Ord> {
+    stack: Vec<(&'a TreeNode<T>, bool)>,
+}
+
+impl<'a, T: Ord> Iterator for PostOrder<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        loop {
+            let (node, expanded) = self.stack.pop()?;
+            if expanded {
+                return Some(&node.value);
+            }
+            self.stack.push((node, true));
+            self.stack
+                .extend(node.right.as_deref().map(|right| (right, false)));
+            self.stack
+                .extend(node.left.as_deref().map(|left| (left, false)));
+        }
+    }
+}
+
+// 层序遍历：按层从上到下、每层从左到右
+struct LevelOrder<'a, T: Ord> {
+    queue: VecDeque<&'a TreeNode<T>>,
+}
+
+impl<'a, T: Ord> Iterator for LevelOrder<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        let node = self.queue.pop_front()?;
+        self.queue.extend(node.left.as_deref());
+        self.queue.extend(node.right.as_deref());
+        Some(&node.value)
+    }
+}
+
+// 区间遍历：在中序遍历的基础上，超过上界后立即结束
+struct Range<'a, T: Ord, R> {
+    in_order: InOrder<'a, T>,
+    range: R,
+}
+
+impl<'a, T: Ord, R: RangeBounds<T>> Iterator for Range<'a, T, R> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        let node = self.in_order.stack.pop()?;
+        let below_end = match self.range.end_bound() {
+            Bound::Included(end) => node.value <= *end,
+            Bound::Excluded(end) => node.value < *end,
+            Bound::Unbounded => true,
+        };
+        if !below_end {
+            self.in_order.stack.clear();
+            return None;
+        }
+        // 右子树中的值都大于当前节点，自然也不小于下界
+        self.in_order.push_left_spine(node.right.as_deref());
+        Some(&node.value)
+    }
+}
+
+// 有序映射：AVL 树，任意节点左右子树的高度差不超过 1，因此高度为 O(log n)
+// 节点额外记录子树大小，用于按名次查找（nth）与求名次（rank_of）
+#[derive(Debug)]
+struct AvlNode<K, V>
+where
+    K: Ord,
+{
+    key: K,
+    value: V,
+    height: usize,
+    size: usize,
+    left: Option<Box<AvlNode<K, V>>>,
+    right: Option<Box<AvlNode<K, V>>>,
+}
+
+#[derive(Debug)]
+struct OrderedMap<K, V>
+where
+    K: Ord,
+{
+    root: Option<Box<AvlNode<K, V>>>,
+}
+
+impl<K, V> AvlNode<K, V>
+where
+    K: Ord,
+{
+    fn new(key: K, value: V) -> Self {
+        AvlNode {
+            key,
+            value,
+            height: 1,
+            size: 1,
+            left: None,
+            right: None,
+        }
+    }
+
+    fn height(node: &Option<Box<AvlNode<K, V>>>) -> usize {
+        node.as_ref().map_or(0, |node| node.height)
+    }
+
+    fn size(node: &Option<Box<AvlNode<K, V>>>) -> usize {
+        node.as_ref().map_or(0, |node| node.size)
+    }
+
+    // 子节点变化后重新计算高度与子树大小
+    fn update(&mut self) {
+        self.height = 1 + Self::height(&self.left).max(Self::height(&self.right));
+        self.size = 1 + Self::size(&self.left) + Self::size(&self.right);
+    }
+
+    //     node          left
+    //     /  \         /  \
+    //   left  c  ->   a   node
+    //   /  \             /  \
+    //  a    b           b    c
+    fn rotate_right(mut node: Box<Self>) -> Box<Self> {
+        let mut left = node.left.take().unwrap();
+        node.left = left.right.take();
+        node.update();
+        left.right = Some(node);
+        left.update();
+        left
+    }
+
+    fn rotate_left(mut node: Box<Self>) -> Box<Self> {
+        let mut right = node.right.take().unwrap();
+        node.right = right.left.take();
+        node.update();
+        right.left = Some(node);
+        right.update();
+        right
+    }
+
+    // 子树插入或删除一个节点后恢复平衡，最多旋转两次
+    fn rebalance(mut node: Box<Self>) -> Box<Self> {
+        node.update();
+        let left_height = Self::height(&node.left);
+        let right_height = Self::height(&node.right);
+        if left_height > right_height + 1 {
+            // 左子树的右侧更高时先左旋左子节点（LR 型），再整体右旋
+            let left = node.left.take().unwrap();
+            node.left = Some(if Self::height(&left.left) < Self::height(&left.right) {
+                Self::rotate_left(left)
+            } else {
+                left
+            });
+            return Self::rotate_right(node);
+        }
+        if right_height > left_height + 1 {
+            let right = node.right.take().unwrap();
+            node.right = Some(if Self::height(&right.right) < Self::height(&right.left) {
+                Self::rotate_right(right)
+            } else {
+                right
+            });
+            return Self::rotate_left(node);
+        }
+        node
+    }
+
+    // 插入后返回新的子树根以及被替换的旧值
+    fn insert(node: Option<Box<Self>>, key: K, value: V) -> (Box<Self>, Option<V>) {
+        let Some(mut node) = node else {
+            return (Box::new(AvlNode::new(key, value)), None);
+        };
+        match key.cmp(&node.key) {
+            Ordering::Less => {
+                let (left, old) = Self::insert(node.left.take(), key, value);
+                node.left = Some(left);
+                (Self::rebalance(node), old)
+            }
+            Ordering::Greater => {
+                let (right, old) = Self::insert(node.right.take(), key, value);
+                node.right = Some(right);
+                (Self::rebalance(node), old)
+            }
+            Ordering::Equal => {
+                let old = std::mem::replace(&mut node.value, value);
+                (node, Some(old))
             }
+        }
+    }
+
+    // 删除后返回新的子树根以及被删除的值
+    fn remove(node: Option<Box<Self>>, key: &K) -> (Option<Box<Self>>, Option<V>) {
+        let Some(mut node) = node else {
+            return (None, None);
+        };
+        match key.cmp(&node.key) {
             Ordering::Less => {
-                // 当前节点小于待插入值，则尝试插入到右子树中
-                if let Some(ref mut right) = self.right {
-                    right.insert(value);
-                } else {
-                    // 右子树为空，直接创建新节点
-                    self.right = Some(Box::new(TreeNode::new(value)));
-                }
+                let (left, removed) = Self::remove(node.left.take(), key);
+                node.left = left;
+                (Some(Self::rebalance(node)), removed)
+            }
+            Ordering::Greater => {
+                let (right, removed) = Self::remove(node.right.take(), key);
+                node.right = right;
+                (Some(Self::rebalance(node)), removed)
             }
             Ordering::Equal => {
-                // 值相同，不插入重复节点
+                let AvlNode {
+                    value, left, right, ..
+                } = *node;
+                let replacement = match (left, right) {
+                    (None, child) | (child, None) => child,
+                    // 有两个子节点时，用右子树中的最小节点（中序后继）顶替
+                    (Some(left), Some(right)) => {
+                        let (rest, mut successor) = Self::take_min(right);
+                        successor.left = Some(left);
+                        successor.right = rest;
+                        Some(Self::rebalance(successor))
+                    }
+                };
+                (replacement, Some(value))
+            }
+        }
+    }
+
+    // 摘下子树中的最小节点，返回剩余部分与该节点
+    fn take_min(mut node: Box<Self>) -> (Option<Box<Self>>, Box<Self>) {
+        match node.left.take() {
+            None => (node.right.take(), node),
+            Some(left) => {
+                let (rest, min) = Self::take_min(left);
+                node.left = rest;
+                (Some(Self::rebalance(node)), min)
+            }
+        }
+    }
+}
+
+impl<K, V> Default for OrderedMap<K, V>
+where
+    K: Ord,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V> OrderedMap<K, V>
+where
+    K: Ord,
+{
+    fn new() -> Self {
+        OrderedMap { root: None }
+    }
+
+    fn len(&self) -> usize {
+        AvlNode::size(&self.root)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+
+    fn height(&self) -> usize {
+        AvlNode::height(&self.root)
+    }
+
+    // 插入键值对，键已存在时替换并返回旧值
+    fn insert(&mut self, key: K, value: V) -> Option<V> {
+        let (root, old) = AvlNode::insert(self.root.take(), key, value);
+        self.root = Some(root);
+        old
+    }
+
+    fn remove(&mut self, key: &K) -> Option<V> {
+        let (root, removed) = AvlNode::remove(self.root.take(), key);
+        self.root = root;
+        removed
+    }
+
+    fn get(&self, key: &K) -> Option<&V> {
+        let mut current = self.root.as_deref();
+        while let Some(node) = current {
+            match key.cmp(&node.key) {
+                Ordering::Less => current = node.left.as_deref(),
+                Ordering::Greater => current = node.right.as_deref(),
+                Ordering::Equal => return Some(&node.value),
+            }
+        }
+        None
+    }
+
+    fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        let mut current = self.root.as_deref_mut();
+        while let Some(node) = current {
+            match key.cmp(&node.key) {
+                Ordering::Less => current = node.left.as_deref_mut(),
+                Ordering::Greater => current = node.right.as_deref_mut(),
+                Ordering::Equal => return Some(&mut node.value),
+            }
+        }
+        None
+    }
+
+    fn contains_key(&self, key: &K) -> bool {
+        self.get(key).is_some()
+    }
+
+    fn entry(&mut self, key: K) -> Entry<'_, K, V> {
+        if self.contains_key(&key) {
+            Entry::Occupied(OccupiedEntry { map: self, key })
+        } else {
+            Entry::Vacant(VacantEntry { map: self, key })
+        }
+    }
+
+    // 小于 key 的键的个数，即 key 在有序序列中的位置（从 0 开始）
+    fn rank_of(&self, key: &K) -> usize {
+        let mut rank = 0;
+        let mut current = self.root.as_deref();
+        while let Some(node) = current {
+            match key.cmp(&node.key) {
+                Ordering::Less => current = node.left.as_deref(),
+                Ordering::Greater => {
+                    rank += AvlNode::size(&node.left) + 1;
+                    current = node.right.as_deref();
+                }
+                Ordering::Equal => return rank + AvlNode::size(&node.left),
+            }
+        }
+        rank
+    }
+
+    // 第 n 小（从 0 开始）的键值对
+    fn nth(&self, mut n: usize) -> Option<(&K, &V)> {
+        let mut current = self.root.as_deref();
+        while let Some(node) = current {
+            let left_size = AvlNode::size(&node.left);
+            match n.cmp(&left_size) {
+                Ordering::Less => current = node.left.as_deref(),
+                Ordering::Equal => return Some((&node.key, &node.value)),
+                Ordering::Greater => {
+                    n -= left_size + 1;
+                    current = node.right.as_deref();
+                }
             }
         }
+        None
+    }
+
+    fn nth_mut(&mut self, mut n: usize) -> Option<&mut V> {
+        let mut current = self.root.as_deref_mut();
+        while let Some(node) = current {
+            let left_size = AvlNode::size(&node.left);
+            match n.cmp(&left_size) {
+                Ordering::Less => current = node.left.as_deref_mut(),
+                Ordering::Equal => return Some(&mut node.value),
+                Ordering::Greater => {
+                    n -= left_size + 1;
+                    current = node.right.as_deref_mut();
+                }
+            }
+        }
+        None
+    }
+
+    // 按键从小到大遍历
+    fn iter(&self) -> MapIter<'_, K, V> {
+        let mut iter = MapIter { stack: Vec::new() };
+        iter.push_left_spine(self.root.as_deref());
+        iter
+    }
+}
+
+struct MapIter<'a, K: Ord, V> {
+    stack: Vec<&'a AvlNode<K, V>>,
+}
+
+impl<'a, K: Ord, V> MapIter<'a, K, V> {
+    fn push_left_spine(&mut self, mut node: Option<&'a AvlNode<K, V>>) {
+        while let Some(current) = node {
+            self.stack.push(current);
+            node = current.left.as_deref();
+        }
+    }
+}
+
+impl<'a, K: Ord, V> Iterator for MapIter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<(&'a K, &'a V)> {
+        let node = self.stack.pop()?;
+        self.push_left_spine(node.right.as_deref());
+        Some((&node.key, &node.value))
+    }
+}
+
+// 某个键在映射中的位置，可以就地读取、修改或插入
+enum Entry<'a, K: Ord, V> {
+    Occupied(OccupiedEntry<'a, K, V>),
+    Vacant(VacantEntry<'a, K, V>),
+}
+
+struct OccupiedEntry<'a, K: Ord, V> {
+    map: &'a mut OrderedMap<K, V>,
+    key: K,
+}
+
+struct VacantEntry<'a, K: Ord, V> {
+    map: &'a mut OrderedMap<K, V>,
+    key: K,
+}
+
+impl<'a, K: Ord, V> Entry<'a, K, V> {
+    fn key(&self) -> &K {
+        match self {
+            Entry::Occupied(entry) => &entry.key,
+            Entry::Vacant(entry) => &entry.key,
+        }
+    }
+
+    fn or_insert(self, default: V) -> &'a mut V {
+        self.or_insert_with(|| default)
+    }
+
+    fn or_insert_with(self, default: impl FnOnce() -> V) -> &'a mut V {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default()),
+        }
+    }
+
+    fn or_default(self) -> &'a mut V
+    where
+        V: Default,
+    {
+        self.or_insert_with(V::default)
+    }
+
+    fn and_modify(self, f: impl FnOnce(&mut V)) -> Self {
+        match self {
+            Entry::Occupied(mut entry) => {
+                f(entry.get_mut());
+                Entry::Occupied(entry)
+            }
+            Entry::Vacant(entry) => Entry::Vacant(entry),
+        }
+    }
+}
+
+impl<'a, K: Ord, V> OccupiedEntry<'a, K, V> {
+    fn get(&self) -> &V {
+        self.map.get(&self.key).unwrap()
+    }
+
+    fn get_mut(&mut self) -> &mut V {
+        self.map.get_mut(&self.key).unwrap()
+    }
+
+    fn into_mut(self) -> &'a mut V {
+        self.map.get_mut(&self.key).unwrap()
+    }
+
+    fn remove(self) -> V {
+        self.map.remove(&self.key).unwrap()
     }
 }
 
+impl<'a, K: Ord, V> VacantEntry<'a, K, V> {
+    // 插入后键的名次就是插入前小于它的键的个数，据此取回新值的可变引用
+    fn insert(self, value: V) -> &'a mut V {
+        let rank = self.map.rank_of(&self.key);
+        self.map.insert(self.key, value);
+        self.map.nth_mut(rank).unwrap()
+    }
+}
+
+// 测试用的伪随机数生成器与其他习题共用，位于同目录下的 test_rng.rs
+#[cfg(test)]
+#[path = "test_rng.rs"]
+mod test_rng;
+
 #[cfg(test)]
 mod tests {
+    use super::test_rng::pseudo_random;
     use super::*;
 
     #[test]
@@ -167,4 +873,344 @@ mod tests {
             None => panic!("Root should not be None after insertion"),
         }
     }
+
+    //        8
+    //      /   \
+    //     4     12
+    //    / \   /  \
+    //   2   6 10  14
+    //        \
+    //         7
+    fn sample_tree() -> BinarySearchTree<i32> {
+        let mut bst = BinarySearchTree::new();
+        for value in [8, 4, 12, 2, 6, 10, 14, 7] {
+            bst.insert(value);
+        }
+        bst
+    }
+
+    #[test]
+    fn test_traversal_orders() {
+        let bst = sample_tree();
+        let collect = |iter: &mut dyn Iterator<Item = &i32>| iter.copied().collect::<Vec<_>>();
+        assert_eq!(
+            collect(&mut bst.in_order()),
+            vec![2, 4, 6, 7, 8, 10, 12, 14]
+        );
+        assert_eq!(collect(&mut bst.iter()), vec![2, 4, 6, 7, 8, 10, 12, 14]);
+        assert_eq!(
+            collect(&mut bst.pre_order()),
+            vec![8, 4, 2, 6, 7, 12, 10, 14]
+        );
+        assert_eq!(
+            collect(&mut bst.post_order()),
+            vec![2, 7, 6, 4, 10, 14, 12, 8]
+        );
+        assert_eq!(
+            collect(&mut bst.level_order()),
+            vec![8, 4, 12, 2, 6, 10, 14, 7]
+        );
+
+        let empty = BinarySearchTree::<i32>::new();
+        assert_eq!(empty.in_order().next(), None);
+        assert_eq!(empty.pre_order().next(), None);
+        assert_eq!(empty.post_order().next(), None);
+        assert_eq!(empty.level_order().next(), None);
+    }
+
+    #[test]
+    fn test_min_max_floor_ceiling() {
+        let bst = sample_tree();
+        assert_eq!(bst.min(), Some(&2));
+        assert_eq!(bst.max(), Some(&14));
+        assert_eq!(bst.floor(&9), Some(&8));
+        assert_eq!(bst.floor(&7), Some(&7));
+        assert_eq!(bst.floor(&1), None);
+        assert_eq!(bst.ceiling(&9), Some(&10));
+        assert_eq!(bst.ceiling(&5), Some(&6));
+        assert_eq!(bst.ceiling(&15), None);
+
+        let empty = BinarySearchTree::<i32>::new();
+        assert_eq!((empty.min(), empty.max()), (None, None));
+        assert_eq!((empty.floor(&1), empty.ceiling(&1)), (None, None));
+    }
+
+    #[test]
+    fn test_range() {
+        let bst = sample_tree();
+        let range = |values: Vec<&i32>| values.into_iter().copied().collect::<Vec<_>>();
+        assert_eq!(range(bst.range(5..12).collect()), vec![6, 7, 8, 10]);
+        assert_eq!(range(bst.range(4..=12).collect()), vec![4, 6, 7, 8, 10, 12]);
+        assert_eq!(range(bst.range(..7).collect()), vec![2, 4, 6]);
+        assert_eq!(range(bst.range(11..).collect()), vec![12, 14]);
+        assert_eq!(range(bst.range(..).collect()).len(), 8);
+        assert_eq!(bst.range(15..20).next(), None);
+        assert_eq!(
+            range(
+                bst.range((Bound::Excluded(4), Bound::Excluded(8)))
+                    .collect()
+            ),
+            vec![6, 7]
+        );
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut bst = sample_tree();
+        assert_eq!(bst.len(), 8);
+        assert_eq!(bst.height(), 4);
+
+        // 叶子节点
+        assert!(bst.remove(&2));
+        // 只有一个子节点
+        assert!(bst.remove(&6));
+        // 两个子节点：由中序后继 14 顶替
+        assert!(bst.remove(&12));
+        // 根节点：由中序后继 10 顶替
+        assert!(bst.remove(&8));
+        assert!(!bst.remove(&8));
+        assert!(!bst.search(8));
+
+        assert_eq!(bst.iter().copied().collect::<Vec<_>>(), vec![4, 7, 10, 14]);
+        assert_eq!(
+            bst.pre_order().copied().collect::<Vec<_>>(),
+            vec![10, 4, 7, 14]
+        );
+        assert_eq!(bst.len(), 4);
+        assert_eq!(bst.height(), 3);
+
+        for value in [4, 7, 10, 14] {
+            assert!(bst.remove(&value));
+        }
+        assert!(bst.is_empty());
+        assert_eq!(bst.height(), 0);
+        assert!(bst.root.is_none());
+    }
+
+    #[test]
+    fn test_matches_btreeset_model() {
+        use std::collections::BTreeSet;
+
+        let mut seed: u64 = 17;
+        let mut bst = BinarySearchTree::new();
+        let mut model = BTreeSet::new();
+        for _ in 0..2000 {
+            let value = (pseudo_random(&mut seed) % 200) as i32;
+            match pseudo_random(&mut seed) % 3 {
+                0 => assert_eq!(bst.remove(&value), model.remove(&value)),
+                _ => {
+                    bst.insert(value);
+                    model.insert(value);
+                }
+            }
+            assert_eq!(bst.len(), model.len());
+        }
+        assert!(bst.iter().eq(model.iter()));
+        assert_eq!(bst.min(), model.first());
+        assert_eq!(bst.max(), model.last());
+        for probe in -5..205 {
+            assert_eq!(bst.floor(&probe), model.range(..=probe).next_back());
+            assert_eq!(bst.ceiling(&probe), model.range(probe..).next());
+            assert!(bst
+                .range(probe..probe + 30)
+                .eq(model.range(probe..probe + 30)));
+        }
+        assert_eq!(bst.pre_order().count(), model.len());
+        assert_eq!(bst.post_order().count(), model.len());
+        assert_eq!(bst.level_order().count(), model.len());
+    }
+
+    #[test]
+    fn test_iterators_handle_degenerate_trees() {
+        // 直接拼出一条只有右孩子的链（顺序插入得到的形状），高度等于节点数；
+        // 递归的插入、查找或析构在这个深度都会栈溢出
+        const DEPTH: i32 = 200_000;
+        let mut root = None;
+        for value in (0..DEPTH).rev() {
+            let mut node = TreeNode::new(value);
+            node.right = root;
+            root = Some(Box::new(node));
+        }
+        let mut bst = BinarySearchTree {
+            root,
+            len: DEPTH as usize,
+        };
+        bst.insert(DEPTH);
+        assert_eq!(bst.len(), DEPTH as usize + 1);
+        assert_eq!(bst.height(), DEPTH as usize + 1);
+        assert!(bst.search(DEPTH));
+        assert!(!bst.search(-1));
+        assert!(bst.in_order().copied().eq(0..=DEPTH));
+        assert!(bst.pre_order().copied().eq(0..=DEPTH));
+        assert!(bst.post_order().copied().eq((0..=DEPTH).rev()));
+        assert!(bst.level_order().copied().eq(0..=DEPTH));
+        assert!(bst.range(DEPTH - 10..).copied().eq(DEPTH - 10..=DEPTH));
+    }
+
+    // 递归检查有序性、平衡因子以及记录的高度和子树大小，返回 (高度, 大小)
+    fn check_avl<K: Ord + Debug, V>(
+        node: &Option<Box<AvlNode<K, V>>>,
+        lower: Option<&K>,
+        upper: Option<&K>,
+    ) -> (usize, usize) {
+        let Some(node) = node else {
+            return (0, 0);
+        };
+        assert!(lower.is_none_or(|lower| *lower < node.key));
+        assert!(upper.is_none_or(|upper| node.key < *upper));
+        let (left_height, left_size) = check_avl(&node.left, lower, Some(&node.key));
+        let (right_height, right_size) = check_avl(&node.right, Some(&node.key), upper);
+        assert!(
+            left_height.abs_diff(right_height) <= 1,
+            "unbalanced at {:?}: {} vs {}",
+            node.key,
+            left_height,
+            right_height
+        );
+        let height = 1 + left_height.max(right_height);
+        let size = 1 + left_size + right_size;
+        assert_eq!(node.height, height);
+        assert_eq!(node.size, size);
+        (height, size)
+    }
+
+    fn assert_avl<K: Ord + Debug, V>(map: &OrderedMap<K, V>) {
+        let (height, size) = check_avl(&map.root, None, None);
+        assert_eq!(map.height(), height);
+        assert_eq!(map.len(), size);
+    }
+
+    #[test]
+    fn test_ordered_map_basic() {
+        let mut map = OrderedMap::new();
+        assert!(map.is_empty());
+        assert_eq!(map.insert(5, "five"), None);
+        assert_eq!(map.insert(3, "three"), None);
+        assert_eq!(map.insert(8, "eight"), None);
+        assert_eq!(map.insert(3, "THREE"), Some("three"));
+        assert_avl(&map);
+        assert_eq!(map.len(), 3);
+        assert_eq!(map.get(&3), Some(&"THREE"));
+        assert_eq!(map.get(&4), None);
+        assert!(map.contains_key(&8));
+
+        *map.get_mut(&8).unwrap() = "EIGHT";
+        assert!(map
+            .iter()
+            .eq([(&3, &"THREE"), (&5, &"five"), (&8, &"EIGHT")]));
+
+        assert_eq!(map.remove(&5), Some("five"));
+        assert_eq!(map.remove(&5), None);
+        assert_avl(&map);
+        assert_eq!(map.remove(&3), Some("THREE"));
+        assert_eq!(map.remove(&8), Some("EIGHT"));
+        assert!(map.is_empty());
+        assert_eq!(map.height(), 0);
+    }
+
+    #[test]
+    fn test_ordered_map_sorted_inserts_stay_balanced() {
+        let mut map = OrderedMap::default();
+        let n = 100_000;
+        for key in 0..n {
+            map.insert(key, key * 2);
+        }
+        assert_avl(&map);
+        // AVL 树的高度不超过 1.44·log2(n + 2)
+        let bound = 1.44 * ((n + 2) as f64).log2();
+        assert!((map.height() as f64) <= bound, "height {}", map.height());
+
+        for key in (0..n).rev().filter(|key| key % 3 != 0) {
+            assert_eq!(map.remove(&key), Some(key * 2));
+        }
+        assert_avl(&map);
+        assert!(map.iter().map(|(key, _)| *key).eq((0..n).step_by(3)));
+    }
+
+    #[test]
+    fn test_ordered_map_rank_and_select() {
+        let map: OrderedMap<i32, ()> = {
+            let mut map = OrderedMap::new();
+            for key in (0..100).map(|key| key * 10) {
+                map.insert(key, ());
+            }
+            map
+        };
+        for index in 0..100 {
+            let key = index as i32 * 10;
+            assert_eq!(map.nth(index), Some((&key, &())));
+            assert_eq!(map.rank_of(&key), index);
+            // 不存在的键返回它插入后会处于的位置
+            assert_eq!(map.rank_of(&(key + 5)), index + 1);
+        }
+        assert_eq!(map.rank_of(&-1), 0);
+        assert_eq!(map.nth(100), None);
+    }
+
+    #[test]
+    fn test_ordered_map_entry() {
+        let mut counts: OrderedMap<&str, usize> = OrderedMap::new();
+        for word in "the quick brown fox jumps over the lazy dog the end".split(' ') {
+            *counts.entry(word).or_insert(0) += 1;
+        }
+        assert_avl(&counts);
+        assert_eq!(counts.get(&"the"), Some(&3));
+        assert_eq!(counts.get(&"fox"), Some(&1));
+        assert_eq!(counts.len(), 9);
+
+        assert_eq!(counts.entry("fox").key(), &"fox");
+        counts
+            .entry("fox")
+            .and_modify(|count| *count += 10)
+            .or_default();
+        counts
+            .entry("cat")
+            .and_modify(|count| *count += 10)
+            .or_default();
+        assert_eq!(counts.get(&"fox"), Some(&11));
+        assert_eq!(counts.get(&"cat"), Some(&0));
+        assert_eq!(*counts.entry("owl").or_insert_with(|| 7), 7);
+
+        match counts.entry("the") {
+            Entry::Occupied(mut entry) => {
+                assert_eq!(*entry.get(), 3);
+                *entry.get_mut() = 30;
+                assert_eq!(entry.remove(), 30);
+            }
+            Entry::Vacant(_) => panic!("expected an occupied entry"),
+        }
+        assert!(!counts.contains_key(&"the"));
+        match counts.entry("the") {
+            Entry::Vacant(entry) => *entry.insert(1) += 1,
+            Entry::Occupied(_) => panic!("expected a vacant entry"),
+        }
+        assert_eq!(counts.get(&"the"), Some(&2));
+        assert_avl(&counts);
+    }
+
+    #[test]
+    fn test_ordered_map_matches_btreemap_model() {
+        use std::collections::BTreeMap;
+
+        let mut seed: u64 = 29;
+        let mut map = OrderedMap::new();
+        let mut model = BTreeMap::new();
+        for step in 0..3000 {
+            let key = (pseudo_random(&mut seed) % 300) as i32;
+            match pseudo_random(&mut seed) % 4 {
+                0 => assert_eq!(map.remove(&key), model.remove(&key)),
+                1 => {
+                    *map.entry(key).or_insert(0) += step;
+                    *model.entry(key).or_insert(0) += step;
+                }
+                _ => assert_eq!(map.insert(key, step), model.insert(key, step)),
+            }
+            // 每次修改之后都检查不变量
+            assert_avl(&map);
+            let rank = model.range(..key).count();
+            assert_eq!(map.rank_of(&key), rank);
+            assert_eq!(map.nth(rank), model.range(key..).next());
+        }
+        assert!(map.iter().eq(model.iter()));
+    }
 }
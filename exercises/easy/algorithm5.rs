@@ -7,57 +7,11 @@
     这个问题要求你实现一个基本的广度优先搜索（BFS）算法
 */
 
-use std::collections::VecDeque;
+// 图的定义与各种图算法位于同目录下的 graph.rs，与 algorithm6 共用
+#[path = "graph.rs"]
+mod graph;
 
-// Define a graph
-struct Graph {
-    adj: Vec<Vec<usize>>,
-}
-
-impl Graph {
-    // Create a new graph with n vertices
-    fn new(n: usize) -> Self {
-        Graph {
-            adj: vec![vec![]; n],
-        }
-    }
-
-    // Add an edge to the graph
-    fn add_edge(&mut self, src: usize, dest: usize) {
-        self.adj[src].push(dest);
-        self.adj[dest].push(src);
-    }
-
-    // Perform a breadth-first search on the graph, return the order of visited nodes
-    fn bfs_with_return(&self, start: usize) -> Vec<usize> {
-        // 初始化访问标记数组，记录每个节点是否已访问
-        let mut visited = vec![false; self.adj.len()];
-        // 创建队列用于存储待访问的节点
-        let mut queue = VecDeque::new();
-        // 存储遍历过程中访问的节点顺序
-        let mut visit_order = vec![];
-
-        // 标记起始节点为已访问，并将其加入队列
-        visited[start] = true;
-        queue.push_back(start);
-
-        // 当队列不为空时，持续进行遍历
-        while let Some(node) = queue.pop_front() {
-            // 记录当前访问的节点
-            visit_order.push(node);
-            // 遍历当前节点的所有相邻节点
-            for &neighbor in &self.adj[node] {
-                // 如果相邻节点未被访问，则标记为已访问并加入队列
-                if !visited[neighbor] {
-                    visited[neighbor] = true;
-                    queue.push_back(neighbor);
-                }
-            }
-        }
-        // 返回所有访问过节点的顺序
-        visit_order
-    }
-}
+use graph::Graph;
 
 #[cfg(test)]
 mod tests {
@@ -106,4 +60,93 @@ mod tests {
         let visited_order = graph.bfs_with_return(0);
         assert_eq!(visited_order, vec![0]);
     }
+
+    #[test]
+    fn test_bfs_follows_edge_direction() {
+        let mut graph = Graph::new_directed(4);
+        graph.add_edge(0, 1);
+        graph.add_edge(2, 0);
+        graph.add_edge(1, 3);
+        assert!(graph.is_directed());
+        assert_eq!(graph.vertex_count(), 4);
+        assert_eq!(graph.neighbors(0), &[1]);
+
+        assert_eq!(graph.bfs_with_return(0), vec![0, 1, 3]);
+        assert_eq!(graph.bfs_with_return(2), vec![2, 0, 1, 3]);
+    }
+
+    #[test]
+    fn test_shortest_path() {
+        // 0 - 1 - 2 - 3 与捷径 0 - 4 - 3
+        let mut graph = Graph::new(6);
+        graph.add_edge(0, 1);
+        graph.add_edge(1, 2);
+        graph.add_edge(2, 3);
+        graph.add_edge(0, 4);
+        graph.add_edge(4, 3);
+
+        assert_eq!(graph.shortest_path(0, 3), Some(vec![0, 4, 3]));
+        assert_eq!(graph.shortest_path(2, 2), Some(vec![2]));
+        assert_eq!(graph.shortest_path(1, 4), Some(vec![1, 0, 4]));
+        assert_eq!(graph.shortest_path(0, 5), None);
+
+        let mut directed = Graph::new_directed(3);
+        directed.add_edge(0, 1);
+        directed.add_edge(1, 2);
+        assert_eq!(directed.shortest_path(0, 2), Some(vec![0, 1, 2]));
+        assert_eq!(directed.shortest_path(2, 0), None);
+    }
+
+    #[test]
+    fn test_connected_components() {
+        let mut graph = Graph::new(7);
+        graph.add_edge(3, 0);
+        graph.add_edge(0, 5);
+        graph.add_edge(2, 4);
+        assert_eq!(
+            graph.connected_components(),
+            vec![vec![0, 3, 5], vec![1], vec![2, 4], vec![6]]
+        );
+
+        // 有向图按弱连通计算
+        let mut directed = Graph::new_directed(4);
+        directed.add_edge(1, 0);
+        directed.add_edge(2, 0);
+        assert_eq!(
+            directed.connected_components(),
+            vec![vec![0, 1, 2], vec![3]]
+        );
+    }
+
+    #[test]
+    fn test_bipartite() {
+        // 偶环可以二染色
+        let mut square = Graph::new(4);
+        for vertex in 0..4 {
+            square.add_edge(vertex, (vertex + 1) % 4);
+        }
+        let coloring = square.two_coloring().unwrap();
+        for vertex in 0..4 {
+            for &neighbor in square.neighbors(vertex) {
+                assert_ne!(coloring[vertex], coloring[neighbor]);
+            }
+        }
+
+        // 奇环不能
+        let mut triangle = Graph::new(5);
+        triangle.add_edge(3, 4);
+        triangle.add_edge(0, 1);
+        triangle.add_edge(1, 2);
+        triangle.add_edge(2, 0);
+        assert!(!triangle.is_bipartite());
+        assert_eq!(triangle.two_coloring(), None);
+
+        // 有向图忽略方向
+        let mut directed = Graph::new_directed(3);
+        directed.add_edge(0, 1);
+        directed.add_edge(2, 1);
+        directed.add_edge(2, 0);
+        assert!(!directed.is_bipartite());
+        assert!(Graph::new(3).is_bipartite());
+    }
 }
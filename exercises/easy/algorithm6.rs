@@ -7,48 +7,20 @@
     这个问题要求你实现一个基本的深度优先搜索遍历
 */
 
-use std::collections::HashSet;
+// 图的定义与各种图算法位于同目录下的 graph.rs，与 algorithm5 共用
+#[path = "graph.rs"]
+mod graph;
 
-struct Graph {
-    adj: Vec<Vec<usize>>,
-}
+use graph::Graph;
 
-impl Graph {
-    fn new(n: usize) -> Self {
-        Graph {
-            adj: vec![vec![]; n],
-        }
-    }
-
-    fn add_edge(&mut self, src: usize, dest: usize) {
-        self.adj[src].push(dest);
-        self.adj[dest].push(src);
-    }
-
-    fn dfs_util(&self, v: usize, visited: &mut HashSet<usize>, visit_order: &mut Vec<usize>) {
-        // 标记节点已访问
-        visited.insert(v);
-        // 添加节点到访问序列
-        visit_order.push(v);
-        for &neighbor in self.adj[v].iter() {
-            if !visited.contains(&neighbor) {
-                // 若邻居未访问，则递归调用
-                self.dfs_util(neighbor, visited, visit_order);
-            }
-        }
-    }
-
-    // Perform a depth-first search on the graph, return the order of visited nodes
-    fn dfs(&self, start: usize) -> Vec<usize> {
-        let mut visited = HashSet::new();
-        let mut visit_order = Vec::new();
-        self.dfs_util(start, &mut visited, &mut visit_order);
-        visit_order
-    }
-}
+// 测试用的伪随机数生成器与其他习题共用，位于同目录下的 test_rng.rs
+#[cfg(test)]
+#[path = "test_rng.rs"]
+mod test_rng;
 
 #[cfg(test)]
 mod tests {
+    use super::test_rng::pseudo_random;
     use super::*;
 
     #[test]
@@ -86,4 +58,257 @@ mod tests {
         let visit_order_disconnected = graph.dfs(3);
         assert_eq!(visit_order_disconnected, vec![3, 4]);
     }
+
+    #[test]
+    fn test_dfs_deep_graph() {
+        // 递归实现在这样长的链上会栈溢出
+        let n = 200_000;
+        let mut graph = Graph::new(n);
+        for vertex in 1..n {
+            graph.add_edge(vertex - 1, vertex);
+        }
+        assert!(graph.dfs(0).into_iter().eq(0..n));
+        assert!(!graph.has_cycle());
+        assert_eq!(graph.bridges().len(), n - 1);
+        assert_eq!(graph.articulation_points(), (1..n - 1).collect::<Vec<_>>());
+
+        let mut directed = Graph::new_directed(n);
+        for vertex in 1..n {
+            directed.add_edge(vertex - 1, vertex);
+        }
+        directed.add_edge(n - 1, 0);
+        assert_eq!(directed.find_cycle().map(|cycle| cycle.len()), Some(n));
+        assert_eq!(directed.strongly_connected_components().len(), 1);
+        assert_eq!(directed.topological_sort_dfs(), None);
+    }
+
+    #[test]
+    fn test_find_cycle_undirected() {
+        let mut tree = Graph::new(5);
+        tree.add_edge(0, 1);
+        tree.add_edge(0, 2);
+        tree.add_edge(2, 3);
+        assert_eq!(tree.find_cycle(), None);
+
+        tree.add_edge(3, 0);
+        assert_eq!(tree.find_cycle(), Some(vec![0, 2, 3]));
+
+        // 自环与平行边
+        let mut self_loop = Graph::new(2);
+        self_loop.add_edge(1, 1);
+        assert_eq!(self_loop.find_cycle(), Some(vec![1]));
+        let mut parallel = Graph::new(2);
+        parallel.add_edge(0, 1);
+        parallel.add_edge(1, 0);
+        assert_eq!(parallel.find_cycle(), Some(vec![0, 1]));
+    }
+
+    #[test]
+    fn test_find_cycle_directed() {
+        // 0 -> 1 -> 2 与 0 -> 2 在无向图中成环，在有向图中不成环
+        let mut graph = Graph::new_directed(5);
+        graph.add_edge(0, 1);
+        graph.add_edge(1, 2);
+        graph.add_edge(0, 2);
+        graph.add_edge(3, 4);
+        assert!(!graph.has_cycle());
+
+        graph.add_edge(2, 3);
+        graph.add_edge(4, 1);
+        let cycle = graph.find_cycle().unwrap();
+        assert_eq!(cycle, vec![1, 2, 3, 4]);
+        for (index, &vertex) in cycle.iter().enumerate() {
+            let next = cycle[(index + 1) % cycle.len()];
+            assert!(graph.neighbors(vertex).contains(&next));
+        }
+    }
+
+    fn assert_topological(graph: &Graph, order: &[usize]) {
+        let mut position = vec![usize::MAX; graph.vertex_count()];
+        for (index, &vertex) in order.iter().enumerate() {
+            position[vertex] = index;
+        }
+        assert!(position.iter().all(|&index| index != usize::MAX));
+        for vertex in 0..graph.vertex_count() {
+            for &next in graph.neighbors(vertex) {
+                assert!(position[vertex] < position[next]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_topological_sort() {
+        let mut graph = Graph::new_directed(6);
+        graph.add_edge(5, 2);
+        graph.add_edge(5, 0);
+        graph.add_edge(4, 0);
+        graph.add_edge(4, 1);
+        graph.add_edge(2, 3);
+        graph.add_edge(3, 1);
+
+        let kahn = graph.topological_sort_kahn().unwrap();
+        assert_eq!(kahn, vec![4, 5, 0, 2, 3, 1]);
+        assert_topological(&graph, &kahn);
+        let dfs = graph.topological_sort_dfs().unwrap();
+        assert_topological(&graph, &dfs);
+
+        // 同时可选时先取编号小的顶点，而不是先入队的
+        let mut ties = Graph::new_directed(4);
+        ties.add_edge(0, 3);
+        ties.add_edge(1, 2);
+        assert_eq!(ties.topological_sort_kahn().unwrap(), vec![0, 1, 2, 3]);
+
+        graph.add_edge(1, 5);
+        assert_eq!(graph.topological_sort_kahn(), None);
+        assert_eq!(graph.topological_sort_dfs(), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "topological sort requires a directed graph")]
+    fn test_topological_sort_rejects_undirected() {
+        Graph::new(2).topological_sort_kahn();
+    }
+
+    #[test]
+    fn test_strongly_connected_components() {
+        let mut graph = Graph::new_directed(8);
+        for (src, dest) in [
+            (0, 1),
+            (1, 2),
+            (2, 0),
+            (2, 3),
+            (3, 4),
+            (4, 5),
+            (5, 3),
+            (6, 5),
+            (6, 7),
+            (7, 6),
+        ] {
+            graph.add_edge(src, dest);
+        }
+        // 逆拓扑序：没有出边的分量先给出
+        assert_eq!(
+            graph.strongly_connected_components(),
+            vec![vec![3, 4, 5], vec![0, 1, 2], vec![6, 7]]
+        );
+    }
+
+    #[test]
+    fn test_bridges_and_articulation_points() {
+        // 两个三角形 0-1-2 与 3-4-5 由桥 2-3 相连，5-6 是悬挂的桥
+        let mut graph = Graph::new(8);
+        for (src, dest) in [
+            (0, 1),
+            (1, 2),
+            (2, 0),
+            (2, 3),
+            (3, 4),
+            (4, 5),
+            (5, 3),
+            (5, 6),
+        ] {
+            graph.add_edge(src, dest);
+        }
+        assert_eq!(graph.bridges(), vec![(2, 3), (5, 6)]);
+        assert_eq!(graph.articulation_points(), vec![2, 3, 5]);
+
+        // 平行边不是桥
+        let mut parallel = Graph::new(3);
+        parallel.add_edge(0, 1);
+        parallel.add_edge(0, 1);
+        parallel.add_edge(1, 2);
+        assert_eq!(
+            parallel.bridges_and_articulation_points(),
+            (vec![(1, 2)], vec![1])
+        );
+    }
+
+    #[test]
+    fn test_matches_brute_force_on_random_graphs() {
+        let mut seed: u64 = 7;
+        for _ in 0..200 {
+            let n = 1 + (pseudo_random(&mut seed) % 9) as usize;
+            let edges: Vec<(usize, usize)> = (0..pseudo_random(&mut seed) % 12)
+                .map(|_| {
+                    let src = (pseudo_random(&mut seed) % n as u64) as usize;
+                    (src, (pseudo_random(&mut seed) % n as u64) as usize)
+                })
+                .collect();
+            let build = |directed: bool, skip_edge: Option<usize>, skip_vertex: Option<usize>| {
+                let mut graph = if directed {
+                    Graph::new_directed(n)
+                } else {
+                    Graph::new(n)
+                };
+                for (index, &(src, dest)) in edges.iter().enumerate() {
+                    if Some(index) != skip_edge
+                        && skip_vertex != Some(src)
+                        && skip_vertex != Some(dest)
+                    {
+                        graph.add_edge(src, dest);
+                    }
+                }
+                graph
+            };
+
+            // 删掉桥会增加连通分量；删掉割点后除它自己单独成为一个分量外，其余部分也会断开
+            let graph = build(false, None, None);
+            let components = graph.connected_components().len();
+            let mut bridges: Vec<(usize, usize)> = (0..edges.len())
+                .filter(|&index| {
+                    build(false, Some(index), None).connected_components().len() > components
+                })
+                .map(|index| {
+                    (
+                        edges[index].0.min(edges[index].1),
+                        edges[index].0.max(edges[index].1),
+                    )
+                })
+                .collect();
+            bridges.sort_unstable();
+            let articulation_points: Vec<usize> = (0..n)
+                .filter(|&vertex| {
+                    build(false, None, Some(vertex))
+                        .connected_components()
+                        .len()
+                        > components + 1
+                })
+                .collect();
+            assert_eq!(
+                graph.bridges_and_articulation_points(),
+                (bridges, articulation_points)
+            );
+
+            // 同一强连通分量中的顶点两两可达
+            let directed = build(true, None, None);
+            let reachable: Vec<Vec<bool>> = (0..n)
+                .map(|vertex| {
+                    let mut row = vec![false; n];
+                    for reached in directed.dfs(vertex) {
+                        row[reached] = true;
+                    }
+                    row
+                })
+                .collect();
+            let mut expected: Vec<Vec<usize>> = vec![];
+            for (vertex, row) in reachable.iter().enumerate() {
+                if expected.iter().any(|component| component.contains(&vertex)) {
+                    continue;
+                }
+                expected.push(
+                    (0..n)
+                        .filter(|&other| row[other] && reachable[other][vertex])
+                        .collect(),
+                );
+            }
+            let mut components = directed.strongly_connected_components();
+            components.sort();
+            assert_eq!(components, expected);
+
+            let acyclic = expected.len() == n && edges.iter().all(|(src, dest)| src != dest);
+            assert_eq!(directed.has_cycle(), !acyclic);
+            assert_eq!(directed.topological_sort_kahn().is_some(), acyclic);
+            assert_eq!(directed.topological_sort_dfs().is_some(), acyclic);
+        }
+    }
 }
@@ -0,0 +1,490 @@
+/*
+    graph
+    Adjacency-list graph shared by algorithm5 (bfs) and algorithm6 (dfs)
+*/
+/*
+    图
+    algorithm5（bfs）与 algorithm6（dfs）共用的邻接表图
+*/
+
+// 两道题各自只用到其中一部分算法
+#![allow(dead_code)]
+
+use std::borrow::Cow;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, VecDeque};
+
+pub struct Graph {
+    adj: Vec<Vec<usize>>,
+    directed: bool,
+}
+
+// 迭代 DFS 中模拟递归调用的栈帧
+struct Frame {
+    vertex: usize,
+    // 到达该顶点所经过的边的起点
+    parent: Option<usize>,
+    // 下一个待检查的邻居下标
+    next: usize,
+    // 无向图中回到 parent 的那条边只跳过一次，其余的是平行边
+    skipped_parent: bool,
+}
+
+impl Frame {
+    fn new(vertex: usize, parent: Option<usize>) -> Self {
+        Frame {
+            vertex,
+            parent,
+            next: 0,
+            skipped_parent: false,
+        }
+    }
+
+    fn advance(&mut self, neighbors: &[usize]) -> Option<usize> {
+        while let Some(&neighbor) = neighbors.get(self.next) {
+            self.next += 1;
+            if Some(neighbor) == self.parent && !self.skipped_parent {
+                self.skipped_parent = true;
+                continue;
+            }
+            return Some(neighbor);
+        }
+        None
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Color {
+    White,
+    Gray,
+    Black,
+}
+
+impl Graph {
+    // 含 n 个顶点的无向图
+    pub fn new(n: usize) -> Self {
+        Graph {
+            adj: vec![vec![]; n],
+            directed: false,
+        }
+    }
+
+    // 含 n 个顶点的有向图
+    pub fn new_directed(n: usize) -> Self {
+        Graph {
+            adj: vec![vec![]; n],
+            directed: true,
+        }
+    }
+
+    pub fn vertex_count(&self) -> usize {
+        self.adj.len()
+    }
+
+    pub fn is_directed(&self) -> bool {
+        self.directed
+    }
+
+    pub fn neighbors(&self, vertex: usize) -> &[usize] {
+        &self.adj[vertex]
+    }
+
+    // 无向图同时记录两个方向，自环会在邻接表中出现两次
+    pub fn add_edge(&mut self, src: usize, dest: usize) {
+        self.adj[src].push(dest);
+        if !self.directed {
+            self.adj[dest].push(src);
+        }
+    }
+
+    // 忽略方向后的邻接表，无向图直接借用
+    fn undirected_adj(&self) -> Cow<'_, [Vec<usize>]> {
+        if !self.directed {
+            return Cow::Borrowed(&self.adj);
+        }
+        let mut adj = vec![vec![]; self.adj.len()];
+        for (src, neighbors) in self.adj.iter().enumerate() {
+            for &dest in neighbors {
+                adj[src].push(dest);
+                adj[dest].push(src);
+            }
+        }
+        Cow::Owned(adj)
+    }
+
+    // 广度优先遍历，返回访问顺序
+    pub fn bfs_with_return(&self, start: usize) -> Vec<usize> {
+        let mut visited = vec![false; self.adj.len()];
+        let mut queue = VecDeque::new();
+        let mut visit_order = vec![];
+
+        visited[start] = true;
+        queue.push_back(start);
+        while let Some(node) = queue.pop_front() {
+            visit_order.push(node);
+            for &neighbor in &self.adj[node] {
+                if !visited[neighbor] {
+                    visited[neighbor] = true;
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+        visit_order
+    }
+
+    // 深度优先遍历，返回访问顺序；用显式栈代替递归，很深的图也不会栈溢出
+    pub fn dfs(&self, start: usize) -> Vec<usize> {
+        let mut visited = vec![false; self.adj.len()];
+        let mut visit_order = vec![start];
+        let mut stack = vec![Frame::new(start, None)];
+        visited[start] = true;
+
+        while let Some(frame) = stack.last_mut() {
+            match frame.advance(&self.adj[frame.vertex]) {
+                Some(neighbor) if !visited[neighbor] => {
+                    visited[neighbor] = true;
+                    visit_order.push(neighbor);
+                    stack.push(Frame::new(neighbor, None));
+                }
+                Some(_) => {}
+                None => {
+                    stack.pop();
+                }
+            }
+        }
+        visit_order
+    }
+
+    // 从 start 到 goal 边数最少的路径，包含两个端点
+    pub fn shortest_path(&self, start: usize, goal: usize) -> Option<Vec<usize>> {
+        let mut parent = vec![None; self.adj.len()];
+        let mut visited = vec![false; self.adj.len()];
+        let mut queue = VecDeque::new();
+
+        visited[start] = true;
+        queue.push_back(start);
+        while let Some(node) = queue.pop_front() {
+            if node == goal {
+                let mut path = vec![goal];
+                let mut current = goal;
+                while let Some(previous) = parent[current] {
+                    path.push(previous);
+                    current = previous;
+                }
+                path.reverse();
+                return Some(path);
+            }
+            for &neighbor in &self.adj[node] {
+                if !visited[neighbor] {
+                    visited[neighbor] = true;
+                    parent[neighbor] = Some(node);
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+        None
+    }
+
+    // 连通分量（有向图按弱连通计算），每个分量内升序，分量按最小顶点排序
+    pub fn connected_components(&self) -> Vec<Vec<usize>> {
+        let adj = self.undirected_adj();
+        let mut visited = vec![false; adj.len()];
+        let mut components = vec![];
+        for root in 0..adj.len() {
+            if visited[root] {
+                continue;
+            }
+            visited[root] = true;
+            let mut component = vec![root];
+            let mut queue = VecDeque::from([root]);
+            while let Some(node) = queue.pop_front() {
+                for &neighbor in &adj[node] {
+                    if !visited[neighbor] {
+                        visited[neighbor] = true;
+                        component.push(neighbor);
+                        queue.push_back(neighbor);
+                    }
+                }
+            }
+            component.sort_unstable();
+            components.push(component);
+        }
+        components
+    }
+
+    // 二染色，true 与 false 各为一侧；存在奇环时返回 None（有向图忽略方向）
+    pub fn two_coloring(&self) -> Option<Vec<bool>> {
+        let adj = self.undirected_adj();
+        let mut color: Vec<Option<bool>> = vec![None; adj.len()];
+        for root in 0..adj.len() {
+            if color[root].is_some() {
+                continue;
+            }
+            color[root] = Some(false);
+            let mut queue = VecDeque::from([root]);
+            while let Some(node) = queue.pop_front() {
+                let side = color[node] == Some(true);
+                for &neighbor in &adj[node] {
+                    match color[neighbor] {
+                        None => {
+                            color[neighbor] = Some(!side);
+                            queue.push_back(neighbor);
+                        }
+                        Some(other) if other == side => return None,
+                        Some(_) => {}
+                    }
+                }
+            }
+        }
+        Some(color.into_iter().map(|side| side == Some(true)).collect())
+    }
+
+    pub fn is_bipartite(&self) -> bool {
+        self.two_coloring().is_some()
+    }
+
+    // 找出任意一个环，按边的方向依次列出环上的顶点；自环为单个顶点，
+    // 无向图中的平行边构成长度为 2 的环
+    pub fn find_cycle(&self) -> Option<Vec<usize>> {
+        let mut color = vec![Color::White; self.adj.len()];
+        let mut parent = vec![0; self.adj.len()];
+        for root in 0..self.adj.len() {
+            if color[root] != Color::White {
+                continue;
+            }
+            color[root] = Color::Gray;
+            let mut stack = vec![Frame::new(root, None)];
+            while let Some(frame) = stack.last_mut() {
+                let vertex = frame.vertex;
+                let Some(neighbor) = frame.advance(&self.adj[vertex]) else {
+                    color[vertex] = Color::Black;
+                    stack.pop();
+                    continue;
+                };
+                match color[neighbor] {
+                    Color::White => {
+                        color[neighbor] = Color::Gray;
+                        parent[neighbor] = vertex;
+                        // 无向图不能沿来时的边走回去
+                        let from = (!self.directed).then_some(vertex);
+                        stack.push(Frame::new(neighbor, from));
+                    }
+                    // 指向栈中祖先的边闭合了一个环
+                    Color::Gray => {
+                        let mut cycle = vec![vertex];
+                        let mut current = vertex;
+                        while current != neighbor {
+                            current = parent[current];
+                            cycle.push(current);
+                        }
+                        cycle.reverse();
+                        return Some(cycle);
+                    }
+                    Color::Black => {}
+                }
+            }
+        }
+        None
+    }
+
+    pub fn has_cycle(&self) -> bool {
+        self.find_cycle().is_some()
+    }
+
+    // Kahn 算法：反复取出入度为 0 的顶点，同时可选时取编号最小的（即字典序最小的拓扑序）；有环时返回 None
+    pub fn topological_sort_kahn(&self) -> Option<Vec<usize>> {
+        assert!(self.directed, "topological sort requires a directed graph");
+        let mut in_degree = vec![0; self.adj.len()];
+        for neighbors in &self.adj {
+            for &dest in neighbors {
+                in_degree[dest] += 1;
+            }
+        }
+        let mut ready: BinaryHeap<Reverse<usize>> = (0..self.adj.len())
+            .filter(|&vertex| in_degree[vertex] == 0)
+            .map(Reverse)
+            .collect();
+        let mut order = Vec::with_capacity(self.adj.len());
+        while let Some(Reverse(vertex)) = ready.pop() {
+            order.push(vertex);
+            for &dest in &self.adj[vertex] {
+                in_degree[dest] -= 1;
+                if in_degree[dest] == 0 {
+                    ready.push(Reverse(dest));
+                }
+            }
+        }
+        (order.len() == self.adj.len()).then_some(order)
+    }
+
+    // DFS 后序的逆序即拓扑序，遇到指向栈中顶点的边说明有环
+    pub fn topological_sort_dfs(&self) -> Option<Vec<usize>> {
+        assert!(self.directed, "topological sort requires a directed graph");
+        let mut color = vec![Color::White; self.adj.len()];
+        let mut post_order = Vec::with_capacity(self.adj.len());
+        for root in 0..self.adj.len() {
+            if color[root] != Color::White {
+                continue;
+            }
+            color[root] = Color::Gray;
+            let mut stack = vec![Frame::new(root, None)];
+            while let Some(frame) = stack.last_mut() {
+                let vertex = frame.vertex;
+                match frame.advance(&self.adj[vertex]) {
+                    Some(dest) => match color[dest] {
+                        Color::White => {
+                            color[dest] = Color::Gray;
+                            stack.push(Frame::new(dest, None));
+                        }
+                        Color::Gray => return None,
+                        Color::Black => {}
+                    },
+                    None => {
+                        color[vertex] = Color::Black;
+                        post_order.push(vertex);
+                        stack.pop();
+                    }
+                }
+            }
+        }
+        post_order.reverse();
+        Some(post_order)
+    }
+
+    // Tarjan 算法求强连通分量，分量按逆拓扑序给出，每个分量内升序
+    pub fn strongly_connected_components(&self) -> Vec<Vec<usize>> {
+        let n = self.adj.len();
+        let mut index: Vec<Option<usize>> = vec![None; n];
+        let mut low = vec![0; n];
+        let mut on_stack = vec![false; n];
+        let mut stack = vec![];
+        let mut counter = 0;
+        let mut components = vec![];
+
+        for root in 0..n {
+            if index[root].is_some() {
+                continue;
+            }
+            index[root] = Some(counter);
+            low[root] = counter;
+            counter += 1;
+            stack.push(root);
+            on_stack[root] = true;
+            let mut calls = vec![Frame::new(root, None)];
+            while let Some(frame) = calls.last_mut() {
+                let vertex = frame.vertex;
+                if let Some(dest) = frame.advance(&self.adj[vertex]) {
+                    match index[dest] {
+                        None => {
+                            index[dest] = Some(counter);
+                            low[dest] = counter;
+                            counter += 1;
+                            stack.push(dest);
+                            on_stack[dest] = true;
+                            calls.push(Frame::new(dest, None));
+                        }
+                        Some(dest_index) if on_stack[dest] => {
+                            low[vertex] = low[vertex].min(dest_index);
+                        }
+                        Some(_) => {}
+                    }
+                    continue;
+                }
+
+                calls.pop();
+                if let Some(caller) = calls.last() {
+                    low[caller.vertex] = low[caller.vertex].min(low[vertex]);
+                }
+                // vertex 是所在分量中最先访问的顶点，栈中它之上的顶点构成一个分量
+                if Some(low[vertex]) == index[vertex] {
+                    let mut component = vec![];
+                    loop {
+                        let member = stack.pop().unwrap();
+                        on_stack[member] = false;
+                        component.push(member);
+                        if member == vertex {
+                            break;
+                        }
+                    }
+                    component.sort_unstable();
+                    components.push(component);
+                }
+            }
+        }
+        components
+    }
+
+    // 无向图的桥与割点，桥的端点按 (小, 大) 给出，结果均升序
+    pub fn bridges_and_articulation_points(&self) -> (Vec<(usize, usize)>, Vec<usize>) {
+        assert!(
+            !self.directed,
+            "bridges and articulation points require an undirected graph"
+        );
+        let n = self.adj.len();
+        let mut discovered: Vec<Option<usize>> = vec![None; n];
+        let mut low = vec![0; n];
+        let mut counter = 0;
+        let mut bridges = vec![];
+        let mut is_articulation = vec![false; n];
+
+        for root in 0..n {
+            if discovered[root].is_some() {
+                continue;
+            }
+            discovered[root] = Some(counter);
+            low[root] = counter;
+            counter += 1;
+            let mut root_children = 0;
+            let mut calls = vec![Frame::new(root, None)];
+            while let Some(frame) = calls.last_mut() {
+                let vertex = frame.vertex;
+                if let Some(neighbor) = frame.advance(&self.adj[vertex]) {
+                    match discovered[neighbor] {
+                        None => {
+                            discovered[neighbor] = Some(counter);
+                            low[neighbor] = counter;
+                            counter += 1;
+                            if vertex == root {
+                                root_children += 1;
+                            }
+                            calls.push(Frame::new(neighbor, Some(vertex)));
+                        }
+                        Some(order) => low[vertex] = low[vertex].min(order),
+                    }
+                    continue;
+                }
+
+                calls.pop();
+                let Some(caller) = calls.last() else {
+                    continue;
+                };
+                let parent = caller.vertex;
+                low[parent] = low[parent].min(low[vertex]);
+                let parent_order = discovered[parent].unwrap();
+                // 子树绕不回 parent 及其祖先，这条树边就是桥
+                if low[vertex] > parent_order {
+                    bridges.push((parent.min(vertex), parent.max(vertex)));
+                }
+                // 根单独判断：有两棵以上的 DFS 子树时才是割点
+                if parent != root && low[vertex] >= parent_order {
+                    is_articulation[parent] = true;
+                }
+            }
+            if root_children >= 2 {
+                is_articulation[root] = true;
+            }
+        }
+
+        bridges.sort_unstable();
+        let articulation_points = (0..n).filter(|&vertex| is_articulation[vertex]).collect();
+        (bridges, articulation_points)
+    }
+
+    pub fn bridges(&self) -> Vec<(usize, usize)> {
+        self.bridges_and_articulation_points().0
+    }
+
+    pub fn articulation_points(&self) -> Vec<usize> {
+        self.bridges_and_articulation_points().1
+    }
+}
@@ -0,0 +1,16 @@
+/*
+    test_rng
+    Deterministic pseudo-random numbers shared by the tests of several exercises
+*/
+/*
+    测试用随机数
+    多道习题的测试共用的伪随机数生成器
+*/
+
+// 线性同余伪随机数，测试结果可复现
+pub fn pseudo_random(seed: &mut u64) -> u64 {
+    *seed = seed
+        .wrapping_mul(6364136223846793005)
+        .wrapping_add(1442695040888963407);
+    *seed >> 33
+}
@@ -0,0 +1,319 @@
+use crate::deadline::LatePolicy;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Exercise {
+    pub name: String,
+    pub path: String,
+    #[serde(rename = "type")]
+    pub exercise_type: String,
+    pub score: i32,
+    // 习题用到的其他文件（相对 exercises/ 目录），例如多道习题通过 #[path] 共用的模块
+    // 签名、重置、查重与未完成标记检查会把这些文件当作习题的一部分
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extra_files: Vec<String>,
+    // 教师端隐藏测试文件的路径（相对仓库根目录），评测时拼接进习题
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hidden_tests: Option<String>,
+    // 测试阶段失败后的最大重试次数，需配合 retry_on 使用
+    #[serde(default)]
+    pub retries: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_on: Option<RetryOn>,
+    // RFC 3339 格式的截止时间，未设置时沿用所属难度的截止时间
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deadline: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub late_policy: Option<LatePolicy>,
+    // 终端界面中按 h 显示的提示
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hint: Option<String>,
+    #[serde(flatten)]
+    pub limits: ResourceLimits,
+    // 与教师参考实现对拍
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub differential: Option<Differential>,
+}
+
+// 对拍配置：教师文件（相对仓库根目录）提供 generate、reference 与 student 三个函数
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Differential {
+    pub file: String,
+    // 随机生成的用例数
+    #[serde(default = "default_differential_cases")]
+    pub cases: u64,
+    // 第 i 个用例使用 seed + i 作为随机数种子
+    #[serde(default)]
+    pub seed: u64,
+}
+
+fn default_differential_cases() -> u64 {
+    1000
+}
+
+// 运行学生代码（测试阶段）时的资源限制，未设置的项不限制
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ResourceLimits {
+    // 每个进程可用的数据段内存（RLIMIT_DATA），超出时判为 MLE
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory_limit_mb: Option<u64>,
+    // 标准输出与标准错误合计的最大字节数，超出时终止进程并判为 OLE
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_output_kb: Option<u64>,
+    // 当前用户可同时拥有的进程（含线程）数（RLIMIT_NPROC）
+    // 按真实用户计数，评测机上该用户已有的进程也算在内；以 root 运行时内核不检查此限制
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_processes: Option<u64>,
+}
+
+impl ResourceLimits {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+impl Exercise {
+    // 习题自身的路径及其 extra_files
+    pub fn source_paths(&self) -> impl Iterator<Item = &str> {
+        std::iter::once(self.path.as_str()).chain(self.extra_files.iter().map(String::as_str))
+    }
+
+    // 测试阶段最多执行的次数（含第一次）
+    pub fn max_test_attempts(&self) -> u32 {
+        match self.retry_on {
+            Some(RetryOn::TestFailure) => self.retries + 1,
+            None => 1,
+        }
+    }
+}
+
+// 触发重试的失败类型
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RetryOn {
+    // 可见测试的任何失败都会重跑（编译、clippy、隐藏测试与对拍不重试）
+    // 评测器无法区分超时与答案错误，只应用于结果确定、仅耗时不稳定的习题
+    // 旧配置中的 "timing" 含义相同，作为别名保留
+    #[serde(alias = "timing")]
+    TestFailure,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ExerciseConfig {
+    pub easy: Vec<Exercise>,
+    pub normal: Vec<Exercise>,
+    pub hard: Vec<Exercise>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template: Option<TemplateSource>,
+    // 按难度（easy、normal、hard）设置的截止时间
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub deadlines: HashMap<String, Deadline>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Deadline {
+    pub deadline: String,
+    #[serde(default)]
+    pub late_policy: LatePolicy,
+}
+
+impl ExerciseConfig {
+    // 按 easy、normal、hard 的顺序遍历全部习题
+    pub fn exercises(&self) -> impl Iterator<Item = &Exercise> {
+        [&self.easy, &self.normal, &self.hard].into_iter().flatten()
+    }
+
+    // 按相同顺序遍历全部习题，并附带所属难度
+    pub fn exercises_by_level(&self) -> impl Iterator<Item = (&'static str, &Exercise)> {
+        [
+            ("easy", &self.easy),
+            ("normal", &self.normal),
+            ("hard", &self.hard),
+        ]
+        .into_iter()
+        .flat_map(|(level, exercises)| exercises.iter().map(move |exercise| (level, exercise)))
+    }
+
+    // 未单独设置截止时间的习题沿用所属难度的截止时间与扣分策略
+    pub fn apply_level_deadlines(&mut self) {
+        for (level, exercises) in [
+            ("easy", &mut self.easy),
+            ("normal", &mut self.normal),
+            ("hard", &mut self.hard),
+        ] {
+            let Some(level_deadline) = self.deadlines.get(level) else {
+                continue;
+            };
+            for exercise in exercises.iter_mut() {
+                if exercise.deadline.is_none() {
+                    exercise.deadline = Some(level_deadline.deadline.clone());
+                    exercise
+                        .late_policy
+                        .get_or_insert(level_deadline.late_policy);
+                }
+            }
+        }
+    }
+
+    // 按名称或路径查找习题
+    pub fn find(&self, name: &str) -> Option<&Exercise> {
+        self.exercises()
+            .find(|exercise| exercise.name == name || exercise.path == name)
+    }
+}
+
+// 习题初始代码的来源，供 reset 命令恢复使用
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "snake_case")]
+pub enum TemplateSource {
+    // git 修订版本，例如 "upstream/main" 或某个提交哈希
+    Git(String),
+    // 与 exercises/ 目录结构相同的模板目录
+    Dir(String),
+}
+
+pub fn load_exercise_config(file_path: &str) -> Result<ExerciseConfig, io::Error> {
+    let file = File::open(file_path)?;
+    let mut config: ExerciseConfig = serde_json::from_reader(file)?;
+    config.apply_level_deadlines();
+    Ok(config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_optional_fields_default() {
+        let exercise: Exercise = serde_json::from_str(
+            r#"{ "name": "algorithm1.rs", "path": "easy/algorithm1.rs", "type": "single_file", "score": 1 }"#,
+        )
+        .unwrap();
+        assert_eq!(exercise.hidden_tests, None);
+        assert_eq!(exercise.retries, 0);
+        assert_eq!(exercise.max_test_attempts(), 1);
+        assert!(exercise.limits.is_empty());
+        assert_eq!(exercise.differential, None);
+        assert!(exercise.extra_files.is_empty());
+        assert_eq!(
+            exercise.source_paths().collect::<Vec<_>>(),
+            vec!["easy/algorithm1.rs"]
+        );
+    }
+
+    #[test]
+    fn test_extra_files_are_source_paths() {
+        let exercise: Exercise = serde_json::from_str(
+            r#"{ "name": "algorithm5.rs", "path": "easy/algorithm5.rs", "type": "single_file", "score": 1, "extra_files": ["easy/graph.rs"] }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            exercise.source_paths().collect::<Vec<_>>(),
+            vec!["easy/algorithm5.rs", "easy/graph.rs"]
+        );
+    }
+
+    #[test]
+    fn test_differential_defaults() {
+        let exercise: Exercise = serde_json::from_str(
+            r#"{ "name": "solution2", "path": "normal/solution2", "type": "cargo_project", "score": 6, "differential": { "file": "hidden/solution2.rs" } }"#,
+        )
+        .unwrap();
+        let differential = exercise.differential.unwrap();
+        assert_eq!(differential.cases, 1000);
+        assert_eq!(differential.seed, 0);
+    }
+
+    #[test]
+    fn test_resource_limits_are_flat_fields() {
+        let exercise: Exercise = serde_json::from_str(
+            r#"{ "name": "solution5", "path": "normal/solution5", "type": "cargo_project", "score": 6, "memory_limit_mb": 256, "max_output_kb": 64 }"#,
+        )
+        .unwrap();
+        assert_eq!(exercise.limits.memory_limit_mb, Some(256));
+        assert_eq!(exercise.limits.max_output_kb, Some(64));
+        assert_eq!(exercise.limits.max_processes, None);
+
+        let json = serde_json::to_value(&exercise).unwrap();
+        assert_eq!(json["memory_limit_mb"], 256);
+        assert!(json.get("max_processes").is_none());
+    }
+
+    #[test]
+    fn test_retries_require_retry_on() {
+        let exercise: Exercise = serde_json::from_str(
+            r#"{ "name": "solutiont1", "path": "hard/solutiont1", "type": "cargo_project", "score": 10, "retries": 2 }"#,
+        )
+        .unwrap();
+        assert_eq!(exercise.max_test_attempts(), 1);
+
+        let exercise: Exercise = serde_json::from_str(
+            r#"{ "name": "solutiont1", "path": "hard/solutiont1", "type": "cargo_project", "score": 10, "retries": 2, "retry_on": "test_failure" }"#,
+        )
+        .unwrap();
+        assert_eq!(exercise.max_test_attempts(), 3);
+
+        let exercise: Exercise = serde_json::from_str(
+            r#"{ "name": "solutiont1", "path": "hard/solutiont1", "type": "cargo_project", "score": 10, "retries": 2, "retry_on": "timing" }"#,
+        )
+        .unwrap();
+        assert_eq!(exercise.retry_on, Some(RetryOn::TestFailure));
+    }
+
+    #[test]
+    fn test_find_by_name_or_path() {
+        let config: ExerciseConfig = serde_json::from_str(
+            r#"{
+                "easy": [{ "name": "algorithm1.rs", "path": "easy/algorithm1.rs", "type": "single_file", "score": 1 }],
+                "normal": [],
+                "hard": [{ "name": "solutiont1", "path": "hard/solutiont1", "type": "cargo_project", "score": 10 }],
+                "template": { "git": "upstream/main" }
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(config.exercises().count(), 2);
+        assert_eq!(config.find("solutiont1").unwrap().score, 10);
+        assert_eq!(config.find("easy/algorithm1.rs").unwrap().score, 1);
+        assert!(config.find("algorithm2.rs").is_none());
+        assert!(matches!(config.template, Some(TemplateSource::Git(_))));
+    }
+
+    #[test]
+    fn test_level_deadlines_apply_to_exercises() {
+        let mut config: ExerciseConfig = serde_json::from_str(
+            r#"{
+                "easy": [{ "name": "algorithm1.rs", "path": "easy/algorithm1.rs", "type": "single_file", "score": 1 }],
+                "normal": [],
+                "hard": [
+                    { "name": "solutiont1", "path": "hard/solutiont1", "type": "cargo_project", "score": 10 },
+                    { "name": "solutiont2", "path": "hard/solutiont2", "type": "cargo_project", "score": 10,
+                      "deadline": "2024-12-08T23:59:59+08:00" }
+                ],
+                "deadlines": {
+                    "hard": { "deadline": "2024-12-01T23:59:59+08:00", "late_policy": { "type": "linear", "percent_per_day": 10 } }
+                }
+            }"#,
+        )
+        .unwrap();
+        config.apply_level_deadlines();
+
+        assert_eq!(config.easy[0].deadline, None);
+        assert_eq!(
+            config.hard[0].deadline.as_deref(),
+            Some("2024-12-01T23:59:59+08:00")
+        );
+        assert!(matches!(
+            config.hard[0].late_policy,
+            Some(LatePolicy::Linear { .. })
+        ));
+        // 习题自己的截止时间优先
+        assert_eq!(
+            config.hard[1].deadline.as_deref(),
+            Some("2024-12-08T23:59:59+08:00")
+        );
+        assert_eq!(config.hard[1].late_policy, None);
+    }
+}
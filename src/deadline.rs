@@ -0,0 +1,94 @@
+use serde::{Deserialize, Serialize};
+
+const SECS_PER_DAY: i64 = 86400;
+
+// 迟交扣分策略
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum LatePolicy {
+    // 截止时间之后提交不得分
+    #[default]
+    Cutoff,
+    // 每迟交一天（不足一天按一天计）扣除 percent_per_day% 的分数，最低保留 min_percent%
+    Linear {
+        percent_per_day: u32,
+        #[serde(default)]
+        min_percent: u32,
+    },
+}
+
+// 迟交天数，不足一天按一天计；按时提交时为 0
+pub fn late_days(submitted_at: i64, deadline: i64) -> u32 {
+    if submitted_at <= deadline {
+        return 0;
+    }
+    let late_secs = submitted_at - deadline;
+    ((late_secs + SECS_PER_DAY - 1) / SECS_PER_DAY) as u32
+}
+
+// 按扣分策略计算迟交后的得分
+pub fn penalised_score(score: i32, late_days: u32, policy: LatePolicy) -> i32 {
+    if late_days == 0 {
+        return score;
+    }
+    match policy {
+        LatePolicy::Cutoff => 0,
+        LatePolicy::Linear {
+            percent_per_day,
+            min_percent,
+        } => {
+            let deduction = late_days.saturating_mul(percent_per_day);
+            let percent = 100u32.saturating_sub(deduction).max(min_percent.min(100));
+            score * percent as i32 / 100
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_late_days() {
+        assert_eq!(late_days(100, 100), 0);
+        assert_eq!(late_days(50, 100), 0);
+        assert_eq!(late_days(101, 100), 1);
+        assert_eq!(late_days(100 + SECS_PER_DAY, 100), 1);
+        assert_eq!(late_days(101 + SECS_PER_DAY, 100), 2);
+    }
+
+    #[test]
+    fn test_cutoff() {
+        assert_eq!(penalised_score(10, 0, LatePolicy::Cutoff), 10);
+        assert_eq!(penalised_score(10, 1, LatePolicy::Cutoff), 0);
+    }
+
+    #[test]
+    fn test_linear_decay() {
+        let policy = LatePolicy::Linear {
+            percent_per_day: 20,
+            min_percent: 30,
+        };
+        assert_eq!(penalised_score(10, 0, policy), 10);
+        assert_eq!(penalised_score(10, 1, policy), 8);
+        assert_eq!(penalised_score(10, 3, policy), 4);
+        // 扣分不低于 min_percent
+        assert_eq!(penalised_score(10, 4, policy), 3);
+        assert_eq!(penalised_score(10, 100, policy), 3);
+    }
+
+    #[test]
+    fn test_policy_from_json() {
+        let policy: LatePolicy =
+            serde_json::from_str(r#"{ "type": "linear", "percent_per_day": 10 }"#).unwrap();
+        assert_eq!(
+            policy,
+            LatePolicy::Linear {
+                percent_per_day: 10,
+                min_percent: 0
+            }
+        );
+        let policy: LatePolicy = serde_json::from_str(r#"{ "type": "cutoff" }"#).unwrap();
+        assert_eq!(policy, LatePolicy::Cutoff);
+    }
+}
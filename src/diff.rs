@@ -0,0 +1,323 @@
+use crate::report::{Environment, Report};
+
+// 两次评测报告之间的差异
+#[derive(Debug, Default)]
+pub struct ReportDiff {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub newly_passed: Vec<String>,
+    pub regressed: Vec<String>,
+    pub score_changed: Vec<(String, i32, i32)>,
+    pub time_changed: Vec<(String, u64, u64)>,
+    // 统计项名称及其新旧值
+    pub statistics: Vec<(&'static str, i64, i64)>,
+    // 工具链与机器信息中发生变化的项及其新旧值
+    pub environment_changed: Vec<(&'static str, String, String)>,
+}
+
+impl ReportDiff {
+    pub fn has_regressions(&self) -> bool {
+        !self.regressed.is_empty()
+    }
+
+    pub fn print(&self) {
+        for name in &self.added {
+            println!("\x1b[36m+ {}: new exercise\x1b[0m", name);
+        }
+        for name in &self.removed {
+            println!("\x1b[36m- {}: removed exercise\x1b[0m", name);
+        }
+
+        println!("\nNewly passed: {}", self.newly_passed.len());
+        for name in &self.newly_passed {
+            println!("\x1b[32m  {}\x1b[0m", name);
+        }
+        println!("Regressed: {}", self.regressed.len());
+        for name in &self.regressed {
+            println!("\x1b[31m  {}\x1b[0m", name);
+        }
+        println!("Score changed: {}", self.score_changed.len());
+        for (name, old_score, new_score) in &self.score_changed {
+            println!("  {}: {} -> {}", name, old_score, new_score);
+        }
+        println!("Time changed: {}", self.time_changed.len());
+        for (name, old_time, new_time) in &self.time_changed {
+            println!("  {}: {}ms -> {}ms", name, old_time, new_time);
+        }
+
+        if !self.environment_changed.is_empty() {
+            println!("\nEnvironment changed:");
+            for (label, old_value, new_value) in &self.environment_changed {
+                println!("  {}: {} -> {}", label, old_value, new_value);
+            }
+        }
+
+        println!("\nStatistics:");
+        for (label, old_value, new_value) in &self.statistics {
+            println!(
+                "{}: {} -> {} ({:+})",
+                label,
+                old_value,
+                new_value,
+                new_value - old_value
+            );
+        }
+    }
+}
+
+// 对比两次评测报告
+pub fn diff_reports(old: &Report, new: &Report) -> ReportDiff {
+    let mut diff = ReportDiff::default();
+
+    for new_result in &new.exercises {
+        let Some(old_result) = old.exercises.iter().find(|r| r.name == new_result.name) else {
+            diff.added.push(new_result.name.clone());
+            continue;
+        };
+        match (old_result.result, new_result.result) {
+            (false, true) => diff.newly_passed.push(new_result.name.clone()),
+            (true, false) => diff.regressed.push(new_result.name.clone()),
+            _ => {}
+        }
+        if old_result.score != new_result.score {
+            diff.score_changed
+                .push((new_result.name.clone(), old_result.score, new_result.score));
+        }
+        let (old_time, new_time) = (
+            elapsed_ms(old_result.time, old_result.time_ms),
+            elapsed_ms(new_result.time, new_result.time_ms),
+        );
+        if time_changed(old_time, new_time) {
+            diff.time_changed
+                .push((new_result.name.clone(), old_time, new_time));
+        }
+    }
+    for old_result in &old.exercises {
+        if !new.exercises.iter().any(|r| r.name == old_result.name) {
+            diff.removed.push(old_result.name.clone());
+            // 之前通过的习题消失同样视为退步
+            if old_result.result {
+                diff.regressed.push(old_result.name.clone());
+            }
+        }
+    }
+
+    let old_stats = &old.statistics;
+    let new_stats = &new.statistics;
+    diff.statistics = vec![
+        (
+            "Total exercises",
+            old_stats.total_exercises as i64,
+            new_stats.total_exercises as i64,
+        ),
+        (
+            "Total successes",
+            old_stats.total_successes as i64,
+            new_stats.total_successes as i64,
+        ),
+        (
+            "Total failures",
+            old_stats.total_failures as i64,
+            new_stats.total_failures as i64,
+        ),
+        (
+            "Total score",
+            old_stats.total_score as i64,
+            new_stats.total_score as i64,
+        ),
+        (
+            "Total time (ms)",
+            elapsed_ms(old_stats.total_time, old_stats.total_time_ms) as i64,
+            elapsed_ms(new_stats.total_time, new_stats.total_time_ms) as i64,
+        ),
+    ];
+    if let (Some(old_env), Some(new_env)) = (&old.environment, &new.environment) {
+        diff.environment_changed = environment_changes(old_env, new_env);
+    }
+
+    diff
+}
+
+// 耗时的变化同时达到这两个阈值才报告，避免把正常的抖动列为变化
+const TIME_CHANGE_MIN_MS: u64 = 100;
+const TIME_CHANGE_MIN_PERCENT: u64 = 20;
+
+fn time_changed(old_ms: u64, new_ms: u64) -> bool {
+    let delta = old_ms.abs_diff(new_ms);
+    delta >= TIME_CHANGE_MIN_MS && delta * 100 >= old_ms * TIME_CHANGE_MIN_PERCENT
+}
+
+// 以毫秒比较耗时；旧报告只有以秒为单位的字段
+fn elapsed_ms(secs: u64, ms: u64) -> u64 {
+    if ms == 0 {
+        secs * 1000
+    } else {
+        ms
+    }
+}
+
+// 对比会影响评测结果的工具链与机器信息，不比较时间戳与提交
+fn environment_changes(
+    old: &Environment,
+    new: &Environment,
+) -> Vec<(&'static str, String, String)> {
+    let show = |value: &Option<String>| value.clone().unwrap_or_else(|| "unknown".to_string());
+    let fields = [
+        ("rustc", show(&old.rustc), show(&new.rustc)),
+        ("cargo", show(&old.cargo), show(&new.cargo)),
+        ("clippy", show(&old.clippy), show(&new.clippy)),
+        ("host", show(&old.host), show(&new.host)),
+        ("CPU model", show(&old.cpu_model), show(&new.cpu_model)),
+        (
+            "CPU cores",
+            show(&old.cpu_cores.map(|n| n.to_string())),
+            show(&new.cpu_cores.map(|n| n.to_string())),
+        ),
+        (
+            "grader version",
+            old.grader_version.clone(),
+            new.grader_version.clone(),
+        ),
+    ];
+    fields
+        .into_iter()
+        .filter(|(_, old_value, new_value)| old_value != new_value)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::ExerciseResult;
+
+    fn report(results: &[(&str, bool, i32)]) -> Report {
+        let mut report = Report::new();
+        for &(name, passed, score) in results {
+            report.push(ExerciseResult {
+                name: name.to_string(),
+                result: passed,
+                score,
+                visible_result: None,
+                hidden_result: None,
+                time: 0,
+                time_ms: 0,
+                attempts: Vec::new(),
+                raw_score: None,
+                late_days: None,
+                verdict: None,
+                differential: None,
+            });
+        }
+        report
+    }
+
+    #[test]
+    fn test_diff_detects_regressions() {
+        let old = report(&[("a", true, 1), ("b", false, 0), ("c", true, 10)]);
+        let new = report(&[("a", false, 0), ("b", true, 1), ("c", true, 10)]);
+        let diff = diff_reports(&old, &new);
+
+        assert_eq!(diff.newly_passed, vec!["b"]);
+        assert_eq!(diff.regressed, vec!["a"]);
+        assert_eq!(diff.score_changed.len(), 2);
+        assert!(diff.has_regressions());
+    }
+
+    #[test]
+    fn test_removed_passing_exercise_is_regression() {
+        let old = report(&[("a", true, 1), ("b", false, 0)]);
+        let new = report(&[("b", false, 0), ("c", true, 1)]);
+        let diff = diff_reports(&old, &new);
+
+        assert_eq!(diff.added, vec!["c"]);
+        assert_eq!(diff.removed, vec!["a"]);
+        assert_eq!(diff.regressed, vec!["a"]);
+    }
+
+    #[test]
+    fn test_identical_reports() {
+        let old = report(&[("a", true, 1)]);
+        let new = report(&[("a", true, 1)]);
+        let diff = diff_reports(&old, &new);
+
+        assert!(!diff.has_regressions());
+        assert!(diff.score_changed.is_empty());
+        assert!(diff.time_changed.is_empty());
+        assert!(diff
+            .statistics
+            .iter()
+            .all(|(_, old_value, new_value)| old_value == new_value));
+    }
+
+    #[test]
+    fn test_time_compared_in_milliseconds() {
+        let mut old = report(&[("a", true, 1), ("b", true, 1)]);
+        let mut new = report(&[("a", true, 1), ("b", true, 1)]);
+        // 同一秒内的变化也要体现
+        old.exercises[0].time_ms = 1200;
+        new.exercises[0].time_ms = 1700;
+        old.exercises[0].time = 1;
+        new.exercises[0].time = 1;
+        // 旧报告没有 time_ms 时按秒换算
+        old.exercises[1].time = 2;
+        new.exercises[1].time_ms = 2000;
+        new.exercises[1].time = 2;
+        old.statistics.total_time = 3;
+        new.statistics.total_time_ms = 3700;
+        let diff = diff_reports(&old, &new);
+
+        assert_eq!(diff.time_changed, vec![("a".to_string(), 1200, 1700)]);
+        assert!(diff.statistics.contains(&("Total time (ms)", 3000, 3700)));
+    }
+
+    #[test]
+    fn test_time_jitter_is_not_reported() {
+        let mut old = report(&[("a", true, 1), ("b", true, 1), ("c", true, 1)]);
+        let mut new = report(&[("a", true, 1), ("b", true, 1), ("c", true, 1)]);
+        // 10ms 的抖动
+        old.exercises[0].time_ms = 1200;
+        new.exercises[0].time_ms = 1210;
+        // 超过 100ms，但不到 20%
+        old.exercises[1].time_ms = 5000;
+        new.exercises[1].time_ms = 5500;
+        // 超过 20%，但不到 100ms
+        old.exercises[2].time_ms = 100;
+        new.exercises[2].time_ms = 150;
+        let diff = diff_reports(&old, &new);
+
+        assert!(diff.time_changed.is_empty());
+    }
+
+    #[test]
+    fn test_environment_changes() {
+        let mut old = report(&[("a", true, 1)]);
+        let mut new = report(&[("a", false, 0)]);
+        let environment = Environment {
+            rustc: Some("rustc 1.82.0 (f6e511eec 2024-10-15)".to_string()),
+            cpu_cores: Some(8),
+            grader_version: "0.1.0".to_string(),
+            started_at: "2025-10-19T08:34:56Z".to_string(),
+            ..Environment::default()
+        };
+        old.environment = Some(environment.clone());
+        new.environment = Some(Environment {
+            rustc: Some("rustc 1.83.0 (90b35a623 2024-11-26)".to_string()),
+            cpu_cores: Some(2),
+            started_at: "2025-10-20T08:00:00Z".to_string(),
+            ..environment
+        });
+        let diff = diff_reports(&old, &new);
+
+        assert_eq!(
+            diff.environment_changed,
+            vec![
+                (
+                    "rustc",
+                    "rustc 1.82.0 (f6e511eec 2024-10-15)".to_string(),
+                    "rustc 1.83.0 (90b35a623 2024-11-26)".to_string()
+                ),
+                ("CPU cores", "8".to_string(), "2".to_string()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,397 @@
+use crate::config::Differential;
+use crate::report::{DifferentialResult, Mismatch};
+use crate::runner::CommandOutput;
+
+// 测试输出中记录不一致用例的行前缀
+const MISMATCH_PREFIX: &str = "DIFFERENTIAL ";
+
+// 追加在教师文件之后的测试框架：随机数生成器、Shrink 及其常用实现、对拍测试本身
+// 教师文件需要提供：
+//   fn generate(rng: &mut Rng) -> Input
+//   fn reference(input: &Input) -> Output
+//   fn student(input: &Input) -> Output
+// 其中 Input: Shrink + Clone + Debug，Output: PartialEq + Debug
+const HARNESS: &str = r#"
+// ---- differential testing harness, generated by the grader ----
+
+// splitmix64 伪随机数生成器，相同种子生成相同的用例
+#[allow(dead_code)]
+pub struct Rng(u64);
+
+#[allow(dead_code)]
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    // [low, high) 内的整数
+    pub fn range(&mut self, low: u64, high: u64) -> u64 {
+        assert!(low < high, "empty range");
+        low + self.next_u64() % (high - low)
+    }
+
+    pub fn range_i64(&mut self, low: i64, high: i64) -> i64 {
+        assert!(low < high, "empty range");
+        low.wrapping_add((self.next_u64() % high.abs_diff(low)) as i64)
+    }
+
+    pub fn bool(&mut self) -> bool {
+        self.next_u64() & 1 == 1
+    }
+
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.range(0, items.len() as u64) as usize]
+    }
+
+    // 由 alphabet 中字符组成、长度不超过 max_len 的字符串
+    pub fn string(&mut self, alphabet: &str, max_len: usize) -> String {
+        let chars: Vec<char> = alphabet.chars().collect();
+        let len = self.range(0, max_len as u64 + 1) as usize;
+        (0..len).map(|_| *self.choose(&chars)).collect()
+    }
+
+    pub fn vec<T>(&mut self, max_len: usize, mut item: impl FnMut(&mut Rng) -> T) -> Vec<T> {
+        let len = self.range(0, max_len as u64 + 1) as usize;
+        (0..len).map(|_| item(self)).collect()
+    }
+}
+
+// 生成比当前输入“更小”的候选输入，用于把不一致的用例缩小到最简
+pub trait Shrink: Sized {
+    fn shrink(&self) -> Vec<Self> {
+        Vec::new()
+    }
+}
+
+macro_rules! shrink_unsigned {
+    ($($t:ty),*) => {$(
+        impl Shrink for $t {
+            fn shrink(&self) -> Vec<Self> {
+                let x = *self;
+                let mut candidates = Vec::new();
+                if x > 0 {
+                    candidates.push(0);
+                    if x / 2 > 0 {
+                        candidates.push(x / 2);
+                    }
+                    if x - 1 > x / 2 {
+                        candidates.push(x - 1);
+                    }
+                }
+                candidates
+            }
+        }
+    )*};
+}
+shrink_unsigned!(u8, u16, u32, u64, usize);
+
+macro_rules! shrink_signed {
+    ($($t:ty),*) => {$(
+        impl Shrink for $t {
+            fn shrink(&self) -> Vec<Self> {
+                let x = *self;
+                let mut candidates = Vec::new();
+                if x != 0 {
+                    candidates.push(0);
+                    if x < 0 && x != <$t>::MIN {
+                        candidates.push(-x);
+                    }
+                    if x / 2 != 0 {
+                        candidates.push(x / 2);
+                    }
+                    if x - x.signum() != x / 2 {
+                        candidates.push(x - x.signum());
+                    }
+                }
+                candidates
+            }
+        }
+    )*};
+}
+shrink_signed!(i8, i16, i32, i64, isize);
+
+impl Shrink for bool {
+    fn shrink(&self) -> Vec<Self> {
+        if *self { vec![false] } else { Vec::new() }
+    }
+}
+
+impl Shrink for char {
+    fn shrink(&self) -> Vec<Self> {
+        if *self != 'a' { vec!['a'] } else { Vec::new() }
+    }
+}
+
+impl<T: Shrink + Clone> Shrink for Vec<T> {
+    fn shrink(&self) -> Vec<Self> {
+        let mut candidates = Vec::new();
+        // 先尝试删除一段连续元素，段长从整个长度（即清空）开始逐次减半到 1
+        let mut chunk = self.len();
+        while chunk > 0 {
+            for start in (0..self.len()).step_by(chunk) {
+                let mut smaller = self[..start].to_vec();
+                smaller.extend_from_slice(&self[(start + chunk).min(self.len())..]);
+                candidates.push(smaller);
+            }
+            chunk /= 2;
+        }
+        // 再逐个缩小元素
+        for (i, item) in self.iter().enumerate() {
+            for smaller in item.shrink() {
+                let mut candidate = self.clone();
+                candidate[i] = smaller;
+                candidates.push(candidate);
+            }
+        }
+        candidates
+    }
+}
+
+impl Shrink for String {
+    fn shrink(&self) -> Vec<Self> {
+        let chars: Vec<char> = self.chars().collect();
+        chars.shrink().into_iter().map(|chars| chars.into_iter().collect()).collect()
+    }
+}
+
+impl<T: Shrink + Clone> Shrink for Option<T> {
+    fn shrink(&self) -> Vec<Self> {
+        match self {
+            Some(x) => std::iter::once(None).chain(x.shrink().into_iter().map(Some)).collect(),
+            None => Vec::new(),
+        }
+    }
+}
+
+impl<A: Shrink + Clone, B: Shrink + Clone> Shrink for (A, B) {
+    fn shrink(&self) -> Vec<Self> {
+        let (a, b) = self;
+        let mut candidates: Vec<Self> = a.shrink().into_iter().map(|a| (a, b.clone())).collect();
+        candidates.extend(b.shrink().into_iter().map(|b| (a.clone(), b)));
+        candidates
+    }
+}
+
+impl<A: Shrink + Clone, B: Shrink + Clone, C: Shrink + Clone> Shrink for (A, B, C) {
+    fn shrink(&self) -> Vec<Self> {
+        let (a, b, c) = self;
+        let mut candidates: Vec<Self> =
+            a.shrink().into_iter().map(|a| (a, b.clone(), c.clone())).collect();
+        candidates.extend(b.shrink().into_iter().map(|b| (a.clone(), b, c.clone())));
+        candidates.extend(c.shrink().into_iter().map(|c| (a.clone(), b.clone(), c)));
+        candidates
+    }
+}
+
+// 学生实现与参考实现不一致时返回 (期望, 实际)，学生实现 panic 也算不一致
+fn differential_mismatch<I, O: PartialEq + std::fmt::Debug>(
+    input: &I,
+    reference: &impl Fn(&I) -> O,
+    student: &impl Fn(&I) -> O,
+) -> Option<(String, String)> {
+    let expected = reference(input);
+    match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| student(input))) {
+        Ok(actual) if actual == expected => None,
+        Ok(actual) => Some((format!("{:?}", expected), format!("{:?}", actual))),
+        Err(payload) => {
+            let message = payload
+                .downcast_ref::<&str>()
+                .map(|s| s.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_default();
+            Some((format!("{:?}", expected), format!("panicked: {}", message)))
+        }
+    }
+}
+
+fn run_differential<I, O>(
+    generate: impl Fn(&mut Rng) -> I,
+    reference: impl Fn(&I) -> O,
+    student: impl Fn(&I) -> O,
+) where
+    I: Shrink + Clone + std::fmt::Debug,
+    O: PartialEq + std::fmt::Debug,
+{
+    // 学生实现可能 panic，对拍期间不打印 panic 信息
+    let hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(|_| {}));
+    let mut failure = None;
+    for case in 0..DIFFERENTIAL_CASES {
+        let mut rng = Rng::new(DIFFERENTIAL_SEED.wrapping_add(case));
+        let input = generate(&mut rng);
+        if let Some(outputs) = differential_mismatch(&input, &reference, &student) {
+            failure = Some((case, input, outputs));
+            break;
+        }
+    }
+    // 反复换成仍然不一致的更小输入，直到无法再缩小（最多检查 DIFFERENTIAL_SHRINK_CHECKS 次）
+    if let Some((case, mut input, mut outputs)) = failure {
+        let mut checks = 0;
+        'shrinking: while checks < DIFFERENTIAL_SHRINK_CHECKS {
+            for candidate in input.shrink() {
+                checks += 1;
+                if let Some(candidate_outputs) = differential_mismatch(&candidate, &reference, &student) {
+                    input = candidate;
+                    outputs = candidate_outputs;
+                    continue 'shrinking;
+                }
+                if checks >= DIFFERENTIAL_SHRINK_CHECKS {
+                    break;
+                }
+            }
+            break;
+        }
+        std::panic::set_hook(hook);
+        println!("DIFFERENTIAL case={}", case);
+        println!("DIFFERENTIAL input={:?}", input);
+        println!("DIFFERENTIAL expected={}", outputs.0);
+        println!("DIFFERENTIAL actual={}", outputs.1);
+        panic!("student output differs from the reference on case {}", case);
+    }
+    std::panic::set_hook(hook);
+}
+
+#[test]
+fn matches_reference() {
+    run_differential(generate, reference, student);
+}
+"#;
+
+// 最多尝试的缩小候选数
+const SHRINK_CHECKS: u64 = 10_000;
+
+// 教师文件加上测试框架与用例数、种子常量，作为额外的测试模块或测试目标的源码
+pub fn harness_source(config: &Differential, instructor_code: &str) -> String {
+    format!(
+        "{}\nconst DIFFERENTIAL_CASES: u64 = {};\nconst DIFFERENTIAL_SEED: u64 = {};\nconst DIFFERENTIAL_SHRINK_CHECKS: u64 = {};\n{}",
+        instructor_code, config.cases, config.seed, SHRINK_CHECKS, HARNESS
+    )
+}
+
+// 根据测试输出得到对拍结果；output 为 None 表示未能运行（例如编译失败）
+pub fn parse_outcome(config: &Differential, output: Option<&CommandOutput>) -> DifferentialResult {
+    let Some(output) = output else {
+        return DifferentialResult {
+            passed: false,
+            cases: config.cases,
+            mismatch: None,
+        };
+    };
+
+    let mut mismatch = Mismatch::default();
+    let mut found = false;
+    for line in output.stdout.lines().chain(output.stderr.lines()) {
+        let Some((key, value)) = line
+            .strip_prefix(MISMATCH_PREFIX)
+            .and_then(|rest| rest.split_once('='))
+        else {
+            continue;
+        };
+        found = true;
+        match key {
+            "case" => mismatch.case = value.parse().unwrap_or_default(),
+            "input" => mismatch.input = value.to_string(),
+            "expected" => mismatch.expected = value.to_string(),
+            "actual" => mismatch.actual = value.to_string(),
+            _ => {}
+        }
+    }
+    DifferentialResult {
+        passed: output.success,
+        cases: config.cases,
+        mismatch: found.then_some(mismatch),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::process::Command;
+
+    fn config() -> Differential {
+        Differential {
+            file: "hidden/solution2_differential.rs".to_string(),
+            cases: 500,
+            seed: 7,
+        }
+    }
+
+    #[test]
+    fn test_parse_outcome() {
+        let output = CommandOutput {
+            success: false,
+            stdout: "running 1 test\nDIFFERENTIAL case=12\nDIFFERENTIAL input=[3, 0]\nDIFFERENTIAL expected=3\nDIFFERENTIAL actual=panicked: index out of bounds\n".to_string(),
+            ..CommandOutput::default()
+        };
+        let outcome = parse_outcome(&config(), Some(&output));
+        assert!(!outcome.passed);
+        assert_eq!(outcome.cases, 500);
+        let mismatch = outcome.mismatch.unwrap();
+        assert_eq!(mismatch.case, 12);
+        assert_eq!(mismatch.input, "[3, 0]");
+        assert_eq!(mismatch.actual, "panicked: index out of bounds");
+
+        let passed = CommandOutput {
+            success: true,
+            ..CommandOutput::default()
+        };
+        assert!(parse_outcome(&config(), Some(&passed)).passed);
+        assert!(parse_outcome(&config(), None).mismatch.is_none());
+    }
+
+    // 真正编译并运行一次测试框架，确认教师文件的约定可用且能缩小到最简输入
+    #[test]
+    fn test_harness_finds_and_shrinks_mismatch() {
+        let instructor = r#"
+fn generate(rng: &mut Rng) -> Vec<u32> {
+    rng.vec(20, |rng| rng.range(0, 100) as u32)
+}
+
+fn reference(input: &Vec<u32>) -> u32 {
+    input.iter().sum()
+}
+
+// 学生实现忽略了大于 50 的元素
+fn student(input: &Vec<u32>) -> u32 {
+    input.iter().filter(|&&x| x <= 50).sum()
+}
+"#;
+        let dir = std::env::temp_dir().join(format!("grader_differential_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let source = dir.join("differential.rs");
+        fs::write(&source, harness_source(&config(), instructor)).unwrap();
+        let binary = dir.join("differential");
+        let compiled = Command::new("rustc")
+            .arg("--test")
+            .arg(&source)
+            .arg("-o")
+            .arg(&binary)
+            .status()
+            .unwrap();
+        assert!(compiled.success());
+        let output = Command::new(&binary).output().unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        let output = CommandOutput {
+            success: output.status.success(),
+            stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+            stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+            verdict: None,
+        };
+        let outcome = parse_outcome(&config(), Some(&output));
+        assert!(!outcome.passed);
+        let mismatch = outcome.mismatch.unwrap();
+        assert_eq!(mismatch.input, "[51]");
+        assert_eq!(mismatch.expected, "51");
+        assert_eq!(mismatch.actual, "0");
+    }
+}
@@ -0,0 +1,135 @@
+use crate::report::Environment;
+use crate::runner::{CommandRunner, CommandSpec};
+use crate::timestamp::format_rfc3339;
+use std::fs;
+use std::thread;
+use std::time::SystemTime;
+
+// 收集工具链与机器信息，started_at 为调用时刻
+pub fn collect_environment(runner: &dyn CommandRunner) -> Environment {
+    let rustc_verbose = command_stdout(runner, CommandSpec::new("rustc").arg("-vV"));
+    let host = rustc_verbose.as_deref().and_then(|output| {
+        output
+            .lines()
+            .find_map(|line| line.strip_prefix("host:"))
+            .map(|host| host.trim().to_string())
+    });
+    Environment {
+        rustc: rustc_verbose.and_then(|output| output.lines().next().map(str::to_string)),
+        cargo: command_stdout(runner, CommandSpec::new("cargo").arg("-V")),
+        clippy: command_stdout(runner, CommandSpec::new("cargo").args(["clippy", "-V"])),
+        host,
+        cpu_model: cpu_model(runner),
+        cpu_cores: thread::available_parallelism().ok().map(|n| n.get()),
+        grader_version: env!("CARGO_PKG_VERSION").to_string(),
+        git_commit: command_stdout(runner, CommandSpec::new("git").args(["rev-parse", "HEAD"])),
+        started_at: format_rfc3339(SystemTime::now()),
+        finished_at: None,
+    }
+}
+
+// 命令成功且有输出时返回去掉首尾空白的标准输出
+fn command_stdout(runner: &dyn CommandRunner, command: CommandSpec) -> Option<String> {
+    let output = runner.run(&command).ok().filter(|output| output.success)?;
+    let stdout = output.stdout.trim();
+    (!stdout.is_empty()).then(|| stdout.to_string())
+}
+
+// Linux 读取 /proc/cpuinfo，macOS 通过 sysctl 查询
+fn cpu_model(runner: &dyn CommandRunner) -> Option<String> {
+    if let Ok(cpuinfo) = fs::read_to_string("/proc/cpuinfo") {
+        return parse_cpuinfo(&cpuinfo);
+    }
+    command_stdout(
+        runner,
+        CommandSpec::new("sysctl").args(["-n", "machdep.cpu.brand_string"]),
+    )
+}
+
+// x86 为 model name，部分 ARM 内核只提供 Hardware 或 Model
+fn parse_cpuinfo(cpuinfo: &str) -> Option<String> {
+    ["model name", "Hardware", "Model"].iter().find_map(|key| {
+        cpuinfo.lines().find_map(|line| {
+            let (name, value) = line.split_once(':')?;
+            (name.trim() == *key && !value.trim().is_empty()).then(|| value.trim().to_string())
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::CommandOutput;
+    use std::io;
+
+    // 按命令行返回固定输出，未登记的命令视为不存在
+    struct FakeRunner(Vec<(&'static str, &'static str)>);
+
+    impl CommandRunner for FakeRunner {
+        fn run(&self, command: &CommandSpec) -> io::Result<CommandOutput> {
+            let mut line = command.program.to_string_lossy().into_owned();
+            for arg in &command.args {
+                line.push(' ');
+                line.push_str(&arg.to_string_lossy());
+            }
+            match self.0.iter().find(|(key, _)| *key == line) {
+                Some((_, stdout)) => Ok(CommandOutput {
+                    success: true,
+                    stdout: stdout.to_string(),
+                    ..CommandOutput::default()
+                }),
+                None => Err(io::Error::new(io::ErrorKind::NotFound, line)),
+            }
+        }
+    }
+
+    #[test]
+    fn test_collect_environment() {
+        let runner = FakeRunner(vec![
+            (
+                "rustc -vV",
+                "rustc 1.82.0 (f6e511eec 2024-10-15)\nbinary: rustc\nhost: x86_64-unknown-linux-gnu\nrelease: 1.82.0\n",
+            ),
+            ("cargo -V", "cargo 1.82.0 (8f40fc59f 2024-08-21)\n"),
+            ("git rev-parse HEAD", "b3423c3f00000000000000000000000000000000\n"),
+        ]);
+        let environment = collect_environment(&runner);
+        assert_eq!(
+            environment.rustc.as_deref(),
+            Some("rustc 1.82.0 (f6e511eec 2024-10-15)")
+        );
+        assert_eq!(
+            environment.host.as_deref(),
+            Some("x86_64-unknown-linux-gnu")
+        );
+        assert_eq!(
+            environment.cargo.as_deref(),
+            Some("cargo 1.82.0 (8f40fc59f 2024-08-21)")
+        );
+        // 未安装 clippy
+        assert_eq!(environment.clippy, None);
+        assert_eq!(
+            environment.git_commit.as_deref(),
+            Some("b3423c3f00000000000000000000000000000000")
+        );
+        assert_eq!(environment.grader_version, env!("CARGO_PKG_VERSION"));
+        assert!(environment.started_at.ends_with('Z'));
+        assert_eq!(environment.finished_at, None);
+    }
+
+    #[test]
+    fn test_parse_cpuinfo() {
+        let x86 = "processor\t: 0\nvendor_id\t: GenuineIntel\nmodel name\t: Intel(R) Xeon(R) CPU @ 2.20GHz\n";
+        assert_eq!(
+            parse_cpuinfo(x86).as_deref(),
+            Some("Intel(R) Xeon(R) CPU @ 2.20GHz")
+        );
+        let arm =
+            "processor\t: 0\nBogoMIPS\t: 108.00\n\nModel\t\t: Raspberry Pi 4 Model B Rev 1.4\n";
+        assert_eq!(
+            parse_cpuinfo(arm).as_deref(),
+            Some("Raspberry Pi 4 Model B Rev 1.4")
+        );
+        assert_eq!(parse_cpuinfo("processor\t: 0\n"), None);
+    }
+}
@@ -0,0 +1,1518 @@
+use crate::config::{Differential, Exercise, ResourceLimits};
+use crate::deadline::{late_days, penalised_score};
+use crate::differential::{harness_source, parse_outcome};
+use crate::events::{emit, Event};
+use crate::report::{Attempt, DifferentialResult, ExerciseResult, Verdict};
+use crate::reset::copy_exercise_files;
+use crate::runner::{CommandOutput, CommandRunner, CommandSpec};
+use crate::say;
+use crate::timestamp::parse_rfc3339;
+use crate::workspace::{package_name, SharedWorkspace};
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Instant;
+
+// 评测单道习题时的上下文
+pub struct EvalContext<'a> {
+    pub runner: &'a dyn CommandRunner,
+    pub exercise: &'a Exercise,
+    // 习题在磁盘上的路径，例如 ./exercises/easy/algorithm1.rs
+    pub path: PathBuf,
+    // 第一次超出资源限制时的判定
+    pub verdict: Cell<Option<Verdict>>,
+}
+
+impl EvalContext<'_> {
+    // 执行评测阶段的命令，并发出 stage_started / stage_finished 事件
+    pub fn run_stage(&self, stage: &str, command: &CommandSpec) -> io::Result<CommandOutput> {
+        let name = self.exercise.name.as_str();
+        emit(&Event::StageStarted {
+            exercise: name,
+            stage,
+        });
+        let start = Instant::now();
+        let output = self.runner.run(command);
+        let (success, stdout, stderr) = match &output {
+            Ok(out) => (out.success, out.stdout.clone(), out.stderr.clone()),
+            Err(e) => (false, String::new(), e.to_string()),
+        };
+        if let Ok(CommandOutput {
+            verdict: Some(verdict),
+            ..
+        }) = &output
+        {
+            if self.verdict.get().is_none() {
+                self.verdict.set(Some(*verdict));
+            }
+        }
+        emit(&Event::StageFinished {
+            exercise: name,
+            stage,
+            success,
+            duration_ms: start.elapsed().as_millis() as u64,
+            stdout: &stdout,
+            stderr: &stderr,
+        });
+        output
+    }
+
+    // 执行评测阶段的命令，只关心是否成功
+    pub fn stage_succeeds(&self, stage: &str, command: &CommandSpec) -> bool {
+        self.run_stage(stage, command)
+            .is_ok_and(|output| output.success)
+    }
+
+    // 为运行学生代码的测试二进制加上习题的资源限制
+    // 限制输出时追加 --nocapture，使测试中的输出实时写出并计入限额
+    pub fn limited_test_command(&self, command: CommandSpec) -> CommandSpec {
+        let limits: ResourceLimits = self.exercise.limits;
+        let command = command.limits(limits);
+        match limits.max_output_kb {
+            Some(_) => command.arg("--nocapture"),
+            None => command,
+        }
+    }
+}
+
+// 某一类习题（exercise_config.json 中的 type）的评测方式
+pub trait Evaluator {
+    // 评测学生可见的测试，attempts 用于记录测试阶段的每次尝试
+    fn evaluate(&self, ctx: &EvalContext, attempts: &mut Vec<Attempt>) -> bool;
+
+    // 评测教师端隐藏测试，默认不支持
+    fn evaluate_hidden(&self, ctx: &EvalContext, _hidden_path: &Path) -> bool {
+        eprintln!(
+            "Hidden tests are not supported for exercise type: {}",
+            ctx.exercise.exercise_type
+        );
+        false
+    }
+
+    // 与教师参考实现对拍，默认不支持
+    fn evaluate_differential(
+        &self,
+        ctx: &EvalContext,
+        config: &Differential,
+    ) -> DifferentialResult {
+        eprintln!(
+            "Differential testing is not supported for exercise type: {}",
+            ctx.exercise.exercise_type
+        );
+        parse_outcome(config, None)
+    }
+}
+
+// 从 cargo test --no-run --message-format=json 的输出中取出测试二进制路径
+fn test_executables(build_stdout: &str) -> Vec<PathBuf> {
+    build_stdout
+        .lines()
+        .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+        .filter(|message| {
+            message["reason"] == "compiler-artifact" && message["profile"]["test"] == true
+        })
+        .filter_map(|message| message["executable"].as_str().map(PathBuf::from))
+        .collect()
+}
+
+// 按习题类型注册的评测器
+pub struct EvaluatorRegistry {
+    evaluators: HashMap<String, Box<dyn Evaluator>>,
+}
+
+impl EvaluatorRegistry {
+    // 创建不含任何评测器的空注册表
+    pub fn new() -> Self {
+        Self {
+            evaluators: HashMap::new(),
+        }
+    }
+
+    // 注册评测器，同名类型会被覆盖
+    pub fn register(&mut self, exercise_type: &str, evaluator: impl Evaluator + 'static) {
+        self.evaluators
+            .insert(exercise_type.to_string(), Box::new(evaluator));
+    }
+
+    pub fn get(&self, exercise_type: &str) -> Option<&dyn Evaluator> {
+        self.evaluators.get(exercise_type).map(Box::as_ref)
+    }
+}
+
+impl Default for EvaluatorRegistry {
+    // 内置 single_file 与 cargo_project 两种习题类型
+    fn default() -> Self {
+        let mut registry = Self::new();
+        registry.register("single_file", SingleFileEvaluator);
+        registry.register("cargo_project", CargoProjectEvaluator::default());
+        registry
+    }
+}
+
+pub struct Grader<R: CommandRunner> {
+    runner: R,
+    registry: EvaluatorRegistry,
+    exercises_dir: PathBuf,
+}
+
+impl<R: CommandRunner> Grader<R> {
+    pub fn new(runner: R) -> Self {
+        Self {
+            runner,
+            registry: EvaluatorRegistry::default(),
+            exercises_dir: PathBuf::from("./exercises"),
+        }
+    }
+
+    pub fn with_registry(mut self, registry: EvaluatorRegistry) -> Self {
+        self.registry = registry;
+        self
+    }
+
+    pub fn with_exercises_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.exercises_dir = dir.into();
+        self
+    }
+
+    pub fn registry_mut(&mut self) -> &mut EvaluatorRegistry {
+        &mut self.registry
+    }
+
+    pub fn runner(&self) -> &R {
+        &self.runner
+    }
+
+    // 评测一道习题（含隐藏测试）并计算得分
+    pub fn evaluate(&self, exercise: &Exercise) -> ExerciseResult {
+        emit(&Event::ExerciseStarted {
+            exercise: &exercise.name,
+            exercise_type: &exercise.exercise_type,
+            path: &exercise.path,
+        });
+        let exercise_start = Instant::now();
+        let ctx = EvalContext {
+            runner: &self.runner,
+            exercise,
+            path: self.exercises_dir.join(&exercise.path),
+            verdict: Cell::new(None),
+        };
+
+        let mut attempts = Vec::new();
+        let mut differential = None;
+        let (visible_result, hidden_result) = match self.registry.get(&exercise.exercise_type) {
+            Some(evaluator) => {
+                let visible_result = evaluator.evaluate(&ctx, &mut attempts);
+                let hidden_result = exercise.hidden_tests.as_ref().map(|hidden_path| {
+                    let passed = evaluator.evaluate_hidden(&ctx, Path::new(hidden_path));
+                    if passed {
+                        say!("\x1b[32m{}: HIDDEN TESTS PASSED\x1b[0m", ctx.path.display());
+                    } else {
+                        say!("\x1b[31m{}: HIDDEN TESTS FAILED\x1b[0m", ctx.path.display());
+                    }
+                    passed
+                });
+                differential = exercise.differential.as_ref().map(|config| {
+                    let outcome = evaluator.evaluate_differential(&ctx, config);
+                    if outcome.passed {
+                        say!(
+                            "\x1b[32m{}: DIFFERENTIAL TESTS PASSED ({} cases)\x1b[0m",
+                            ctx.path.display(),
+                            outcome.cases
+                        );
+                    } else {
+                        say!(
+                            "\x1b[31m{}: DIFFERENTIAL TESTS FAILED\x1b[0m",
+                            ctx.path.display()
+                        );
+                        if let Some(mismatch) = &outcome.mismatch {
+                            say!(
+                                "  case {}: input {}, expected {}, got {}",
+                                mismatch.case,
+                                mismatch.input,
+                                mismatch.expected,
+                                mismatch.actual
+                            );
+                        }
+                    }
+                    outcome
+                });
+                (visible_result, hidden_result)
+            }
+            None => {
+                eprintln!("Unknown exercise type: {}", exercise.exercise_type);
+                (false, None)
+            }
+        };
+        // 隐藏测试或对拍未通过时同样不得分
+        let result = visible_result
+            && hidden_result.unwrap_or(true)
+            && differential.as_ref().is_none_or(|outcome| outcome.passed);
+        // 重试后通过时不再保留之前的超限判定
+        let verdict = ctx.verdict.get().filter(|_| !result);
+        match verdict {
+            Some(Verdict::MemoryLimitExceeded) => {
+                say!(
+                    "\x1b[31m{}: MEMORY LIMIT EXCEEDED\x1b[0m",
+                    ctx.path.display()
+                )
+            }
+            Some(Verdict::OutputLimitExceeded) => {
+                say!(
+                    "\x1b[31m{}: OUTPUT LIMIT EXCEEDED\x1b[0m",
+                    ctx.path.display()
+                )
+            }
+            Some(Verdict::LimitsUnavailable) => {
+                say!(
+                    "\x1b[31m{}: RESOURCE LIMITS UNAVAILABLE (prlimit not found)\x1b[0m",
+                    ctx.path.display()
+                )
+            }
+            None => {}
+        }
+        let raw_score = if result { exercise.score } else { 0 };
+        let late_days = self.late_days(&ctx);
+        let score = match late_days {
+            Some(days) => {
+                let policy = exercise.late_policy.unwrap_or_default();
+                let score = penalised_score(raw_score, days, policy);
+                if days > 0 {
+                    say!(
+                        "\x1b[33m{}: SUBMITTED {} DAY(S) LATE, SCORE {} -> {}\x1b[0m",
+                        ctx.path.display(),
+                        days,
+                        raw_score,
+                        score
+                    );
+                }
+                score
+            }
+            None => raw_score,
+        };
+
+        let elapsed = exercise_start.elapsed();
+        let exercise_result = ExerciseResult {
+            name: exercise.name.clone(),
+            result,
+            score,
+            visible_result: (hidden_result.is_some() || differential.is_some())
+                .then_some(visible_result),
+            hidden_result,
+            time: elapsed.as_secs(),
+            time_ms: elapsed.as_millis() as u64,
+            attempts,
+            raw_score: late_days.map(|_| raw_score),
+            late_days,
+            verdict,
+            differential,
+        };
+        emit(&Event::ExerciseFinished {
+            exercise: &exercise_result.name,
+            result: exercise_result.result,
+            score: exercise_result.score,
+            visible_result: exercise_result.visible_result,
+            hidden_result: exercise_result.hidden_result,
+            time: exercise_result.time,
+            time_ms: exercise_result.time_ms,
+        });
+        exercise_result
+    }
+
+    // 习题文件（含 extra_files）最后一次提交相对截止时间的迟交天数
+    // 未设置截止时间或取不到提交时间时返回 None，此时不扣分
+    fn late_days(&self, ctx: &EvalContext) -> Option<u32> {
+        let deadline = ctx.exercise.deadline.as_deref()?;
+        let Some(deadline_secs) = parse_rfc3339(deadline) else {
+            eprintln!("Invalid deadline for {}: {}", ctx.exercise.name, deadline);
+            return None;
+        };
+        let committed_at = self
+            .runner
+            .run(
+                &CommandSpec::new("git")
+                    .args(["log", "-1", "--format=%ct", "--"])
+                    .args(
+                        ctx.exercise
+                            .source_paths()
+                            .map(|path| self.exercises_dir.join(path)),
+                    ),
+            )
+            .ok()
+            .filter(|output| output.success)
+            .and_then(|output| output.stdout.trim().parse::<i64>().ok());
+        match committed_at {
+            Some(committed_at) => Some(late_days(committed_at, deadline_secs)),
+            None => {
+                eprintln!(
+                    "No commit found for {}, skipping deadline check",
+                    ctx.path.display()
+                );
+                None
+            }
+        }
+    }
+}
+
+// 评测单文件 Rust 习题（使用 rustc --test 并执行测试）
+pub struct SingleFileEvaluator;
+
+impl Evaluator for SingleFileEvaluator {
+    fn evaluate(&self, ctx: &EvalContext, attempts: &mut Vec<Attempt>) -> bool {
+        let file_path = &ctx.path;
+        // 获取文件名（不带扩展名）
+        let test_binary = file_path.with_extension("");
+
+        // 编译测试文件
+        let compile_output = ctx.run_stage(
+            "compile",
+            &CommandSpec::new("rustc")
+                .arg("--test") // 使用 rustc --test 进行编译
+                .arg(file_path)
+                .arg("-o")
+                .arg(&test_binary), // 指定输出文件
+        );
+
+        if let Ok(output) = compile_output {
+            if output.success {
+                // 编译成功，运行测试二进制文件
+                let test_passed =
+                    run_with_retries(ctx.exercise.max_test_attempts(), attempts, || {
+                        let command = ctx.limited_test_command(CommandSpec::new(&test_binary));
+                        match ctx.run_stage("test", &command) {
+                            Ok(test_run) => {
+                                if test_run.success {
+                                    say!("\x1b[32m{}: TEST PASSED\x1b[0m", file_path.display());
+                                    true
+                                } else {
+                                    say!("\x1b[31m{}: TEST FAILED\x1b[0m", file_path.display());
+                                    false
+                                }
+                            }
+                            Err(_) => {
+                                eprintln!(
+                                    "Error running test executable for {}",
+                                    file_path.display()
+                                );
+                                false
+                            }
+                        }
+                    });
+
+                // 删除测试二进制文件
+                if let Err(e) = fs::remove_file(&test_binary) {
+                    eprintln!(
+                        "Failed to remove test binary {}: {}",
+                        test_binary.display(),
+                        e
+                    );
+                } else {
+                    say!(
+                        "Successfully removed test binary: {}",
+                        test_binary.display()
+                    );
+                }
+
+                test_passed
+            } else {
+                // 编译失败
+                eprintln!("\x1b[31m{}: COMPILATION FAILED\x1b[0m", file_path.display());
+                false
+            }
+        } else {
+            eprintln!("Error executing rustc --test for {}", file_path.display());
+            false
+        }
+    }
+
+    // 将隐藏测试作为额外的测试模块追加到单文件习题副本中，只运行该模块的测试
+    fn evaluate_hidden(&self, ctx: &EvalContext, hidden_path: &Path) -> bool {
+        let hidden = match fs::read_to_string(hidden_path) {
+            Ok(hidden) => hidden,
+            Err(e) => {
+                eprintln!(
+                    "Error reading hidden tests for {}: {}",
+                    ctx.path.display(),
+                    e
+                );
+                return false;
+            }
+        };
+        self.run_extra_module(ctx, "hidden", "hidden_tests", &hidden)
+            .is_some_and(|output| output.success)
+    }
+
+    fn evaluate_differential(
+        &self,
+        ctx: &EvalContext,
+        config: &Differential,
+    ) -> DifferentialResult {
+        let output = match fs::read_to_string(&config.file) {
+            Ok(code) => self.run_extra_module(
+                ctx,
+                "differential",
+                "differential",
+                &harness_source(config, &code),
+            ),
+            Err(e) => {
+                eprintln!("Error reading {}: {}", config.file, e);
+                None
+            }
+        };
+        parse_outcome(config, output.as_ref())
+    }
+}
+
+impl SingleFileEvaluator {
+    // 把 code 作为模块 module 追加到习题副本末尾，编译（<stage>_compile）后只运行该模块的测试（<stage>_test）
+    // 编译失败或无法执行时返回 None
+    fn run_extra_module(
+        &self,
+        ctx: &EvalContext,
+        stage: &str,
+        module: &str,
+        code: &str,
+    ) -> Option<CommandOutput> {
+        let file_path = &ctx.path;
+        let source = match fs::read_to_string(file_path) {
+            Ok(source) => source,
+            Err(e) => {
+                eprintln!("Error reading {}: {}", file_path.display(), e);
+                return None;
+            }
+        };
+
+        // 拼接后的源文件与测试二进制都放在本次评测独有的临时目录，不污染学生目录
+        let scratch = match ScratchDir::create(stage) {
+            Ok(scratch) => scratch,
+            Err(e) => {
+                eprintln!("Failed to create a temporary directory: {}", e);
+                return None;
+            }
+        };
+        let stem = file_path.file_stem().unwrap_or_default().to_string_lossy();
+        let spliced_path = scratch.path().join(format!("{}_{}.rs", stem, stage));
+        let test_binary = spliced_path.with_extension("");
+        // 副本不在习题目录下，#[path] 引用的同目录模块（如 graph.rs）需要改成绝对路径
+        let exercise_dir = file_path.parent().unwrap_or(Path::new("."));
+        let exercise_dir = fs::canonicalize(exercise_dir).unwrap_or(exercise_dir.to_path_buf());
+        let spliced = format!(
+            "{}\n#[cfg(test)]\nmod {} {{\n#[allow(unused_imports)]\nuse super::*;\n{}\n}}\n",
+            resolve_module_paths(&source, &exercise_dir),
+            module,
+            code
+        );
+        if let Err(e) = fs::write(&spliced_path, spliced) {
+            eprintln!("Failed to write {}: {}", spliced_path.display(), e);
+            return None;
+        }
+
+        let compiled = ctx.stage_succeeds(
+            &format!("{}_compile", stage),
+            &CommandSpec::new("rustc")
+                .arg("--test")
+                .arg(&spliced_path)
+                .arg("-o")
+                .arg(&test_binary),
+        );
+        let output = if compiled {
+            ctx.run_stage(
+                &format!("{}_test", stage),
+                &ctx.limited_test_command(
+                    CommandSpec::new(&test_binary).arg(format!("{}::", module)),
+                ),
+            )
+            .ok()
+        } else {
+            None
+        };
+        output
+    }
+}
+
+// 评测 Cargo 项目
+#[derive(Default)]
+pub struct CargoProjectEvaluator {
+    // 设置时通过共享工作区以 -p <包名> 构建，各题共用 target 目录且不再逐题清理
+    workspace: Option<(PathBuf, PathBuf)>,
+}
+
+impl CargoProjectEvaluator {
+    pub fn in_workspace(workspace: &SharedWorkspace) -> Self {
+        Self {
+            workspace: Some((
+                workspace.manifest_path().to_path_buf(),
+                workspace.target_dir().to_path_buf(),
+            )),
+        }
+    }
+
+    // 运行 Cargo 命令
+    fn run_cargo_command(&self, ctx: &EvalContext, stage: &str, args: &[&str]) -> bool {
+        ctx.stage_succeeds(stage, &self.cargo_command(ctx, args))
+    }
+
+    // 运行 cargo test：设置了资源限制时先不受限地编译（<stage>_build），再在限制下运行测试
+    // cargo 给出以 args 为参数的 cargo 命令，区分学生项目本身与隐藏测试使用的副本
+    fn cargo_tests(
+        &self,
+        ctx: &EvalContext,
+        stage: &str,
+        args: &[&str],
+        cargo: &dyn Fn(&[&str]) -> CommandSpec,
+    ) -> io::Result<CommandOutput> {
+        if ctx.exercise.limits.is_empty() {
+            return ctx.run_stage(stage, &cargo(args));
+        }
+        // 限制只加在测试二进制上：cargo 与 rustc 不受内存和进程数限制，
+        // 也不会被输出超限时的终止波及。文档测试不在这条路径上运行
+        let build_args = [args, &["--no-run", "--message-format=json"]].concat();
+        let build_command = cargo(&build_args);
+        let build = ctx.run_stage(&format!("{}_build", stage), &build_command)?;
+        if !build.success {
+            return Ok(build);
+        }
+        let dir = build_command
+            .current_dir
+            .clone()
+            .unwrap_or_else(|| ctx.path.clone());
+        let mut combined = CommandOutput {
+            success: true,
+            stdout: String::new(),
+            stderr: String::new(),
+            verdict: None,
+        };
+        // 与 cargo test 一样，某个测试二进制失败后不再运行后面的
+        for executable in test_executables(&build.stdout) {
+            let command = ctx.limited_test_command(CommandSpec::new(executable).current_dir(&dir));
+            let output = ctx.run_stage(stage, &command)?;
+            combined.stdout.push_str(&output.stdout);
+            combined.stderr.push_str(&output.stderr);
+            combined.verdict = combined.verdict.or(output.verdict);
+            if !output.success {
+                combined.success = false;
+                break;
+            }
+        }
+        Ok(combined)
+    }
+
+    fn cargo_command(&self, ctx: &EvalContext, args: &[&str]) -> CommandSpec {
+        match &self.workspace {
+            Some((manifest_path, target_dir)) => {
+                let package = package_name(&ctx.path).unwrap_or_else(|| {
+                    ctx.path
+                        .file_name()
+                        .unwrap_or_default()
+                        .to_string_lossy()
+                        .into_owned()
+                });
+                CommandSpec::new("cargo")
+                    .args(args)
+                    .arg("--manifest-path")
+                    .arg(manifest_path)
+                    .arg("--target-dir")
+                    .arg(target_dir)
+                    .arg("-p")
+                    .arg(package)
+            }
+            None => CommandSpec::new("cargo").args(args).current_dir(&ctx.path),
+        }
+    }
+
+    // 独立构建时清理 target 目录，共享工作区的 target 目录留给后续习题复用
+    fn clean_up(&self, proj_path: &Path) {
+        if self.workspace.is_none() {
+            clean_target_directory(proj_path);
+        }
+    }
+}
+
+impl Evaluator for CargoProjectEvaluator {
+    fn evaluate(&self, ctx: &EvalContext, attempts: &mut Vec<Attempt>) -> bool {
+        let proj_path = &ctx.path;
+        let build_success = self.run_cargo_command(ctx, "build", &["build"]);
+        let test_success = run_with_retries(ctx.exercise.max_test_attempts(), attempts, || {
+            self.cargo_tests(ctx, "test", &["test"], &|args| {
+                self.cargo_command(ctx, args)
+            })
+            .is_ok_and(|output| output.success)
+        });
+        let clippy_success = self.run_cargo_command(ctx, "clippy", &["clippy"]);
+
+        let passed = build_success && test_success && clippy_success;
+
+        if passed {
+            say!("\x1b[32m{}: PASSED\x1b[0m", proj_path.display());
+        } else {
+            say!("\x1b[31m{}: FAILED\x1b[0m", proj_path.display());
+        }
+
+        self.clean_up(proj_path);
+
+        passed
+    }
+
+    // 将隐藏测试复制进项目并临时注册为额外的 [[test]] 目标
+    fn evaluate_hidden(&self, ctx: &EvalContext, hidden_path: &Path) -> bool {
+        let hidden = match fs::read_to_string(hidden_path) {
+            Ok(hidden) => hidden,
+            Err(e) => {
+                eprintln!(
+                    "Failed to read hidden tests for {}: {}",
+                    ctx.path.display(),
+                    e
+                );
+                return false;
+            }
+        };
+        self.run_extra_test_target(ctx, "hidden_test", "hidden_tests", &hidden)
+            .is_some_and(|output| output.success)
+    }
+
+    fn evaluate_differential(
+        &self,
+        ctx: &EvalContext,
+        config: &Differential,
+    ) -> DifferentialResult {
+        let output = match fs::read_to_string(&config.file) {
+            Ok(code) => self.run_extra_test_target(
+                ctx,
+                "differential_test",
+                "differential",
+                &harness_source(config, &code),
+            ),
+            Err(e) => {
+                eprintln!("Error reading {}: {}", config.file, e);
+                None
+            }
+        };
+        parse_outcome(config, output.as_ref())
+    }
+}
+
+impl CargoProjectEvaluator {
+    // 把项目复制到临时目录，在副本中写入 src/__<target>.rs 并注册为 [[test]] 目标运行
+    // 学生目录始终不被修改，评测中途被中断也不会留下隐藏测试
+    fn run_extra_test_target(
+        &self,
+        ctx: &EvalContext,
+        stage: &str,
+        target: &str,
+        code: &str,
+    ) -> Option<CommandOutput> {
+        let copy = match ScratchDir::create(stage).and_then(|scratch| {
+            self.copy_project(ctx, scratch.path(), target, code)?;
+            Ok(scratch)
+        }) {
+            Ok(copy) => copy,
+            Err(e) => {
+                eprintln!(
+                    "Failed to prepare {} for {}: {}",
+                    target,
+                    ctx.path.display(),
+                    e
+                );
+                return None;
+            }
+        };
+        // 共享工作区模式下复用工作区的 target 目录，依赖不必重新编译
+        let cargo = |args: &[&str]| {
+            let command = CommandSpec::new("cargo")
+                .args(args)
+                .current_dir(copy.path());
+            match &self.workspace {
+                Some((_, target_dir)) => command.arg("--target-dir").arg(target_dir),
+                None => command,
+            }
+        };
+        self.cargo_tests(ctx, stage, &["test", "--test", target], &cargo)
+            .ok()
+    }
+
+    // 把学生项目（不含 target 目录）复制到 copy 并拼接额外的测试目标
+    fn copy_project(
+        &self,
+        ctx: &EvalContext,
+        copy: &Path,
+        target: &str,
+        code: &str,
+    ) -> io::Result<()> {
+        copy_exercise_files(&ctx.path, copy)?;
+        // 工作区成员自己没有 Cargo.lock，沿用工作区锁定的依赖版本
+        if let Some((manifest_path, _)) = &self.workspace {
+            let lock = manifest_path.with_file_name("Cargo.lock");
+            if !copy.join("Cargo.lock").exists() && lock.exists() {
+                fs::copy(lock, copy.join("Cargo.lock"))?;
+            }
+        }
+        let test_file = format!("src/__{}.rs", target);
+        fs::write(copy.join(&test_file), code)?;
+        let manifest = fs::read_to_string(copy.join("Cargo.toml"))?;
+        fs::write(
+            copy.join("Cargo.toml"),
+            format!(
+                "{}\n[[test]]\nname = \"{}\"\npath = \"{}\"\n",
+                manifest, target, test_file
+            ),
+        )?;
+        Ok(())
+    }
+}
+
+// 评测期间使用的临时目录，以进程号与序号区分，多个评测器同时运行也不会冲突；离开作用域时删除
+pub struct ScratchDir {
+    path: PathBuf,
+}
+
+impl ScratchDir {
+    pub fn create(label: &str) -> io::Result<Self> {
+        static NEXT: AtomicU64 = AtomicU64::new(0);
+        loop {
+            let path = std::env::temp_dir().join(format!(
+                "grader-{}-{}-{}",
+                label,
+                std::process::id(),
+                NEXT.fetch_add(1, Ordering::Relaxed)
+            ));
+            // create_dir 在目录已存在时失败，遗留的同名目录不会被复用
+            match fs::create_dir(&path) {
+                Ok(()) => return Ok(Self { path }),
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for ScratchDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
+
+// 测试阶段失败时重跑，最多 max_attempts 次；允许重试时记录每次尝试
+pub fn run_with_retries(
+    max_attempts: u32,
+    attempts: &mut Vec<Attempt>,
+    mut run_tests: impl FnMut() -> bool,
+) -> bool {
+    for attempt in 1..=max_attempts {
+        if attempt > 1 {
+            say!("Retrying test stage (attempt {}/{})", attempt, max_attempts);
+        }
+        let start = Instant::now();
+        let passed = run_tests();
+        if max_attempts > 1 {
+            attempts.push(Attempt {
+                stage: "test".to_string(),
+                passed,
+                time_ms: start.elapsed().as_millis() as u64,
+            });
+        }
+        if passed {
+            return true;
+        }
+    }
+    false
+}
+
+// 清理 target 目录
+fn clean_target_directory(proj_path: &Path) {
+    let target_dir = proj_path.join("target");
+
+    if target_dir.exists() {
+        if let Err(e) = fs::remove_dir_all(&target_dir) {
+            eprintln!("Failed to clean up target directory: {}", e);
+        } else {
+            say!(
+                "Successfully cleaned up target directory in: {}",
+                proj_path.display()
+            );
+        }
+    }
+}
+
+// 将 #[path = "..."] 中的相对路径改为相对 dir 的绝对路径
+fn resolve_module_paths(source: &str, dir: &Path) -> String {
+    let mut resolved = String::with_capacity(source.len());
+    for line in source.split_inclusive('\n') {
+        let relative = line
+            .trim()
+            .strip_prefix("#[path = \"")
+            .and_then(|rest| rest.strip_suffix("\"]"))
+            .filter(|path| Path::new(path).is_relative());
+        match relative {
+            Some(path) => {
+                let indent = &line[..line.len() - line.trim_start().len()];
+                let absolute = dir.join(path).to_string_lossy().into_owned();
+                resolved.push_str(&format!("{}#[path = {:?}]\n", indent, absolute));
+            }
+            None => resolved.push_str(line),
+        }
+    }
+    resolved
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::RetryOn;
+    use crate::deadline::LatePolicy;
+    use std::cell::RefCell;
+    use std::collections::VecDeque;
+
+    // 记录收到的命令并按脚本返回结果，不启动任何进程
+    #[derive(Default)]
+    struct FakeRunner {
+        commands: RefCell<Vec<CommandSpec>>,
+        // (程序名或首个参数, 依次返回的结果)，未配置的命令默认成功
+        scripted: RefCell<Vec<(String, VecDeque<bool>)>>,
+        // (程序名或首个参数, 标准输出)，未配置的命令输出为空
+        stdout: Vec<(String, String)>,
+        // (程序名或首个参数, 超限判定)，配置了判定的命令总是失败
+        verdicts: Vec<(String, Verdict)>,
+    }
+
+    impl FakeRunner {
+        fn script(self, key: &str, results: &[bool]) -> Self {
+            self.scripted
+                .borrow_mut()
+                .push((key.to_string(), results.iter().copied().collect()));
+            self
+        }
+
+        fn script_stdout(mut self, key: &str, stdout: &str) -> Self {
+            self.stdout.push((key.to_string(), stdout.to_string()));
+            self
+        }
+
+        fn script_verdict(mut self, key: &str, verdict: Verdict) -> Self {
+            self.verdicts.push((key.to_string(), verdict));
+            self
+        }
+
+        fn command_lines(&self) -> Vec<String> {
+            self.commands
+                .borrow()
+                .iter()
+                .map(|command| {
+                    let mut line = command.program.to_string_lossy().into_owned();
+                    for arg in &command.args {
+                        line.push(' ');
+                        line.push_str(&arg.to_string_lossy());
+                    }
+                    line
+                })
+                .collect()
+        }
+    }
+
+    impl CommandRunner for FakeRunner {
+        fn run(&self, command: &CommandSpec) -> io::Result<CommandOutput> {
+            self.commands.borrow_mut().push(command.clone());
+            let program = command.program.to_string_lossy();
+            let first_arg = command
+                .args
+                .first()
+                .map(|arg| arg.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            let matches = |key: &str| program.ends_with(key) || key == first_arg;
+            let mut scripted = self.scripted.borrow_mut();
+            let success = scripted
+                .iter_mut()
+                .find(|(key, _)| matches(key))
+                .and_then(|(_, results)| results.pop_front())
+                .unwrap_or(true);
+            let stdout = self
+                .stdout
+                .iter()
+                .find(|(key, _)| matches(key))
+                .map(|(_, stdout)| stdout.clone())
+                .unwrap_or_default();
+            let verdict = self
+                .verdicts
+                .iter()
+                .find(|(key, _)| matches(key))
+                .map(|&(_, verdict)| verdict);
+            Ok(CommandOutput {
+                success: success && verdict.is_none(),
+                stdout,
+                verdict,
+                ..CommandOutput::default()
+            })
+        }
+    }
+
+    fn exercise(name: &str, path: &str, exercise_type: &str, score: i32) -> Exercise {
+        Exercise {
+            name: name.to_string(),
+            path: path.to_string(),
+            exercise_type: exercise_type.to_string(),
+            score,
+            extra_files: Vec::new(),
+            hidden_tests: None,
+            retries: 0,
+            retry_on: None,
+            deadline: None,
+            late_policy: None,
+            hint: None,
+            limits: ResourceLimits::default(),
+            differential: None,
+        }
+    }
+
+    fn grader(runner: FakeRunner) -> Grader<FakeRunner> {
+        Grader::new(runner).with_exercises_dir("/nonexistent/exercises")
+    }
+
+    #[test]
+    fn test_single_file_passes() {
+        let grader = grader(FakeRunner::default());
+        let result = grader.evaluate(&exercise(
+            "algorithm1.rs",
+            "easy/algorithm1.rs",
+            "single_file",
+            1,
+        ));
+
+        assert!(result.result);
+        assert_eq!(result.score, 1);
+        assert_eq!(
+            grader.runner.command_lines(),
+            vec![
+                "rustc --test /nonexistent/exercises/easy/algorithm1.rs -o /nonexistent/exercises/easy/algorithm1",
+                "/nonexistent/exercises/easy/algorithm1",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_single_file_compile_failure_skips_tests() {
+        let grader = grader(FakeRunner::default().script("rustc", &[false]));
+        let result = grader.evaluate(&exercise(
+            "algorithm1.rs",
+            "easy/algorithm1.rs",
+            "single_file",
+            1,
+        ));
+
+        assert!(!result.result);
+        assert_eq!(result.score, 0);
+        assert_eq!(grader.runner.command_lines().len(), 1);
+    }
+
+    #[test]
+    fn test_ndjson_stdout_holds_only_events() {
+        let grader = grader(FakeRunner::default().script("clippy", &[false]));
+        let stdout = crate::events::capture_ndjson_stdout(|| {
+            grader.evaluate(&exercise(
+                "algorithm1.rs",
+                "easy/algorithm1.rs",
+                "single_file",
+                1,
+            ));
+            grader.evaluate(&exercise(
+                "solution1",
+                "normal/solution1",
+                "cargo_project",
+                6,
+            ));
+        });
+
+        let events: Vec<String> = stdout
+            .lines()
+            .map(|line| {
+                let value: serde_json::Value = serde_json::from_str(line)
+                    .unwrap_or_else(|e| panic!("not an event line {:?}: {}", line, e));
+                value["event"].as_str().unwrap().to_string()
+            })
+            .collect();
+        assert_eq!(events.first().map(String::as_str), Some("exercise_started"));
+        assert_eq!(events.last().map(String::as_str), Some("exercise_finished"));
+        assert_eq!(
+            events.iter().filter(|e| *e == "exercise_finished").count(),
+            2
+        );
+        assert!(events.iter().any(|e| e == "stage_finished"));
+    }
+
+    #[test]
+    fn test_cargo_project_runs_all_stages() {
+        let grader = grader(FakeRunner::default().script("clippy", &[false]));
+        let result = grader.evaluate(&exercise(
+            "solution1",
+            "normal/solution1",
+            "cargo_project",
+            6,
+        ));
+
+        assert!(!result.result);
+        assert_eq!(
+            grader.runner.command_lines(),
+            vec!["cargo build", "cargo test", "cargo clippy"]
+        );
+        let commands = grader.runner.commands.borrow();
+        assert!(commands.iter().all(|command| command.current_dir
+            == Some(PathBuf::from("/nonexistent/exercises/normal/solution1"))));
+    }
+
+    #[test]
+    fn test_cargo_project_in_shared_workspace() {
+        let dir = std::env::temp_dir().join(format!("grader_shared_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let config = serde_json::from_str(
+            r#"{ "easy": [], "normal": [{ "name": "solution1", "path": "normal/solution1", "type": "cargo_project", "score": 6 }], "hard": [] }"#,
+        )
+        .unwrap();
+        let workspace = SharedWorkspace::create(&config, &dir, &dir.join("target")).unwrap();
+
+        let mut grader = grader(FakeRunner::default());
+        grader.registry_mut().register(
+            "cargo_project",
+            CargoProjectEvaluator::in_workspace(&workspace),
+        );
+        let result = grader.evaluate(&exercise(
+            "solution1",
+            "normal/solution1",
+            "cargo_project",
+            6,
+        ));
+        let manifest = workspace.manifest_path().display().to_string();
+        let target = workspace.target_dir().display().to_string();
+        drop(workspace);
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(result.result);
+        let flags = format!(
+            "--manifest-path {} --target-dir {} -p solution1",
+            manifest, target
+        );
+        assert_eq!(
+            grader.runner.command_lines(),
+            vec![
+                format!("cargo build {}", flags),
+                format!("cargo test {}", flags),
+                format!("cargo clippy {}", flags),
+            ]
+        );
+        assert!(grader.runner.commands.borrow()[0].current_dir.is_none());
+    }
+
+    #[test]
+    fn test_timing_retries_record_every_attempt() {
+        let grader = grader(FakeRunner::default().script("test", &[false, false, true]));
+        let mut solution = exercise("solutiont1", "hard/solutiont1", "cargo_project", 10);
+        solution.retries = 2;
+        solution.retry_on = Some(RetryOn::TestFailure);
+        let result = grader.evaluate(&solution);
+
+        assert!(result.result);
+        assert_eq!(result.score, 10);
+        let attempts: Vec<bool> = result.attempts.iter().map(|a| a.passed).collect();
+        assert_eq!(attempts, vec![false, false, true]);
+    }
+
+    #[test]
+    fn test_retries_exhausted() {
+        let grader = grader(FakeRunner::default().script("test", &[false, false]));
+        let mut solution = exercise("solutiont3", "hard/solutiont3", "cargo_project", 10);
+        solution.retries = 1;
+        solution.retry_on = Some(RetryOn::TestFailure);
+        let result = grader.evaluate(&solution);
+
+        assert!(!result.result);
+        assert_eq!(result.attempts.len(), 2);
+    }
+
+    #[test]
+    fn test_no_attempts_recorded_without_retries() {
+        let grader = grader(FakeRunner::default().script("test", &[false]));
+        let result = grader.evaluate(&exercise(
+            "solution2",
+            "normal/solution2",
+            "cargo_project",
+            6,
+        ));
+
+        assert!(!result.result);
+        assert!(result.attempts.is_empty());
+        assert_eq!(grader.runner.command_lines().len(), 3);
+    }
+
+    #[test]
+    fn test_unknown_type_fails() {
+        let grader = grader(FakeRunner::default());
+        let result = grader.evaluate(&exercise("quiz", "easy/quiz.md", "quiz", 1));
+
+        assert!(!result.result);
+        assert!(grader.runner.command_lines().is_empty());
+    }
+
+    #[test]
+    fn test_register_custom_evaluator() {
+        struct AlwaysPass;
+
+        impl Evaluator for AlwaysPass {
+            fn evaluate(&self, ctx: &EvalContext, _attempts: &mut Vec<Attempt>) -> bool {
+                ctx.stage_succeeds("check", &CommandSpec::new("true"))
+            }
+        }
+
+        let mut grader = grader(FakeRunner::default());
+        grader.registry_mut().register("quiz", AlwaysPass);
+        let result = grader.evaluate(&exercise("quiz", "easy/quiz.md", "quiz", 2));
+
+        assert!(result.result);
+        assert_eq!(result.score, 2);
+        assert_eq!(grader.runner.command_lines(), vec!["true"]);
+    }
+
+    #[test]
+    fn test_hidden_test_failure_withholds_score() {
+        let dir = std::env::temp_dir().join(format!("grader_hidden_{}", std::process::id()));
+        fs::create_dir_all(dir.join("easy")).unwrap();
+        fs::write(
+            dir.join("easy/hidden_demo.rs"),
+            "fn answer() -> i32 { 42 }\n",
+        )
+        .unwrap();
+        let hidden_path = dir.join("hidden_demo_tests.rs");
+        fs::write(
+            &hidden_path,
+            "#[test]\nfn t() { assert_eq!(answer(), 42); }\n",
+        )
+        .unwrap();
+
+        // 可见测试通过，隐藏测试失败
+        let runner = FakeRunner::default().script("hidden_tests::", &[false]);
+        let grader = Grader::new(runner).with_exercises_dir(&dir);
+        let mut demo = exercise("hidden_demo.rs", "easy/hidden_demo.rs", "single_file", 1);
+        demo.hidden_tests = Some(hidden_path.to_string_lossy().into_owned());
+        let result = grader.evaluate(&demo);
+        // 两者都失败时仍能看出可见测试没有通过
+        let both_failed = Grader::new(
+            FakeRunner::default()
+                .script("easy/hidden_demo", &[false])
+                .script("hidden_tests::", &[false]),
+        )
+        .with_exercises_dir(&dir)
+        .evaluate(&demo);
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(!result.result);
+        assert_eq!(result.visible_result, Some(true));
+        assert_eq!(result.hidden_result, Some(false));
+        assert_eq!(result.score, 0);
+        let commands = grader.runner.command_lines();
+        assert_eq!(commands.len(), 4);
+        assert!(commands[3].ends_with("hidden_demo_hidden hidden_tests::"));
+        assert_eq!(both_failed.visible_result, Some(false));
+        assert_eq!(both_failed.hidden_result, Some(false));
+    }
+
+    #[test]
+    fn test_resolve_module_paths() {
+        let source = "#[path = \"graph.rs\"]\nmod graph;\n    #[path = \"/abs/util.rs\"]\nmod util;\nfn main() {}";
+        assert_eq!(
+            resolve_module_paths(source, Path::new("/work/exercises/easy")),
+            "#[path = \"/work/exercises/easy/graph.rs\"]\nmod graph;\n    #[path = \"/abs/util.rs\"]\nmod util;\nfn main() {}"
+        );
+    }
+
+    #[test]
+    fn test_cargo_hidden_tests_run_in_a_copy() {
+        let dir = std::env::temp_dir().join(format!("grader_cargo_hidden_{}", std::process::id()));
+        let project = dir.join("hard/solutiont1");
+        fs::create_dir_all(project.join("src")).unwrap();
+        fs::create_dir_all(project.join("target/debug")).unwrap();
+        let manifest = "[package]\nname = \"solutiont1\"\n";
+        fs::write(project.join("Cargo.toml"), manifest).unwrap();
+        fs::write(
+            project.join("src/lib.rs"),
+            "pub fn answer() -> i32 { 42 }\n",
+        )
+        .unwrap();
+        let hidden_path = dir.join("hidden.rs");
+        fs::write(&hidden_path, "#[test]\nfn t() {}\n").unwrap();
+
+        let grader = Grader::new(FakeRunner::default()).with_exercises_dir(&dir);
+        let mut solution = exercise("solutiont1", "hard/solutiont1", "cargo_project", 10);
+        solution.hidden_tests = Some(hidden_path.to_string_lossy().into_owned());
+        let result = grader.evaluate(&solution);
+
+        // 拼接只发生在副本中
+        let copy = dir.join("copy");
+        fs::create_dir_all(&copy).unwrap();
+        CargoProjectEvaluator::default()
+            .copy_project(
+                &EvalContext {
+                    runner: &grader.runner,
+                    exercise: &solution,
+                    path: project.clone(),
+                    verdict: Cell::new(None),
+                },
+                &copy,
+                "hidden_tests",
+                "#[test]\nfn t() {}\n",
+            )
+            .unwrap();
+        let copied_manifest = fs::read_to_string(copy.join("Cargo.toml")).unwrap();
+        let copied_test = fs::read_to_string(copy.join("src/__hidden_tests.rs")).unwrap();
+        let copied_target = copy.join("target").exists();
+        let student_manifest = fs::read_to_string(project.join("Cargo.toml")).unwrap();
+        let student_files = fs::read_dir(project.join("src")).unwrap().count();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(result.hidden_result, Some(true));
+        assert_eq!(student_manifest, manifest);
+        assert_eq!(student_files, 1);
+        assert!(copied_manifest
+            .ends_with("[[test]]\nname = \"hidden_tests\"\npath = \"src/__hidden_tests.rs\"\n"));
+        assert_eq!(copied_test, "#[test]\nfn t() {}\n");
+        assert!(!copied_target);
+
+        let commands = grader.runner.commands.borrow();
+        let hidden = commands.last().unwrap();
+        assert_eq!(
+            grader.runner.command_lines().last().unwrap(),
+            "cargo test --test hidden_tests"
+        );
+        // 副本所在的临时目录在评测结束后删除
+        let copy_dir = hidden.current_dir.as_ref().unwrap();
+        assert_ne!(copy_dir, &project);
+        assert!(!copy_dir.exists());
+    }
+
+    #[test]
+    fn test_differential_mismatch_withholds_score() {
+        let dir = std::env::temp_dir().join(format!("grader_differential_{}", std::process::id()));
+        fs::create_dir_all(dir.join("easy")).unwrap();
+        fs::write(
+            dir.join("easy/diff_demo.rs"),
+            "fn double(x: u32) -> u32 { x + x }\n",
+        )
+        .unwrap();
+        let reference_path = dir.join("diff_demo_reference.rs");
+        fs::write(
+            &reference_path,
+            "fn generate(rng: &mut Rng) -> u32 { rng.range(0, 100) as u32 }\n",
+        )
+        .unwrap();
+
+        let runner = FakeRunner::default().script("differential::", &[false]).script_stdout(
+            "differential::",
+            "DIFFERENTIAL case=7\nDIFFERENTIAL input=3\nDIFFERENTIAL expected=6\nDIFFERENTIAL actual=9\n",
+        );
+        let grader = Grader::new(runner).with_exercises_dir(&dir);
+        let mut demo = exercise("diff_demo.rs", "easy/diff_demo.rs", "single_file", 1);
+        demo.differential = Some(Differential {
+            file: reference_path.to_string_lossy().into_owned(),
+            cases: 50,
+            seed: 1,
+        });
+        let result = grader.evaluate(&demo);
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(!result.result);
+        assert_eq!(result.score, 0);
+        let outcome = result.differential.unwrap();
+        assert!(!outcome.passed);
+        assert_eq!(outcome.cases, 50);
+        let mismatch = outcome.mismatch.unwrap();
+        assert_eq!((mismatch.case, mismatch.input.as_str()), (7, "3"));
+        assert_eq!(
+            (mismatch.expected.as_str(), mismatch.actual.as_str()),
+            ("6", "9")
+        );
+        let commands = grader.runner.command_lines();
+        assert_eq!(commands.len(), 4);
+        assert!(commands[3].ends_with("diff_demo_differential differential::"));
+    }
+
+    #[test]
+    fn test_limits_apply_to_test_binary_and_report_verdict() {
+        let runner =
+            FakeRunner::default().script_verdict("algorithm1", Verdict::OutputLimitExceeded);
+        let grader = grader(runner);
+        let mut algorithm = exercise("algorithm1.rs", "easy/algorithm1.rs", "single_file", 1);
+        algorithm.limits = ResourceLimits {
+            memory_limit_mb: Some(64),
+            max_output_kb: Some(16),
+            max_processes: None,
+        };
+        let result = grader.evaluate(&algorithm);
+
+        assert!(!result.result);
+        assert_eq!(result.verdict, Some(Verdict::OutputLimitExceeded));
+        let commands = grader.runner.commands.borrow();
+        // 只限制测试二进制，不限制编译
+        assert!(commands[0].limits.is_empty());
+        assert_eq!(commands[1].limits, algorithm.limits);
+        assert_eq!(commands[1].args, vec!["--nocapture"]);
+    }
+
+    #[test]
+    fn test_limited_cargo_tests_are_built_first() {
+        let build = concat!(
+            r#"{"reason":"compiler-artifact","profile":{"test":false},"executable":null}"#,
+            "\n",
+            r#"{"reason":"compiler-artifact","profile":{"test":true},"executable":"/nonexistent/target/debug/deps/solution5-1"}"#,
+            "\n",
+            r#"{"reason":"compiler-artifact","profile":{"test":true},"executable":"/nonexistent/target/debug/deps/tests-2"}"#,
+            "\n",
+            r#"{"reason":"build-finished","success":true}"#,
+            "\n",
+        );
+        let runner = FakeRunner::default()
+            .script_stdout("test", build)
+            .script("solution5-1", &[false]);
+        let grader = grader(runner);
+        let mut solution = exercise("solution5", "normal/solution5", "cargo_project", 6);
+        solution.limits.memory_limit_mb = Some(256);
+        let result = grader.evaluate(&solution);
+
+        // 第一个测试二进制失败后不再运行后面的
+        assert!(!result.result);
+        assert_eq!(
+            grader.runner.command_lines(),
+            vec![
+                "cargo build",
+                "cargo test --no-run --message-format=json",
+                "/nonexistent/target/debug/deps/solution5-1",
+                "cargo clippy",
+            ]
+        );
+        let commands = grader.runner.commands.borrow();
+        assert!(commands[1].limits.is_empty());
+        assert_eq!(commands[2].limits.memory_limit_mb, Some(256));
+        assert_eq!(
+            commands[2].current_dir.as_deref(),
+            Some(Path::new("/nonexistent/exercises/normal/solution5"))
+        );
+    }
+
+    #[test]
+    fn test_test_executables_from_build_messages() {
+        let build = concat!(
+            "Compiling solution5\n",
+            r#"{"reason":"compiler-artifact","profile":{"test":false},"executable":"/t/debug/solution5"}"#,
+            "\n",
+            r#"{"reason":"compiler-artifact","profile":{"test":true},"executable":"/t/debug/deps/solution5-1"}"#,
+            "\n",
+        );
+        assert_eq!(
+            test_executables(build),
+            vec![PathBuf::from("/t/debug/deps/solution5-1")]
+        );
+    }
+
+    #[test]
+    fn test_missing_prlimit_is_reported() {
+        let runner =
+            FakeRunner::default().script_verdict("solution5-1", Verdict::LimitsUnavailable);
+        let runner = runner
+            .script("solution5-1", &[false])
+            .script_stdout(
+                "test",
+                r#"{"reason":"compiler-artifact","profile":{"test":true},"executable":"/t/deps/solution5-1"}"#,
+            );
+        let grader = grader(runner);
+        let mut solution = exercise("solution5", "normal/solution5", "cargo_project", 6);
+        solution.limits.max_processes = Some(64);
+        let result = grader.evaluate(&solution);
+
+        assert!(!result.result);
+        assert_eq!(result.verdict, Some(Verdict::LimitsUnavailable));
+    }
+
+    #[test]
+    fn test_late_submission_is_penalised() {
+        // 2024-12-03T00:00:00Z，比截止时间晚两天
+        let runner = FakeRunner::default().script_stdout("log", "1733184000\n");
+        let grader = grader(runner);
+        let mut solution = exercise("solution1", "normal/solution1", "cargo_project", 6);
+        solution.deadline = Some("2024-12-01T00:00:00Z".to_string());
+        solution.late_policy = Some(LatePolicy::Linear {
+            percent_per_day: 25,
+            min_percent: 0,
+        });
+        let result = grader.evaluate(&solution);
+
+        assert!(result.result);
+        assert_eq!(result.raw_score, Some(6));
+        assert_eq!(result.late_days, Some(2));
+        assert_eq!(result.score, 3);
+        assert_eq!(
+            grader.runner.command_lines().last().unwrap(),
+            "git log -1 --format=%ct -- /nonexistent/exercises/normal/solution1"
+        );
+    }
+
+    #[test]
+    fn test_late_commit_to_extra_file_is_penalised() {
+        let runner = FakeRunner::default().script_stdout("log", "1733184000\n");
+        let grader = grader(runner);
+        let mut algorithm = exercise("algorithm5.rs", "easy/algorithm5.rs", "single_file", 1);
+        algorithm.extra_files = vec!["easy/graph.rs".to_string()];
+        algorithm.deadline = Some("2024-12-01T00:00:00Z".to_string());
+        let result = grader.evaluate(&algorithm);
+
+        assert_eq!(result.late_days, Some(2));
+        let commands = grader.runner.commands.borrow();
+        let log = commands.last().unwrap();
+        assert_eq!(
+            log.args,
+            vec![
+                "log",
+                "-1",
+                "--format=%ct",
+                "--",
+                "/nonexistent/exercises/easy/algorithm5.rs",
+                "/nonexistent/exercises/easy/graph.rs",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_on_time_submission_keeps_score() {
+        let runner = FakeRunner::default().script_stdout("log", "1733011200\n");
+        let grader = grader(runner);
+        let mut solution = exercise("solution1", "normal/solution1", "cargo_project", 6);
+        solution.deadline = Some("2024-12-01T08:00:00+08:00".to_string());
+        let result = grader.evaluate(&solution);
+
+        assert_eq!(result.score, 6);
+        assert_eq!(result.raw_score, Some(6));
+        assert_eq!(result.late_days, Some(0));
+    }
+
+    #[test]
+    fn test_missing_commit_skips_deadline() {
+        // 未提交过的习题 git log 输出为空
+        let grader = grader(FakeRunner::default());
+        let mut solution = exercise("solution1", "normal/solution1", "cargo_project", 6);
+        solution.deadline = Some("2024-12-01T00:00:00Z".to_string());
+        let result = grader.evaluate(&solution);
+
+        assert_eq!(result.score, 6);
+        assert_eq!(result.raw_score, None);
+        assert_eq!(result.late_days, None);
+    }
+
+    #[test]
+    fn test_empty_registry_has_no_evaluators() {
+        let registry = EvaluatorRegistry::new();
+        assert!(registry.get("single_file").is_none());
+
+        let registry = EvaluatorRegistry::default();
+        assert!(registry.get("single_file").is_some());
+        assert!(registry.get("cargo_project").is_some());
+    }
+}
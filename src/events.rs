@@ -0,0 +1,211 @@
+use crate::report::Statistics;
+use serde::Serialize;
+#[cfg(test)]
+use std::cell::RefCell;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+// 是否以 NDJSON 事件流输出（--events ndjson），启用时 stdout 只输出事件
+static NDJSON_EVENTS: AtomicBool = AtomicBool::new(false);
+
+pub fn enable_ndjson() {
+    NDJSON_EVENTS.store(true, Ordering::Relaxed);
+}
+
+#[cfg(test)]
+thread_local! {
+    // 测试中截获本线程写往 stdout 的内容，截获期间本线程处于事件模式，不影响并行运行的其他测试
+    static CAPTURED_STDOUT: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+pub fn ndjson_enabled() -> bool {
+    #[cfg(test)]
+    if CAPTURED_STDOUT.with(|captured| captured.borrow().is_some()) {
+        return true;
+    }
+    NDJSON_EVENTS.load(Ordering::Relaxed)
+}
+
+// 向 stdout 写出一行，供 say! 与 emit 使用
+#[doc(hidden)]
+pub fn print_line(line: fmt::Arguments) {
+    #[cfg(test)]
+    {
+        let captured = CAPTURED_STDOUT.with(|captured| match captured.borrow_mut().as_mut() {
+            Some(buffer) => {
+                buffer.push_str(&line.to_string());
+                buffer.push('\n');
+                true
+            }
+            None => false,
+        });
+        if captured {
+            return;
+        }
+    }
+    println!("{}", line);
+}
+
+// 在事件模式下运行 f，返回其间写往 stdout 的全部内容
+#[cfg(test)]
+pub fn capture_ndjson_stdout(f: impl FnOnce()) -> String {
+    CAPTURED_STDOUT.with(|captured| *captured.borrow_mut() = Some(String::new()));
+    f();
+    CAPTURED_STDOUT.with(|captured| captured.borrow_mut().take().unwrap_or_default())
+}
+
+// 输出人类可读的评测信息；事件模式下改写到 stderr，避免混入事件流
+#[macro_export]
+macro_rules! say {
+    ($($arg:tt)*) => {
+        if $crate::events::ndjson_enabled() {
+            eprintln!($($arg)*);
+        } else {
+            $crate::events::print_line(format_args!($($arg)*));
+        }
+    };
+}
+
+// 供编辑器插件消费的评测事件，每个事件序列化为一行 JSON
+#[derive(Serialize, Debug)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Event<'a> {
+    ExerciseStarted {
+        exercise: &'a str,
+        #[serde(rename = "type")]
+        exercise_type: &'a str,
+        path: &'a str,
+    },
+    StageStarted {
+        exercise: &'a str,
+        stage: &'a str,
+    },
+    StageFinished {
+        exercise: &'a str,
+        stage: &'a str,
+        success: bool,
+        duration_ms: u64,
+        stdout: &'a str,
+        stderr: &'a str,
+    },
+    ExerciseFinished {
+        exercise: &'a str,
+        result: bool,
+        score: i32,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        visible_result: Option<bool>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        hidden_result: Option<bool>,
+        time: u64,
+        time_ms: u64,
+    },
+    RunFinished {
+        statistics: &'a Statistics,
+    },
+}
+
+// 事件模式下向 stdout 写出一行 JSON 事件
+pub fn emit(event: &Event) {
+    if !ndjson_enabled() {
+        return;
+    }
+    match serde_json::to_string(event) {
+        Ok(line) => print_line(format_args!("{}", line)),
+        Err(e) => eprintln!("Failed to serialize event: {}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(event: &Event) -> String {
+        serde_json::to_string(event).unwrap()
+    }
+
+    #[test]
+    fn test_event_lines() {
+        assert_eq!(
+            line(&Event::ExerciseStarted {
+                exercise: "algorithm1.rs",
+                exercise_type: "single_file",
+                path: "easy/algorithm1.rs",
+            }),
+            r#"{"event":"exercise_started","exercise":"algorithm1.rs","type":"single_file","path":"easy/algorithm1.rs"}"#
+        );
+        assert_eq!(
+            line(&Event::StageStarted {
+                exercise: "algorithm1.rs",
+                stage: "compile",
+            }),
+            r#"{"event":"stage_started","exercise":"algorithm1.rs","stage":"compile"}"#
+        );
+        assert_eq!(
+            line(&Event::StageFinished {
+                exercise: "algorithm1.rs",
+                stage: "test",
+                success: false,
+                duration_ms: 12,
+                stdout: "running 1 test\n",
+                stderr: "",
+            }),
+            r#"{"event":"stage_finished","exercise":"algorithm1.rs","stage":"test","success":false,"duration_ms":12,"stdout":"running 1 test\n","stderr":""}"#
+        );
+        assert_eq!(
+            line(&Event::RunFinished {
+                statistics: &Statistics {
+                    total_exercises: 2,
+                    total_successes: 1,
+                    total_failures: 1,
+                    total_score: 1,
+                    total_time: 3,
+                    total_time_ms: 3100,
+                },
+            }),
+            r#"{"event":"run_finished","statistics":{"total_exercises":2,"total_successes":1,"total_failures":1,"total_score":1,"total_time":3,"total_time_ms":3100}}"#
+        );
+    }
+
+    #[test]
+    fn test_exercise_finished_skips_missing_results() {
+        assert_eq!(
+            line(&Event::ExerciseFinished {
+                exercise: "solution1",
+                result: true,
+                score: 6,
+                visible_result: None,
+                hidden_result: None,
+                time: 1,
+                time_ms: 1500,
+            }),
+            r#"{"event":"exercise_finished","exercise":"solution1","result":true,"score":6,"time":1,"time_ms":1500}"#
+        );
+        assert_eq!(
+            line(&Event::ExerciseFinished {
+                exercise: "solution1",
+                result: false,
+                score: 0,
+                visible_result: Some(true),
+                hidden_result: Some(false),
+                time: 1,
+                time_ms: 1500,
+            }),
+            r#"{"event":"exercise_finished","exercise":"solution1","result":false,"score":0,"visible_result":true,"hidden_result":false,"time":1,"time_ms":1500}"#
+        );
+    }
+
+    #[test]
+    fn test_say_goes_to_stderr_in_ndjson_mode() {
+        let stdout = capture_ndjson_stdout(|| {
+            crate::say!("human readable");
+            emit(&Event::StageStarted {
+                exercise: "algorithm1.rs",
+                stage: "compile",
+            });
+        });
+        assert_eq!(
+            stdout,
+            "{\"event\":\"stage_started\",\"exercise\":\"algorithm1.rs\",\"stage\":\"compile\"}\n"
+        );
+    }
+}
@@ -0,0 +1,18 @@
+// 评测器的库部分：配置与报告的数据结构、按习题类型注册的评测器以及各子命令的实现
+
+pub mod config;
+pub mod deadline;
+pub mod diff;
+pub mod differential;
+pub mod environment;
+pub mod evaluator;
+pub mod events;
+pub mod list;
+pub mod report;
+pub mod reset;
+pub mod runner;
+pub mod sign;
+pub mod similarity;
+pub mod timestamp;
+pub mod tui;
+pub mod workspace;
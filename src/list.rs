@@ -0,0 +1,250 @@
+use crate::config::ExerciseConfig;
+use crate::report::Report;
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+
+// 学生尚未完成习题时保留在源码中的标记
+pub const NOT_DONE_MARKER: &str = "I AM NOT DONE";
+
+// 一道习题的配置与上次评测结果
+#[derive(Serialize, Debug, PartialEq)]
+pub struct ExerciseStatus {
+    pub level: String,
+    pub name: String,
+    pub path: String,
+    #[serde(rename = "type")]
+    pub exercise_type: String,
+    pub max_score: i32,
+    pub last_result: Option<bool>,
+    pub last_score: Option<i32>,
+    pub not_done: bool,
+}
+
+// list 命令的过滤条件
+#[derive(Debug, Default)]
+pub struct ListFilter {
+    pub level: Option<String>,
+    // 只显示上次评测未通过（或尚未评测）的习题
+    pub failed_only: bool,
+}
+
+impl ListFilter {
+    pub fn matches(&self, status: &ExerciseStatus) -> bool {
+        self.level
+            .as_ref()
+            .is_none_or(|level| *level == status.level)
+            && (!self.failed_only || status.last_result != Some(true))
+    }
+}
+
+// 汇总习题配置与报告中的上次结果
+pub fn collect_statuses(
+    config: &ExerciseConfig,
+    report: Option<&Report>,
+    exercises_dir: &Path,
+) -> Vec<ExerciseStatus> {
+    config
+        .exercises_by_level()
+        .map(|(level, exercise)| {
+            let last = report.and_then(|report| {
+                report
+                    .exercises
+                    .iter()
+                    .find(|result| result.name == exercise.name)
+            });
+            ExerciseStatus {
+                level: level.to_string(),
+                name: exercise.name.clone(),
+                path: exercise.path.clone(),
+                exercise_type: exercise.exercise_type.clone(),
+                max_score: exercise.score,
+                last_result: last.map(|result| result.result),
+                last_score: last.map(|result| result.score),
+                not_done: exercise
+                    .source_paths()
+                    .any(|path| has_not_done_marker(&exercises_dir.join(path))),
+            }
+        })
+        .collect()
+}
+
+// 检查习题文件（或 Cargo 项目下的 .rs 文件）是否仍含有未完成标记
+pub fn has_not_done_marker(path: &Path) -> bool {
+    if path.is_dir() {
+        let Ok(entries) = fs::read_dir(path) else {
+            return false;
+        };
+        entries
+            .flatten()
+            .any(|entry| entry.file_name() != "target" && has_not_done_marker(&entry.path()))
+    } else {
+        path.extension().is_some_and(|ext| ext == "rs")
+            && fs::read_to_string(path).is_ok_and(|source| source.contains(NOT_DONE_MARKER))
+    }
+}
+
+// 以表格形式输出习题列表
+pub fn print_table(statuses: &[ExerciseStatus]) {
+    let headers = [
+        "Level",
+        "Name",
+        "Path",
+        "Type",
+        "Max score",
+        "Last result",
+        "Last score",
+        "Not done",
+    ];
+    let rows: Vec<[String; 8]> = statuses
+        .iter()
+        .map(|status| {
+            [
+                status.level.clone(),
+                status.name.clone(),
+                status.path.clone(),
+                status.exercise_type.clone(),
+                status.max_score.to_string(),
+                match status.last_result {
+                    Some(true) => "PASSED".to_string(),
+                    Some(false) => "FAILED".to_string(),
+                    None => "-".to_string(),
+                },
+                status
+                    .last_score
+                    .map_or("-".to_string(), |score| score.to_string()),
+                if status.not_done { "yes" } else { "no" }.to_string(),
+            ]
+        })
+        .collect();
+
+    let mut widths = headers.map(str::len);
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.len());
+        }
+    }
+
+    let format_row = |cells: &[&str]| {
+        cells
+            .iter()
+            .zip(widths)
+            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+            .collect::<Vec<_>>()
+            .join("  ")
+            .trim_end()
+            .to_string()
+    };
+    println!("{}", format_row(&headers));
+    for row in &rows {
+        let cells: Vec<&str> = row.iter().map(String::as_str).collect();
+        println!("{}", format_row(&cells));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::ExerciseResult;
+
+    fn config() -> ExerciseConfig {
+        serde_json::from_str(
+            r#"{
+                "easy": [
+                    { "name": "algorithm1.rs", "path": "easy/algorithm1.rs", "type": "single_file", "score": 1 },
+                    { "name": "algorithm2.rs", "path": "easy/algorithm2.rs", "type": "single_file", "score": 1 }
+                ],
+                "normal": [],
+                "hard": [{ "name": "solutiont1", "path": "hard/solutiont1", "type": "cargo_project", "score": 10 }]
+            }"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_collect_statuses_with_report() {
+        let dir = std::env::temp_dir().join(format!("grader_list_{}", std::process::id()));
+        fs::create_dir_all(dir.join("easy")).unwrap();
+        fs::create_dir_all(dir.join("hard/solutiont1/src")).unwrap();
+        fs::write(dir.join("easy/algorithm1.rs"), "fn main() {}\n").unwrap();
+        fs::write(dir.join("easy/algorithm2.rs"), "// I AM NOT DONE\n").unwrap();
+        fs::write(
+            dir.join("hard/solutiont1/src/main.rs"),
+            "// I AM NOT DONE\n",
+        )
+        .unwrap();
+
+        let mut report = Report::new();
+        report.push(ExerciseResult {
+            name: "algorithm1.rs".to_string(),
+            result: true,
+            score: 1,
+            visible_result: None,
+            hidden_result: None,
+            time: 0,
+            time_ms: 0,
+            attempts: Vec::new(),
+            raw_score: None,
+            late_days: None,
+            verdict: None,
+            differential: None,
+        });
+        let statuses = collect_statuses(&config(), Some(&report), &dir);
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(statuses.len(), 3);
+        assert_eq!(statuses[0].level, "easy");
+        assert_eq!(statuses[0].last_result, Some(true));
+        assert_eq!(statuses[0].last_score, Some(1));
+        assert!(!statuses[0].not_done);
+        assert_eq!(statuses[1].last_result, None);
+        assert!(statuses[1].not_done);
+        assert_eq!(statuses[2].level, "hard");
+        assert!(statuses[2].not_done);
+    }
+
+    #[test]
+    fn test_not_done_marker_in_extra_files() {
+        let dir = std::env::temp_dir().join(format!("grader_list_extra_{}", std::process::id()));
+        fs::create_dir_all(dir.join("easy")).unwrap();
+        fs::write(dir.join("easy/algorithm5.rs"), "mod graph;\n").unwrap();
+        fs::write(dir.join("easy/graph.rs"), "// I AM NOT DONE\n").unwrap();
+        let config: ExerciseConfig = serde_json::from_str(
+            r#"{
+                "easy": [{ "name": "algorithm5.rs", "path": "easy/algorithm5.rs", "type": "single_file", "score": 1, "extra_files": ["easy/graph.rs"] }],
+                "normal": [],
+                "hard": []
+            }"#,
+        )
+        .unwrap();
+        let statuses = collect_statuses(&config, None, &dir);
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(statuses[0].not_done);
+    }
+
+    #[test]
+    fn test_filters() {
+        let statuses = collect_statuses(&config(), None, Path::new("/nonexistent"));
+
+        let hard = ListFilter {
+            level: Some("hard".to_string()),
+            failed_only: false,
+        };
+        assert_eq!(statuses.iter().filter(|s| hard.matches(s)).count(), 1);
+
+        let failed = ListFilter {
+            level: None,
+            failed_only: true,
+        };
+        let mut passed = statuses;
+        passed[0].last_result = Some(true);
+        passed[1].last_result = Some(false);
+        let names: Vec<&str> = passed
+            .iter()
+            .filter(|s| failed.matches(s))
+            .map(|s| s.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["algorithm2.rs", "solutiont1"]);
+    }
+}
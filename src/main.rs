@@ -1,58 +1,88 @@
-use serde::{Deserialize, Serialize};
-use std::fs::{self, File};
-use std::io::{self};
-use std::path::{Path, PathBuf};
-use std::process::{exit, Command};
-use std::time::Instant;
-
-#[derive(Serialize, Deserialize, Debug, Clone)]
-struct Exercise {
-    name: String,
-    path: String,
-    #[serde(rename = "type")]
-    exercise_type: String,
-    score: i32,
-}
-
-#[derive(Serialize, Deserialize, Debug)]
-struct ExerciseConfig {
-    easy: Vec<Exercise>,
-    normal: Vec<Exercise>,
-    hard: Vec<Exercise>,
-}
-
-#[derive(Serialize, Deserialize, Debug)]
-struct ExerciseResult {
-    name: String,
-    result: bool,
-    score: i32,
-}
-
-#[derive(Serialize, Deserialize, Debug)]
-struct Statistics {
-    total_exercises: usize,
-    total_successes: usize,
-    total_failures: usize,
-    total_score: i32,
-    total_time: u64,
-}
-
-#[derive(Serialize, Deserialize, Debug)]
-struct Report {
-    exercises: Vec<ExerciseResult>,
-    statistics: Statistics,
-}
+use cargotest::config::load_exercise_config;
+use cargotest::diff::diff_reports;
+use cargotest::environment::collect_environment;
+use cargotest::evaluator::{CargoProjectEvaluator, Grader};
+use cargotest::events::{self, emit, Event};
+use cargotest::list::{collect_statuses, print_table, ListFilter};
+use cargotest::report::{load_report, save_report_to_json, Report};
+use cargotest::reset::reset_exercise;
+use cargotest::runner::{RecordingRunner, SystemRunner};
+use cargotest::say;
+use cargotest::sign::{
+    changed_sources, hash_sources, sign_report, verify_report, SigningKey, VerifyingKey,
+};
+use cargotest::similarity::{detect_similarity, print_report};
+use cargotest::timestamp::format_rfc3339;
+use cargotest::tui;
+use cargotest::workspace::SharedWorkspace;
+use std::io;
+use std::path::Path;
+use std::process::exit;
+use std::time::{Instant, SystemTime};
 
 fn main() {
-    let args: Vec<String> = std::env::args().collect();
+    let mut args: Vec<String> = std::env::args().collect();
+    if let Some(pos) = args.iter().position(|arg| arg == "--events") {
+        if args.get(pos + 1).map(String::as_str) != Some("ndjson") {
+            eprintln!("Unsupported event format, expected '--events ndjson'");
+            exit(1);
+        }
+        events::enable_ndjson();
+        args.drain(pos..pos + 2);
+    }
+    // 所有 cargo_project 习题通过一个生成的工作区构建，共享 target 目录
+    let shared_workspace = match args.iter().position(|arg| arg == "--shared-workspace") {
+        Some(pos) => {
+            args.remove(pos);
+            true
+        }
+        None => false,
+    };
     if args.len() < 2 {
-        eprintln!("Please provide a command: 'watch' or 'all'");
+        eprintln!(
+            "Please provide a command: 'watch', 'all', 'tui', 'list', 'reset <exercise>', 'diff <old.json> <new.json>', 'verify-report <report.json>' or 'similarity <dir-of-checkouts>'"
+        );
         exit(1);
     }
 
     let mode = &args[1];
     let start_time = Instant::now();
 
+    if mode == "diff" {
+        let (Some(old_path), Some(new_path)) = (args.get(2), args.get(3)) else {
+            eprintln!("Usage: diff <old.json> <new.json>");
+            exit(1);
+        };
+        let (old_report, new_report) = match (load_report(old_path), load_report(new_path)) {
+            (Ok(old_report), Ok(new_report)) => (old_report, new_report),
+            (Err(e), _) | (_, Err(e)) => {
+                eprintln!("Failed to load report: {}", e);
+                exit(1);
+            }
+        };
+        let diff = diff_reports(&old_report, &new_report);
+        diff.print();
+        // 存在退步的习题时以非零状态退出，便于在 CI 中拦截
+        if diff.has_regressions() {
+            exit(1);
+        }
+        return;
+    }
+
+    if mode == "verify-report" {
+        let Some(report_path) = args.get(2) else {
+            eprintln!("Usage: verify-report <report.json> [--sources]");
+            exit(1);
+        };
+        let check_sources = args[3..].iter().any(|arg| arg == "--sources");
+        if let Err(e) = verify_report_file(report_path, check_sources) {
+            eprintln!("\x1b[31m{}: VERIFICATION FAILED: {}\x1b[0m", report_path, e);
+            exit(1);
+        }
+        println!("\x1b[32m{}: SIGNATURE VALID\x1b[0m", report_path);
+        return;
+    }
+
     let config = match load_exercise_config("exercise_config.json") {
         Ok(cfg) => cfg,
         Err(e) => {
@@ -61,200 +91,236 @@ fn main() {
         }
     };
 
-    let mut report = Report {
-        exercises: Vec::new(),
-        statistics: Statistics {
-            total_exercises: 0,
-            total_successes: 0,
-            total_failures: 0,
-            total_score: 0,
-            total_time: 0,
-        },
-    };
-
-    evaluate_exercises_from_config(mode, config, &mut report);
-
-    report.statistics.total_time = start_time.elapsed().as_secs();
-    report.statistics.total_exercises =
-        report.statistics.total_successes + report.statistics.total_failures;
-
-    println!("\nSummary:");
-    println!("Total exercises: {}", report.statistics.total_exercises);
-    println!("Total successes: {}", report.statistics.total_successes);
-    println!("Total failures: {}", report.statistics.total_failures);
-    println!("Total score: {}", report.statistics.total_score);
+    if mode == "list" {
+        let mut filter = ListFilter::default();
+        let mut as_json = false;
+        let mut options = args[2..].iter();
+        while let Some(option) = options.next() {
+            match option.as_str() {
+                "--level" => filter.level = options.next().cloned(),
+                "--failed" => filter.failed_only = true,
+                "--json" => as_json = true,
+                _ => {
+                    eprintln!("Usage: list [--level <easy|normal|hard>] [--failed] [--json]");
+                    exit(1);
+                }
+            }
+        }
 
-    if let Err(e) = save_report_to_json("report.json", &report) {
-        eprintln!("Error saving report: {}", e);
+        // 没有 report.json 时只显示配置信息
+        let report = load_report("report.json").ok();
+        let statuses: Vec<_> = collect_statuses(&config, report.as_ref(), Path::new("./exercises"))
+            .into_iter()
+            .filter(|status| filter.matches(status))
+            .collect();
+        if as_json {
+            match serde_json::to_string_pretty(&statuses) {
+                Ok(json) => println!("{}", json),
+                Err(e) => {
+                    eprintln!("Failed to serialize exercise list: {}", e);
+                    exit(1);
+                }
+            }
+        } else {
+            print_table(&statuses);
+        }
+        return;
     }
-}
-
-fn load_exercise_config(file_path: &str) -> Result<ExerciseConfig, io::Error> {
-    let file = File::open(file_path)?;
-    let config: ExerciseConfig = serde_json::from_reader(file)?;
-    Ok(config)
-}
-
-fn evaluate_exercises_from_config(mode: &str, config: ExerciseConfig, report: &mut Report) {
-    let all_exercises = [config.easy, config.normal, config.hard].concat();
-
-    for exercise in all_exercises {
-        println!("\nEvaluating {}: {}", exercise.exercise_type, exercise.name);
-        let result = evaluate_exercise(&exercise);
-
-        let score = if result { exercise.score } else { 0 };
 
-        report.exercises.push(ExerciseResult {
-            name: exercise.name.clone(),
-            result,
-            score,
-        });
+    if mode == "similarity" {
+        const USAGE: &str =
+            "Usage: similarity <dir-of-checkouts> [--base <checkout>] [--min <0.0-1.0>] [--json]";
+        let Some(checkouts_dir) = args.get(2) else {
+            eprintln!("{}", USAGE);
+            exit(1);
+        };
+        let mut base = None;
+        let mut min_similarity = 0.0;
+        let mut as_json = false;
+        let mut options = args[3..].iter();
+        while let Some(option) = options.next() {
+            match option.as_str() {
+                "--base" => base = options.next().map(Path::new),
+                "--min" => match options.next().and_then(|value| value.parse().ok()) {
+                    Some(value) => min_similarity = value,
+                    None => {
+                        eprintln!("{}", USAGE);
+                        exit(1);
+                    }
+                },
+                "--json" => as_json = true,
+                _ => {
+                    eprintln!("{}", USAGE);
+                    exit(1);
+                }
+            }
+        }
 
-        if result {
-            report.statistics.total_successes += 1;
+        let report =
+            match detect_similarity(&config, Path::new(checkouts_dir), base, min_similarity) {
+                Ok(report) => report,
+                Err(e) => {
+                    eprintln!("Failed to compare submissions in {}: {}", checkouts_dir, e);
+                    exit(1);
+                }
+            };
+        if as_json {
+            match serde_json::to_string_pretty(&report) {
+                Ok(json) => println!("{}", json),
+                Err(e) => {
+                    eprintln!("Failed to serialize similarity report: {}", e);
+                    exit(1);
+                }
+            }
         } else {
-            report.statistics.total_failures += 1;
+            print_report(&report);
         }
+        return;
+    }
 
-        report.statistics.total_score += score;
-
-        if mode == "watch" && !ask_to_continue() {
-            break;
+    if mode == "tui" {
+        let grader = Grader::new(RecordingRunner::new(SystemRunner));
+        // 在上次的报告基础上更新重新评测的习题
+        let mut report = load_report("report.json").unwrap_or_default();
+        if let Err(e) = tui::run(&config, &grader, &mut report, Path::new("./exercises")) {
+            eprintln!("Terminal UI failed: {}", e);
+            exit(1);
+        }
+        if !report.exercises.is_empty() {
+            if let Err(e) = save_report_to_json("report.json", &report) {
+                eprintln!("Error saving report: {}", e);
+            }
         }
+        return;
     }
-}
 
-fn evaluate_exercise(exercise: &Exercise) -> bool {
-    let exercise_path = PathBuf::from(&format!("./exercises/{}", exercise.path));
-    match exercise.exercise_type.as_str() {
-        "single_file" => evaluate_single_file(&exercise_path),
-        "cargo_project" => evaluate_cargo_project(&exercise_path),
-        _ => {
-            eprintln!("Unknown exercise type: {}", exercise.exercise_type);
-            false
+    if mode == "reset" {
+        let Some(name) = args.get(2) else {
+            eprintln!("Usage: reset <exercise> [--yes]");
+            exit(1);
+        };
+        let assume_yes = args[3..].iter().any(|arg| arg == "--yes" || arg == "-y");
+        if let Err(e) = reset_exercise(&config, name, assume_yes) {
+            eprintln!("Failed to reset {}: {}", name, e);
+            exit(1);
         }
+        return;
     }
-}
-
-// 评测单文件 Rust 习题（使用 rustc --test 并执行测试）
-fn evaluate_single_file(file_path: &PathBuf) -> bool {
-    // 获取文件名（不带扩展名）
-    let test_binary = file_path.with_extension("");
-
-    // 编译测试文件
-    let compile_output = Command::new("rustc")
-        .arg("--test") // 使用 rustc --test 进行编译
-        .arg(file_path)
-        .arg("-o")
-        .arg(&test_binary) // 指定输出文件
-        .output();
-
-    if let Ok(output) = compile_output {
-        if output.status.success() {
-            // 编译成功，运行测试二进制文件
-            let test_output = Command::new(&test_binary).output();
-
-            let test_passed = match test_output {
-                Ok(test_run) => {
-                    if test_run.status.success() {
-                        println!("\x1b[32m{}: TEST PASSED\x1b[0m", file_path.display());
-                        true
-                    } else {
-                        println!("\x1b[31m{}: TEST FAILED\x1b[0m", file_path.display());
-                        false
-                    }
-                }
-                Err(_) => {
-                    eprintln!("Error running test executable for {}", file_path.display());
-                    false
-                }
-            };
 
-            // 删除测试二进制文件
-            if let Err(e) = fs::remove_file(&test_binary) {
-                eprintln!(
-                    "Failed to remove test binary {}: {}",
-                    test_binary.display(),
-                    e
-                );
-            } else {
-                println!(
-                    "Successfully removed test binary: {}",
-                    test_binary.display()
+    let mut grader = Grader::new(SystemRunner);
+    // 工作区在评测结束、函数返回时删除
+    let _workspace = if shared_workspace {
+        match SharedWorkspace::create(
+            &config,
+            Path::new("./exercises"),
+            Path::new("./target/grader-workspace"),
+        ) {
+            Ok(workspace) => {
+                grader.registry_mut().register(
+                    "cargo_project",
+                    CargoProjectEvaluator::in_workspace(&workspace),
                 );
+                Some(workspace)
+            }
+            Err(e) => {
+                eprintln!("Failed to create shared workspace: {}", e);
+                exit(1);
             }
-
-            test_passed
-        } else {
-            // 编译失败
-            eprintln!("\x1b[31m{}: COMPILATION FAILED\x1b[0m", file_path.display());
-            false
         }
     } else {
-        eprintln!("Error executing rustc --test for {}", file_path.display());
-        false
-    }
-}
-
-// 评测 Cargo 项目
-fn evaluate_cargo_project(proj_path: &PathBuf) -> bool {
-    let build_success = run_cargo_command(proj_path, "build");
-    let test_success = run_cargo_command(proj_path, "test");
-    let clippy_success = run_cargo_command(proj_path, "clippy");
+        None
+    };
+    let mut report = Report::new();
+    report.environment = Some(collect_environment(&SystemRunner));
 
-    let passed = build_success && test_success && clippy_success;
+    for exercise in config.exercises() {
+        say!("\nEvaluating {}: {}", exercise.exercise_type, exercise.name);
+        report.push(grader.evaluate(exercise));
 
-    if passed {
-        println!("\x1b[32m{}: PASSED\x1b[0m", proj_path.display());
-    } else {
-        println!("\x1b[31m{}: FAILED\x1b[0m", proj_path.display());
+        if mode == "watch" && !ask_to_continue() {
+            break;
+        }
     }
 
-    clean_target_directory(proj_path);
-
-    passed
-}
+    let total_time = start_time.elapsed();
+    report.statistics.total_time = total_time.as_secs();
+    report.statistics.total_time_ms = total_time.as_millis() as u64;
+    if let Some(environment) = &mut report.environment {
+        environment.finished_at = Some(format_rfc3339(SystemTime::now()));
+    }
 
-// 运行 Cargo 命令
-fn run_cargo_command(proj_path: &PathBuf, command: &str) -> bool {
-    let output = Command::new("cargo")
-        .arg(command)
-        .current_dir(proj_path)
-        .output();
+    say!("\nSummary:");
+    say!("Total exercises: {}", report.statistics.total_exercises);
+    say!("Total successes: {}", report.statistics.total_successes);
+    say!("Total failures: {}", report.statistics.total_failures);
+    say!("Total score: {}", report.statistics.total_score);
+
+    let hidden_results: Vec<bool> = report
+        .exercises
+        .iter()
+        .filter_map(|exercise| exercise.hidden_result)
+        .collect();
+    if !hidden_results.is_empty() {
+        let hidden_passed = hidden_results.iter().filter(|&&passed| passed).count();
+        say!(
+            "Hidden tests passed: {}/{}",
+            hidden_passed,
+            hidden_results.len()
+        );
+    }
+    emit(&Event::RunFinished {
+        statistics: &report.statistics,
+    });
+
+    // 配置了签名密钥时为报告签名
+    match SigningKey::from_env() {
+        Ok(Some(key)) => {
+            let signed = hash_sources(&config, Path::new("./exercises"))
+                .and_then(|sources| sign_report(&mut report, sources, &key));
+            if let Err(e) = signed {
+                eprintln!("Error signing report: {}", e);
+            }
+        }
+        Ok(None) => {}
+        Err(e) => eprintln!("Error loading signing key: {}", e),
+    }
 
-    match output {
-        Ok(out) => out.status.success(),
-        Err(_) => false,
+    if let Err(e) = save_report_to_json("report.json", &report) {
+        eprintln!("Error saving report: {}", e);
     }
 }
 
-// 清理 target 目录
-fn clean_target_directory(proj_path: &Path) {
-    let target_dir = proj_path.join("target");
-
-    if target_dir.exists() {
-        if let Err(e) = fs::remove_dir_all(&target_dir) {
-            eprintln!("Failed to clean up target directory: {}", e);
-        } else {
-            println!(
-                "Successfully cleaned up target directory in: {}",
-                proj_path.display()
-            );
+// 校验报告签名，check_sources 时还会与当前检出的源码比对
+fn verify_report_file(report_path: &str, check_sources: bool) -> io::Result<()> {
+    let report = load_report(report_path)?;
+    let key = VerifyingKey::from_env()?.ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            "no verification key, set GRADER_HMAC_KEY or GRADER_ED25519_PUBLIC_KEY",
+        )
+    })?;
+    verify_report(&report, &key)?;
+
+    if check_sources {
+        let config = load_exercise_config("exercise_config.json")?;
+        let current = hash_sources(&config, Path::new("./exercises"))?;
+        if let Some(signature) = &report.signature {
+            let changed = changed_sources(signature, &current);
+            if !changed.is_empty() {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("sources changed since signing: {}", changed.join(", ")),
+                ));
+            }
         }
     }
+    Ok(())
 }
 
 // 用户确认是否继续
 fn ask_to_continue() -> bool {
     let mut input = String::new();
-    println!("\nPress any key to continue, or 'q' to quit.");
+    say!("\nPress any key to continue, or 'q' to quit.");
     io::stdin().read_line(&mut input).unwrap();
     input.trim().to_lowercase() != "q"
 }
-
-// 保存评测报告
-fn save_report_to_json(file_name: &str, report: &Report) -> io::Result<()> {
-    let file = File::create(file_name)?;
-    serde_json::to_writer_pretty(file, report)?;
-    Ok(())
-}
@@ -0,0 +1,242 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ExerciseResult {
+    pub name: String,
+    pub result: bool,
+    pub score: i32,
+    // 配置了隐藏测试或对拍时单独记录可见测试的结果，result 为三者同时通过
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub visible_result: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hidden_result: Option<bool>,
+    // 评测耗时（秒），旧报告中没有该字段时视为 0
+    #[serde(default)]
+    pub time: u64,
+    // 评测耗时（毫秒）
+    #[serde(default)]
+    pub time_ms: u64,
+    // 配置了重试时记录测试阶段的每一次尝试
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attempts: Vec<Attempt>,
+    // 设置了截止时间时记录扣分前的得分与迟交天数
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub raw_score: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub late_days: Option<u32>,
+    // 因超出资源限制而失败时的判定
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verdict: Option<Verdict>,
+    // 配置了对拍时的结果
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub differential: Option<DifferentialResult>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct DifferentialResult {
+    pub passed: bool,
+    pub cases: u64,
+    // 第一个不一致的用例（已缩小）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mismatch: Option<Mismatch>,
+}
+
+// 输入与输出均为 Debug 格式的文本
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct Mismatch {
+    pub case: u64,
+    pub input: String,
+    pub expected: String,
+    pub actual: String,
+}
+
+// 超出资源限制的判定
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    // Memory Limit Exceeded
+    #[serde(rename = "MLE")]
+    MemoryLimitExceeded,
+    // Output Limit Exceeded
+    #[serde(rename = "OLE")]
+    OutputLimitExceeded,
+    // 配置了内存或进程数限制，但评测机上没有 prlimit，测试没有运行
+    #[serde(rename = "LIMITS_UNAVAILABLE")]
+    LimitsUnavailable,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Attempt {
+    pub stage: String,
+    pub passed: bool,
+    pub time_ms: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct Statistics {
+    pub total_exercises: usize,
+    pub total_successes: usize,
+    pub total_failures: usize,
+    pub total_score: i32,
+    pub total_time: u64,
+    #[serde(default)]
+    pub total_time_ms: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct Report {
+    pub exercises: Vec<ExerciseResult>,
+    pub statistics: Statistics,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub environment: Option<Environment>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<ReportSignature>,
+}
+
+// 生成报告时的工具链与机器信息，无法获取的项为 None
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct Environment {
+    // rustc -V 的输出，如 "rustc 1.82.0 (f6e511eec 2024-10-15)"
+    pub rustc: Option<String>,
+    pub cargo: Option<String>,
+    pub clippy: Option<String>,
+    // 目标三元组，如 x86_64-unknown-linux-gnu
+    pub host: Option<String>,
+    pub cpu_model: Option<String>,
+    pub cpu_cores: Option<usize>,
+    pub grader_version: String,
+    // 仓库当前提交的哈希
+    pub git_commit: Option<String>,
+    // 评测开始与结束的 UTC 时间（RFC 3339）
+    pub started_at: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub finished_at: Option<String>,
+}
+
+// 报告签名，覆盖报告内容（不含签名本身）与各习题源码的哈希
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ReportSignature {
+    // "hmac-sha256" 或 "ed25519"
+    pub algorithm: String,
+    // 习题路径到其源码 SHA-256 的映射
+    pub sources: BTreeMap<String, String>,
+    // 十六进制编码的签名
+    pub value: String,
+}
+
+impl Report {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // 记录一道习题的结果并更新统计信息
+    pub fn push(&mut self, result: ExerciseResult) {
+        if result.result {
+            self.statistics.total_successes += 1;
+        } else {
+            self.statistics.total_failures += 1;
+        }
+        self.statistics.total_score += result.score;
+        self.statistics.total_exercises =
+            self.statistics.total_successes + self.statistics.total_failures;
+        self.exercises.push(result);
+    }
+
+    // 记录重新评测的结果：替换同名习题的旧结果并重新统计，原有签名随之失效
+    pub fn record(&mut self, result: ExerciseResult) {
+        match self
+            .exercises
+            .iter_mut()
+            .find(|existing| existing.name == result.name)
+        {
+            Some(existing) => *existing = result,
+            None => self.exercises.push(result),
+        }
+        let statistics = &mut self.statistics;
+        statistics.total_successes = self.exercises.iter().filter(|e| e.result).count();
+        statistics.total_exercises = self.exercises.len();
+        statistics.total_failures = statistics.total_exercises - statistics.total_successes;
+        statistics.total_score = self.exercises.iter().map(|e| e.score).sum();
+        self.signature = None;
+    }
+}
+
+// 读取评测报告
+pub fn load_report(file_path: &str) -> Result<Report, io::Error> {
+    let file = File::open(file_path)?;
+    let report: Report = serde_json::from_reader(file)?;
+    Ok(report)
+}
+
+// 保存评测报告
+pub fn save_report_to_json(file_name: &str, report: &Report) -> io::Result<()> {
+    let file = File::create(file_name)?;
+    serde_json::to_writer_pretty(file, report)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(name: &str, passed: bool, score: i32) -> ExerciseResult {
+        ExerciseResult {
+            name: name.to_string(),
+            result: passed,
+            score,
+            visible_result: None,
+            hidden_result: None,
+            time: 0,
+            time_ms: 0,
+            attempts: Vec::new(),
+            raw_score: None,
+            late_days: None,
+            verdict: None,
+            differential: None,
+        }
+    }
+
+    #[test]
+    fn test_push_updates_statistics() {
+        let mut report = Report::new();
+        report.push(result("algorithm1.rs", true, 1));
+        report.push(result("solution1", false, 0));
+        report.push(result("solutiont1", true, 10));
+
+        assert_eq!(report.statistics.total_exercises, 3);
+        assert_eq!(report.statistics.total_successes, 2);
+        assert_eq!(report.statistics.total_failures, 1);
+        assert_eq!(report.statistics.total_score, 11);
+    }
+
+    #[test]
+    fn test_record_replaces_previous_result() {
+        let mut report = Report::new();
+        report.push(result("algorithm1.rs", false, 0));
+        report.push(result("solutiont1", true, 10));
+        report.record(result("algorithm1.rs", true, 1));
+        report.record(result("algorithm2.rs", false, 0));
+
+        assert_eq!(report.exercises.len(), 3);
+        assert!(report.exercises[0].result);
+        assert_eq!(report.statistics.total_exercises, 3);
+        assert_eq!(report.statistics.total_successes, 2);
+        assert_eq!(report.statistics.total_failures, 1);
+        assert_eq!(report.statistics.total_score, 11);
+    }
+
+    #[test]
+    fn test_old_reports_still_parse() {
+        let report: Report = serde_json::from_str(
+            r#"{
+                "exercises": [{ "name": "algorithm1.rs", "result": true, "score": 1 }],
+                "statistics": { "total_exercises": 1, "total_successes": 1, "total_failures": 0, "total_score": 1, "total_time": 3 }
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(report.exercises[0].time, 0);
+        assert!(report.exercises[0].attempts.is_empty());
+    }
+}
@@ -0,0 +1,307 @@
+use crate::config::{ExerciseConfig, TemplateSource};
+use crate::timestamp::format_timestamp;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::SystemTime;
+
+// 将习题恢复为模板中的初始代码，恢复前备份当前版本
+pub fn reset_exercise(config: &ExerciseConfig, name: &str, assume_yes: bool) -> io::Result<()> {
+    reset_exercise_in(Path::new("."), config, name, assume_yes)
+}
+
+// 在 root 下的 exercises/ 中恢复习题，备份写到 root 下的 backups/
+fn reset_exercise_in(
+    root: &Path,
+    config: &ExerciseConfig,
+    name: &str,
+    assume_yes: bool,
+) -> io::Result<()> {
+    let exercise = config
+        .find(name)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no such exercise in config"))?;
+    let template = config.template.as_ref().ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            "no template configured in exercise_config.json",
+        )
+    })?;
+
+    let mut files = load_template_files(template, &exercise.path)?;
+    if files.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("template has no files for {}", exercise.path),
+        ));
+    }
+    // extra_files 可能被多道习题共用，同样恢复，并在下面列出供确认
+    for extra in &exercise.extra_files {
+        files.extend(load_template_files(template, extra)?);
+    }
+
+    let exercises_dir = root.join("exercises");
+    let exercise_path = exercises_dir.join(&exercise.path);
+    println!("The following files will be restored from {:?}:", template);
+    for (path, _) in &files {
+        println!("  exercises/{}", path.display());
+    }
+    if !assume_yes && !ask_to_confirm(&format!("Reset {}?", exercise.name)) {
+        println!("Reset cancelled.");
+        return Ok(());
+    }
+
+    // 备份当前版本到带时间戳的目录，避免误操作丢失代码
+    let existing: Vec<&str> = exercise
+        .source_paths()
+        .filter(|path| exercises_dir.join(path).exists())
+        .collect();
+    if !existing.is_empty() {
+        let backup_dir = create_backup_dir(&root.join("backups"))?;
+        for path in existing {
+            copy_exercise_files(&exercises_dir.join(path), &backup_dir.join(path))?;
+        }
+        println!(
+            "Backed up current version to {}",
+            backup_dir.join(&exercise.path).display()
+        );
+    }
+
+    // Cargo 项目先整体删除，以便去掉模板中不存在的文件
+    if exercise.exercise_type == "cargo_project" && exercise_path.is_dir() {
+        fs::remove_dir_all(&exercise_path)?;
+    }
+    for (path, contents) in files {
+        let target = exercises_dir.join(path);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(target, contents)?;
+    }
+
+    println!("\x1b[32m{}: RESET\x1b[0m", exercise.name);
+    Ok(())
+}
+
+// 读取模板中属于某个习题的全部文件，返回相对 exercises/ 的路径与内容
+pub fn load_template_files(
+    template: &TemplateSource,
+    exercise_path: &str,
+) -> io::Result<Vec<(PathBuf, Vec<u8>)>> {
+    let mut files = Vec::new();
+    match template {
+        TemplateSource::Git(revision) => {
+            let listing = run_git(&[
+                "ls-tree",
+                "-r",
+                "--name-only",
+                revision,
+                "--",
+                &format!("exercises/{}", exercise_path),
+            ])?;
+            for file in String::from_utf8_lossy(&listing).lines() {
+                let contents = run_git(&["show", &format!("{}:{}", revision, file)])?;
+                let relative = Path::new(file)
+                    .strip_prefix("exercises")
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+                files.push((relative.to_path_buf(), contents));
+            }
+        }
+        TemplateSource::Dir(dir) => {
+            let root = Path::new(dir);
+            let mut pending = vec![root.join(exercise_path)];
+            while let Some(path) = pending.pop() {
+                if path.is_dir() {
+                    for entry in fs::read_dir(&path)? {
+                        let entry = entry?;
+                        if entry.file_name() != "target" {
+                            pending.push(entry.path());
+                        }
+                    }
+                } else if path.is_file() {
+                    let relative = path
+                        .strip_prefix(root)
+                        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+                    files.push((relative.to_path_buf(), fs::read(&path)?));
+                }
+            }
+        }
+    }
+    Ok(files)
+}
+
+// 新建 backups/<时间戳> 目录；同一秒内多次重置时依次加上 -2、-3 等后缀
+fn create_backup_dir(backups: &Path) -> io::Result<PathBuf> {
+    fs::create_dir_all(backups)?;
+    let timestamp = format_timestamp(SystemTime::now());
+    let mut suffix = 1;
+    loop {
+        let name = if suffix == 1 {
+            timestamp.clone()
+        } else {
+            format!("{}-{}", timestamp, suffix)
+        };
+        let path = backups.join(name);
+        // create_dir 在目录已存在时失败，并发重置也不会共用同一个备份目录
+        match fs::create_dir(&path) {
+            Ok(()) => return Ok(path),
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => suffix += 1,
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+// 执行 git 命令并返回标准输出
+fn run_git(args: &[&str]) -> io::Result<Vec<u8>> {
+    let output = Command::new("git").args(args).output()?;
+    if !output.status.success() {
+        return Err(io::Error::other(format!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(output.stdout)
+}
+
+// 复制习题文件或目录（跳过 target 目录）
+pub fn copy_exercise_files(from: &Path, to: &Path) -> io::Result<()> {
+    if from.is_dir() {
+        fs::create_dir_all(to)?;
+        for entry in fs::read_dir(from)? {
+            let entry = entry?;
+            if entry.file_name() != "target" {
+                copy_exercise_files(&entry.path(), &to.join(entry.file_name()))?;
+            }
+        }
+    } else {
+        if let Some(parent) = to.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::copy(from, to)?;
+    }
+    Ok(())
+}
+
+// 用户确认操作，默认为否
+fn ask_to_confirm(question: &str) -> bool {
+    let mut input = String::new();
+    println!("{} [y/N]", question);
+    io::stdin().read_line(&mut input).unwrap();
+    matches!(input.trim().to_lowercase().as_str(), "y" | "yes")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_template_files_from_dir() {
+        let dir = std::env::temp_dir().join(format!("grader_template_{}", std::process::id()));
+        fs::create_dir_all(dir.join("normal/solution1/src")).unwrap();
+        fs::create_dir_all(dir.join("normal/solution1/target/debug")).unwrap();
+        fs::write(dir.join("normal/solution1/Cargo.toml"), "[package]\n").unwrap();
+        fs::write(dir.join("normal/solution1/src/main.rs"), "fn main() {}\n").unwrap();
+        fs::write(dir.join("normal/solution1/target/debug/build.log"), "").unwrap();
+        let template = TemplateSource::Dir(dir.to_string_lossy().into_owned());
+
+        let mut files = load_template_files(&template, "normal/solution1").unwrap();
+        files.sort();
+        let missing = load_template_files(&template, "normal/solution2").unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        // target 目录中的构建产物不属于模板
+        assert_eq!(
+            files,
+            vec![
+                (
+                    PathBuf::from("normal/solution1/Cargo.toml"),
+                    b"[package]\n".to_vec()
+                ),
+                (
+                    PathBuf::from("normal/solution1/src/main.rs"),
+                    b"fn main() {}\n".to_vec()
+                ),
+            ]
+        );
+        assert!(missing.is_empty());
+    }
+
+    #[test]
+    fn test_reset_backs_up_and_restores() {
+        let root = std::env::temp_dir().join(format!("grader_reset_{}", std::process::id()));
+        let template_dir = root.join("templates");
+        fs::create_dir_all(template_dir.join("easy")).unwrap();
+        fs::create_dir_all(template_dir.join("normal/solution1/src")).unwrap();
+        fs::write(
+            template_dir.join("easy/algorithm1.rs"),
+            "// I AM NOT DONE\n",
+        )
+        .unwrap();
+        fs::write(template_dir.join("easy/graph.rs"), "// shared\n").unwrap();
+        fs::write(
+            template_dir.join("normal/solution1/src/main.rs"),
+            "fn main() {}\n",
+        )
+        .unwrap();
+        fs::create_dir_all(root.join("exercises/easy")).unwrap();
+        fs::create_dir_all(root.join("exercises/normal/solution1/src")).unwrap();
+        fs::write(root.join("exercises/easy/algorithm1.rs"), "// my answer\n").unwrap();
+        fs::write(root.join("exercises/easy/graph.rs"), "// my graph\n").unwrap();
+        fs::write(
+            root.join("exercises/normal/solution1/src/main.rs"),
+            "// edited\n",
+        )
+        .unwrap();
+        fs::write(root.join("exercises/normal/solution1/src/extra.rs"), "").unwrap();
+
+        let config: ExerciseConfig = serde_json::from_value(serde_json::json!({
+            "easy": [{ "name": "algorithm1.rs", "path": "easy/algorithm1.rs", "type": "single_file", "score": 1, "extra_files": ["easy/graph.rs"] }],
+            "normal": [{ "name": "solution1", "path": "normal/solution1", "type": "cargo_project", "score": 6 }],
+            "hard": [],
+            "template": { "dir": template_dir },
+        }))
+        .unwrap();
+
+        // 同一秒内重置两次，备份不能互相覆盖
+        reset_exercise_in(&root, &config, "algorithm1.rs", true).unwrap();
+        fs::write(root.join("exercises/easy/algorithm1.rs"), "// second try\n").unwrap();
+        reset_exercise_in(&root, &config, "algorithm1.rs", true).unwrap();
+        reset_exercise_in(&root, &config, "solution1", true).unwrap();
+
+        let restored = fs::read_to_string(root.join("exercises/easy/algorithm1.rs")).unwrap();
+        let restored_graph = fs::read_to_string(root.join("exercises/easy/graph.rs")).unwrap();
+        let project_files: Vec<_> = fs::read_dir(root.join("exercises/normal/solution1/src"))
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        let mut backups: Vec<String> = fs::read_dir(root.join("backups"))
+            .unwrap()
+            .map(|entry| {
+                let path = entry.unwrap().path();
+                let single = path.join("easy/algorithm1.rs");
+                if single.exists() {
+                    fs::read_to_string(single).unwrap()
+                        + &fs::read_to_string(path.join("easy/graph.rs")).unwrap()
+                } else {
+                    fs::read_to_string(path.join("normal/solution1/src/main.rs")).unwrap()
+                }
+            })
+            .collect();
+        backups.sort();
+        fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(restored, "// I AM NOT DONE\n");
+        assert_eq!(restored_graph, "// shared\n");
+        // 模板中没有的文件随 Cargo 项目一起删除
+        assert_eq!(project_files, vec!["main.rs"]);
+        assert_eq!(
+            backups,
+            vec![
+                "// edited\n",
+                "// my answer\n// my graph\n",
+                "// second try\n// shared\n"
+            ]
+        );
+    }
+}
@@ -0,0 +1,360 @@
+use crate::config::ResourceLimits;
+use crate::report::Verdict;
+use std::cell::RefCell;
+use std::ffi::{OsStr, OsString};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::thread;
+use std::time::Duration;
+
+// Rust 程序在内存分配失败时输出的信息
+const ALLOCATION_FAILURE: &str = "memory allocation of ";
+
+// 待执行的外部命令，评测逻辑只描述命令，由 CommandRunner 负责实际执行
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommandSpec {
+    pub program: OsString,
+    pub args: Vec<OsString>,
+    pub current_dir: Option<PathBuf>,
+    pub limits: ResourceLimits,
+}
+
+impl CommandSpec {
+    pub fn new(program: impl AsRef<OsStr>) -> Self {
+        Self {
+            program: program.as_ref().to_os_string(),
+            args: Vec::new(),
+            current_dir: None,
+            limits: ResourceLimits::default(),
+        }
+    }
+
+    pub fn arg(mut self, arg: impl AsRef<OsStr>) -> Self {
+        self.args.push(arg.as_ref().to_os_string());
+        self
+    }
+
+    pub fn args<I, S>(mut self, args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        self.args
+            .extend(args.into_iter().map(|arg| arg.as_ref().to_os_string()));
+        self
+    }
+
+    pub fn current_dir(mut self, dir: impl AsRef<Path>) -> Self {
+        self.current_dir = Some(dir.as_ref().to_path_buf());
+        self
+    }
+
+    pub fn limits(mut self, limits: ResourceLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    // 实际执行的程序与参数：设置了内存或进程数限制时通过 prlimit 在 exec 前设置 rlimit
+    // RLIMIT_NPROC 统计的是整个用户的进程数，对 root 无效
+    pub fn program_and_args(&self) -> (OsString, Vec<OsString>) {
+        let limits = &self.limits;
+        if limits.memory_limit_mb.is_none() && limits.max_processes.is_none() {
+            return (self.program.clone(), self.args.clone());
+        }
+        if !cfg!(target_os = "linux") {
+            eprintln!("Resource limits are only enforced on Linux, running without them");
+            return (self.program.clone(), self.args.clone());
+        }
+        let mut args = Vec::new();
+        if let Some(mb) = limits.memory_limit_mb {
+            args.push(OsString::from(format!("--data={}", mb * 1024 * 1024)));
+        }
+        if let Some(processes) = limits.max_processes {
+            args.push(OsString::from(format!("--nproc={}", processes)));
+        }
+        args.push(OsString::from("--"));
+        args.push(self.program.clone());
+        args.extend(self.args.iter().cloned());
+        (OsString::from("prlimit"), args)
+    }
+}
+
+// 命令执行结果
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CommandOutput {
+    pub success: bool,
+    pub stdout: String,
+    pub stderr: String,
+    // 超出资源限制时的判定
+    pub verdict: Option<Verdict>,
+}
+
+// 执行外部命令的抽象，测试中可替换为不真正启动进程的实现
+pub trait CommandRunner {
+    fn run(&self, command: &CommandSpec) -> io::Result<CommandOutput>;
+}
+
+// 通过 std::process::Command 执行命令
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemRunner;
+
+impl CommandRunner for SystemRunner {
+    fn run(&self, command: &CommandSpec) -> io::Result<CommandOutput> {
+        let (program, args) = command.program_and_args();
+        let through_prlimit = program != command.program;
+        let mut process = Command::new(program);
+        process.args(args);
+        if let Some(dir) = &command.current_dir {
+            process.current_dir(dir);
+        }
+        let output = match command.limits.max_output_kb {
+            Some(kb) => run_with_output_limit(process, kb * 1024),
+            None => process.output().map(|output| CommandOutput {
+                success: output.status.success(),
+                stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+                stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+                verdict: None,
+            }),
+        };
+        let mut output = match output {
+            // 找不到的只可能是 prlimit 本身：被限制的程序不存在时 prlimit 会正常启动并报错退出
+            Err(e) if through_prlimit && e.kind() == io::ErrorKind::NotFound => {
+                return Ok(CommandOutput {
+                    success: false,
+                    stdout: String::new(),
+                    stderr: "prlimit not found: memory_limit_mb and max_processes need prlimit from util-linux\n".to_string(),
+                    verdict: Some(Verdict::LimitsUnavailable),
+                });
+            }
+            output => output?,
+        };
+        if command.limits.memory_limit_mb.is_some() && memory_limit_exceeded(&output) {
+            output.verdict = Some(Verdict::MemoryLimitExceeded);
+        }
+        Ok(output)
+    }
+}
+
+// 受内存限制的进程分配失败时会中止并输出分配失败信息
+pub fn memory_limit_exceeded(output: &CommandOutput) -> bool {
+    !output.success
+        && output.verdict.is_none()
+        && (output.stderr.contains(ALLOCATION_FAILURE)
+            || output.stdout.contains(ALLOCATION_FAILURE))
+}
+
+// 边运行边读取输出，标准输出与标准错误合计超过 limit 字节时终止进程
+// 子进程自成一个进程组，超限时连同它派生的进程一起终止，不会留下仍在运行的孤儿进程
+fn run_with_output_limit(mut process: Command, limit: u64) -> io::Result<CommandOutput> {
+    #[cfg(unix)]
+    std::os::unix::process::CommandExt::process_group(&mut process, 0);
+    let mut child = process
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    let stdout = child.stdout.take().expect("stdout is piped");
+    let stderr = child.stderr.take().expect("stderr is piped");
+    let total = AtomicU64::new(0);
+    let exceeded = AtomicBool::new(false);
+
+    let (status, stdout, stderr) = thread::scope(|scope| {
+        let stdout = scope.spawn(|| read_limited(stdout, &total, &exceeded, limit));
+        let stderr = scope.spawn(|| read_limited(stderr, &total, &exceeded, limit));
+        let mut killed = false;
+        let status = loop {
+            if !killed && exceeded.load(Ordering::Relaxed) {
+                kill_process_group(&mut child);
+                killed = true;
+            }
+            match child.try_wait() {
+                Ok(Some(status)) => break Ok(status),
+                Ok(None) => thread::sleep(Duration::from_millis(10)),
+                Err(e) => break Err(e),
+            }
+        };
+        (
+            status,
+            stdout.join().unwrap_or_default(),
+            stderr.join().unwrap_or_default(),
+        )
+    });
+    let exceeded = exceeded.load(Ordering::Relaxed);
+    Ok(CommandOutput {
+        success: status?.success() && !exceeded,
+        stdout: String::from_utf8_lossy(&stdout).into_owned(),
+        stderr: String::from_utf8_lossy(&stderr).into_owned(),
+        verdict: exceeded.then_some(Verdict::OutputLimitExceeded),
+    })
+}
+
+// 终止以 child 为组长的整个进程组
+fn kill_process_group(child: &mut Child) {
+    #[cfg(unix)]
+    {
+        let _ = Command::new("kill")
+            .args(["-KILL", "--", &format!("-{}", child.id())])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status();
+    }
+    let _ = child.kill();
+}
+
+// 读取管道直到结束或总输出超限，超限后关闭管道（写端随之收到 EPIPE）
+fn read_limited(
+    mut pipe: impl Read,
+    total: &AtomicU64,
+    exceeded: &AtomicBool,
+    limit: u64,
+) -> Vec<u8> {
+    let mut captured = Vec::new();
+    let mut buffer = [0u8; 8192];
+    loop {
+        let read = match pipe.read(&mut buffer) {
+            Ok(0) | Err(_) => break,
+            Ok(read) => read,
+        };
+        let before = total.fetch_add(read as u64, Ordering::Relaxed);
+        let room = limit.saturating_sub(before) as usize;
+        captured.extend_from_slice(&buffer[..read.min(room)]);
+        if before + read as u64 > limit {
+            exceeded.store(true, Ordering::Relaxed);
+            break;
+        }
+    }
+    captured
+}
+
+// 包装另一个 CommandRunner，记录执行过的命令及其输出，供终端界面展示
+pub struct RecordingRunner<R: CommandRunner> {
+    inner: R,
+    log: RefCell<String>,
+}
+
+impl<R: CommandRunner> RecordingRunner<R> {
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            log: RefCell::new(String::new()),
+        }
+    }
+
+    // 取出目前记录的输出并清空
+    pub fn take_log(&self) -> String {
+        self.log.take()
+    }
+}
+
+impl<R: CommandRunner> CommandRunner for RecordingRunner<R> {
+    fn run(&self, command: &CommandSpec) -> io::Result<CommandOutput> {
+        let result = self.inner.run(command);
+        let mut log = self.log.borrow_mut();
+        log.push_str("$ ");
+        log.push_str(&command.program.to_string_lossy());
+        for arg in &command.args {
+            log.push(' ');
+            log.push_str(&arg.to_string_lossy());
+        }
+        log.push('\n');
+        match &result {
+            Ok(output) => {
+                log.push_str(&output.stdout);
+                log.push_str(&output.stderr);
+            }
+            Err(e) => log.push_str(&format!("error: {}\n", e)),
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rlimits_wrap_command_with_prlimit() {
+        let command = CommandSpec::new("./algorithm1").arg("hidden_tests::");
+        let (program, args) = command.program_and_args();
+        assert_eq!(program, "./algorithm1");
+        assert_eq!(args, vec!["hidden_tests::"]);
+
+        let command = command.limits(ResourceLimits {
+            memory_limit_mb: Some(64),
+            max_output_kb: Some(16),
+            max_processes: Some(32),
+        });
+        let (program, args) = command.program_and_args();
+        if cfg!(target_os = "linux") {
+            assert_eq!(program, "prlimit");
+            assert_eq!(
+                args,
+                vec![
+                    "--data=67108864",
+                    "--nproc=32",
+                    "--",
+                    "./algorithm1",
+                    "hidden_tests::"
+                ]
+            );
+        }
+    }
+
+    #[test]
+    fn test_allocation_failure_is_memory_limit_exceeded() {
+        let mut output = CommandOutput {
+            success: false,
+            stderr: "memory allocation of 1048576 bytes failed\n".to_string(),
+            ..CommandOutput::default()
+        };
+        assert!(memory_limit_exceeded(&output));
+        output.stderr = "thread 'main' panicked at src/main.rs:3:5".to_string();
+        assert!(!memory_limit_exceeded(&output));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_output_limit_kills_process() {
+        let command = CommandSpec::new("sh")
+            .args(["-c", "while true; do echo spam; done"])
+            .limits(ResourceLimits {
+                max_output_kb: Some(4),
+                ..ResourceLimits::default()
+            });
+        let output = SystemRunner.run(&command).unwrap();
+        assert!(!output.success);
+        assert_eq!(output.verdict, Some(Verdict::OutputLimitExceeded));
+        assert_eq!(output.stdout.len(), 4096);
+
+        let command = CommandSpec::new("sh")
+            .args(["-c", "echo ok"])
+            .limits(command.limits);
+        let output = SystemRunner.run(&command).unwrap();
+        assert!(output.success);
+        assert_eq!(output.verdict, None);
+        assert_eq!(output.stdout, "ok\n");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_output_limit_kills_whole_process_group() {
+        let marker = std::env::temp_dir().join(format!("runner-orphan-{}", std::process::id()));
+        let _ = std::fs::remove_file(&marker);
+        let script = format!(
+            "(sleep 0.5; touch '{}') & while true; do echo spam; done",
+            marker.display()
+        );
+        let command = CommandSpec::new("sh")
+            .args(["-c", &script])
+            .limits(ResourceLimits {
+                max_output_kb: Some(4),
+                ..ResourceLimits::default()
+            });
+        let output = SystemRunner.run(&command).unwrap();
+        assert_eq!(output.verdict, Some(Verdict::OutputLimitExceeded));
+        // 后台子进程与 sh 同组，应一并被终止
+        thread::sleep(Duration::from_millis(1000));
+        assert!(!marker.exists());
+    }
+}
@@ -0,0 +1,414 @@
+use crate::config::ExerciseConfig;
+use crate::report::{Environment, ExerciseResult, Report, ReportSignature, Statistics};
+use ed25519_dalek::{Signer, Verifier};
+use hmac::{Hmac, Mac};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+type HmacSha256 = Hmac<Sha256>;
+
+pub const HMAC_SHA256: &str = "hmac-sha256";
+pub const ED25519: &str = "ed25519";
+
+// 签名所用的密钥，从环境变量或密钥文件读取
+pub enum SigningKey {
+    Hmac(Vec<u8>),
+    Ed25519(ed25519_dalek::SigningKey),
+}
+
+// 教师端校验签名所用的密钥
+pub enum VerifyingKey {
+    Hmac(Vec<u8>),
+    Ed25519(ed25519_dalek::VerifyingKey),
+}
+
+impl SigningKey {
+    // 依次读取 GRADER_HMAC_KEY(_FILE) 与 GRADER_ED25519_KEY(_FILE)，都未设置时返回 None
+    pub fn from_env() -> io::Result<Option<Self>> {
+        if let Some(secret) = read_key_source("GRADER_HMAC_KEY")? {
+            return Ok(Some(SigningKey::Hmac(secret.into_bytes())));
+        }
+        if let Some(seed) = read_key_source("GRADER_ED25519_KEY")? {
+            let seed = decode_key::<32>(&seed)?;
+            return Ok(Some(SigningKey::Ed25519(
+                ed25519_dalek::SigningKey::from_bytes(&seed),
+            )));
+        }
+        Ok(None)
+    }
+
+    fn algorithm(&self) -> &'static str {
+        match self {
+            SigningKey::Hmac(_) => HMAC_SHA256,
+            SigningKey::Ed25519(_) => ED25519,
+        }
+    }
+}
+
+impl VerifyingKey {
+    // 依次读取 GRADER_HMAC_KEY(_FILE) 与 GRADER_ED25519_PUBLIC_KEY(_FILE)，都未设置时返回 None
+    pub fn from_env() -> io::Result<Option<Self>> {
+        if let Some(secret) = read_key_source("GRADER_HMAC_KEY")? {
+            return Ok(Some(VerifyingKey::Hmac(secret.into_bytes())));
+        }
+        if let Some(public_key) = read_key_source("GRADER_ED25519_PUBLIC_KEY")? {
+            let public_key = decode_key::<32>(&public_key)?;
+            let key = ed25519_dalek::VerifyingKey::from_bytes(&public_key)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+            return Ok(Some(VerifyingKey::Ed25519(key)));
+        }
+        Ok(None)
+    }
+}
+
+// 参与签名的内容：报告本身（不含签名）、签名算法与源码哈希
+#[derive(Serialize)]
+struct SignedPayload<'a> {
+    exercises: &'a [ExerciseResult],
+    statistics: &'a Statistics,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    environment: Option<&'a Environment>,
+    algorithm: &'a str,
+    sources: &'a BTreeMap<String, String>,
+}
+
+fn signed_payload(
+    report: &Report,
+    algorithm: &str,
+    sources: &BTreeMap<String, String>,
+) -> io::Result<Vec<u8>> {
+    let payload = SignedPayload {
+        exercises: &report.exercises,
+        statistics: &report.statistics,
+        environment: report.environment.as_ref(),
+        algorithm,
+        sources,
+    };
+    Ok(serde_json::to_vec(&payload)?)
+}
+
+// 计算每道习题源码的 SHA-256（Cargo 项目忽略 target 目录与 Cargo.lock）
+// extra_files 接在习题自身的文件之后，以相对 exercises/ 的路径计入
+pub fn hash_sources(
+    config: &ExerciseConfig,
+    exercises_dir: &Path,
+) -> io::Result<BTreeMap<String, String>> {
+    let mut sources = BTreeMap::new();
+    for exercise in config.exercises() {
+        let mut hasher = Sha256::new();
+        for (index, path) in exercise.source_paths().enumerate() {
+            let root = exercises_dir.join(path);
+            // 习题自身的文件名相对习题目录，与没有 extra_files 时的哈希保持一致
+            let prefix = if index == 0 {
+                root.as_path()
+            } else {
+                exercises_dir
+            };
+            let mut files = Vec::new();
+            collect_source_files(&root, &mut files)?;
+            files.sort();
+            for file in files {
+                let relative = file.strip_prefix(prefix).unwrap_or(&file);
+                let contents = fs::read(&file)?;
+                hasher.update(relative.to_string_lossy().as_bytes());
+                hasher.update([0]);
+                hasher.update((contents.len() as u64).to_le_bytes());
+                hasher.update(&contents);
+            }
+        }
+        sources.insert(exercise.path.clone(), to_hex(&hasher.finalize()));
+    }
+    Ok(sources)
+}
+
+fn collect_source_files(path: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+    if path.is_dir() {
+        for entry in fs::read_dir(path)? {
+            let entry = entry?;
+            if entry.file_name() != "target" && entry.file_name() != "Cargo.lock" {
+                collect_source_files(&entry.path(), files)?;
+            }
+        }
+    } else {
+        files.push(path.to_path_buf());
+    }
+    Ok(())
+}
+
+// 为报告签名，签名写入 report.signature
+pub fn sign_report(
+    report: &mut Report,
+    sources: BTreeMap<String, String>,
+    key: &SigningKey,
+) -> io::Result<()> {
+    let algorithm = key.algorithm();
+    let payload = signed_payload(report, algorithm, &sources)?;
+    let value = match key {
+        SigningKey::Hmac(secret) => {
+            let mut mac = HmacSha256::new_from_slice(secret)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+            mac.update(&payload);
+            to_hex(&mac.finalize().into_bytes())
+        }
+        SigningKey::Ed25519(signing_key) => to_hex(&signing_key.sign(&payload).to_bytes()),
+    };
+    report.signature = Some(ReportSignature {
+        algorithm: algorithm.to_string(),
+        sources,
+        value,
+    });
+    Ok(())
+}
+
+// 校验报告签名，报告或源码哈希被改动时返回错误
+pub fn verify_report(report: &Report, key: &VerifyingKey) -> io::Result<()> {
+    let invalid = |message: &str| io::Error::new(io::ErrorKind::InvalidData, message.to_string());
+    let signature = report
+        .signature
+        .as_ref()
+        .ok_or_else(|| invalid("report is not signed"))?;
+    let payload = signed_payload(report, &signature.algorithm, &signature.sources)?;
+    let value = from_hex(&signature.value).ok_or_else(|| invalid("malformed signature"))?;
+
+    let valid = match (key, signature.algorithm.as_str()) {
+        (VerifyingKey::Hmac(secret), HMAC_SHA256) => {
+            let mut mac = HmacSha256::new_from_slice(secret)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+            mac.update(&payload);
+            mac.verify_slice(&value).is_ok()
+        }
+        (VerifyingKey::Ed25519(verifying_key), ED25519) => {
+            let bytes: [u8; 64] = value
+                .try_into()
+                .map_err(|_| invalid("malformed signature"))?;
+            let signature = ed25519_dalek::Signature::from_bytes(&bytes);
+            verifying_key.verify(&payload, &signature).is_ok()
+        }
+        _ => {
+            return Err(invalid(
+                "signature algorithm does not match the supplied key",
+            ))
+        }
+    };
+
+    if valid {
+        Ok(())
+    } else {
+        Err(invalid("signature does not match report contents"))
+    }
+}
+
+// 对比签名中的源码哈希与当前检出的源码，返回不一致的习题路径
+pub fn changed_sources(
+    signature: &ReportSignature,
+    current: &BTreeMap<String, String>,
+) -> Vec<String> {
+    let mut changed: Vec<String> = signature
+        .sources
+        .iter()
+        .filter(|(path, hash)| current.get(*path) != Some(*hash))
+        .map(|(path, _)| path.clone())
+        .collect();
+    changed.extend(
+        current
+            .keys()
+            .filter(|path| !signature.sources.contains_key(*path))
+            .cloned(),
+    );
+    changed
+}
+
+// 读取密钥：优先使用环境变量 NAME，其次读取 NAME_FILE 指向的文件
+fn read_key_source(name: &str) -> io::Result<Option<String>> {
+    if let Ok(value) = env::var(name) {
+        return Ok(Some(value.trim().to_string()));
+    }
+    match env::var(format!("{}_FILE", name)) {
+        Ok(path) => Ok(Some(fs::read_to_string(path)?.trim().to_string())),
+        Err(_) => Ok(None),
+    }
+}
+
+fn decode_key<const N: usize>(hex: &str) -> io::Result<[u8; N]> {
+    from_hex(hex)
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("expected a {}-byte hex encoded key", N),
+            )
+        })
+}
+
+pub fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+pub fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report() -> Report {
+        let mut report = Report::new();
+        report.push(ExerciseResult {
+            name: "algorithm1.rs".to_string(),
+            result: false,
+            score: 0,
+            visible_result: None,
+            hidden_result: None,
+            time: 0,
+            time_ms: 0,
+            attempts: Vec::new(),
+            raw_score: None,
+            late_days: None,
+            verdict: None,
+            differential: None,
+        });
+        report
+    }
+
+    fn sources() -> BTreeMap<String, String> {
+        BTreeMap::from([("easy/algorithm1.rs".to_string(), "00".repeat(32))])
+    }
+
+    #[test]
+    fn test_hmac_round_trip_and_tamper() {
+        let mut report = report();
+        sign_report(
+            &mut report,
+            sources(),
+            &SigningKey::Hmac(b"secret".to_vec()),
+        )
+        .unwrap();
+        let key = VerifyingKey::Hmac(b"secret".to_vec());
+        assert!(verify_report(&report, &key).is_ok());
+
+        // 通过 JSON 往返后签名仍然有效
+        let json = serde_json::to_string_pretty(&report).unwrap();
+        let mut report: Report = serde_json::from_str(&json).unwrap();
+        assert!(verify_report(&report, &key).is_ok());
+
+        assert!(verify_report(&report, &VerifyingKey::Hmac(b"other".to_vec())).is_err());
+
+        report.exercises[0].result = true;
+        report.exercises[0].score = 1;
+        assert!(verify_report(&report, &key).is_err());
+    }
+
+    #[test]
+    fn test_ed25519_round_trip_and_tamper() {
+        let signing_key = ed25519_dalek::SigningKey::from_bytes(&[7; 32]);
+        let key = VerifyingKey::Ed25519(signing_key.verifying_key());
+        let mut report = report();
+        sign_report(&mut report, sources(), &SigningKey::Ed25519(signing_key)).unwrap();
+        assert!(verify_report(&report, &key).is_ok());
+
+        // 篡改源码哈希同样会使签名失效
+        report
+            .signature
+            .as_mut()
+            .unwrap()
+            .sources
+            .insert("easy/algorithm1.rs".to_string(), "11".repeat(32));
+        assert!(verify_report(&report, &key).is_err());
+    }
+
+    #[test]
+    fn test_unsigned_and_mismatched_algorithm() {
+        let key = VerifyingKey::Hmac(b"secret".to_vec());
+        assert!(verify_report(&report(), &key).is_err());
+
+        let signing_key = ed25519_dalek::SigningKey::from_bytes(&[7; 32]);
+        let mut report = report();
+        sign_report(&mut report, sources(), &SigningKey::Ed25519(signing_key)).unwrap();
+        assert!(verify_report(&report, &key).is_err());
+    }
+
+    #[test]
+    fn test_hash_sources_include_extra_files() {
+        let dir = std::env::temp_dir().join(format!("grader_sign_extra_{}", std::process::id()));
+        fs::create_dir_all(dir.join("easy")).unwrap();
+        fs::write(dir.join("easy/algorithm5.rs"), "mod graph;\n").unwrap();
+        fs::write(dir.join("easy/graph.rs"), "pub struct Graph;\n").unwrap();
+        let config: ExerciseConfig = serde_json::from_str(
+            r#"{
+                "easy": [
+                    { "name": "algorithm5.rs", "path": "easy/algorithm5.rs", "type": "single_file", "score": 1, "extra_files": ["easy/graph.rs"] },
+                    { "name": "algorithm6.rs", "path": "easy/graph.rs", "type": "single_file", "score": 1 }
+                ],
+                "normal": [],
+                "hard": []
+            }"#,
+        )
+        .unwrap();
+
+        let before = hash_sources(&config, &dir).unwrap();
+        fs::write(dir.join("easy/graph.rs"), "pub struct Graph(Vec<usize>);\n").unwrap();
+        let after = hash_sources(&config, &dir).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_ne!(before["easy/algorithm5.rs"], after["easy/algorithm5.rs"]);
+        // 附加文件带着路径计入，不会与把同一文件当作习题本身时的哈希相同
+        assert_ne!(before["easy/algorithm5.rs"], before["easy/graph.rs"]);
+    }
+
+    #[test]
+    fn test_hash_sources_detects_changes() {
+        let dir = std::env::temp_dir().join(format!("grader_sign_{}", std::process::id()));
+        fs::create_dir_all(dir.join("easy")).unwrap();
+        fs::create_dir_all(dir.join("hard/solutiont1/src")).unwrap();
+        fs::create_dir_all(dir.join("hard/solutiont1/target")).unwrap();
+        fs::write(dir.join("easy/algorithm1.rs"), "fn a() {}\n").unwrap();
+        fs::write(dir.join("hard/solutiont1/src/main.rs"), "fn main() {}\n").unwrap();
+        let config: ExerciseConfig = serde_json::from_str(
+            r#"{
+                "easy": [{ "name": "algorithm1.rs", "path": "easy/algorithm1.rs", "type": "single_file", "score": 1 }],
+                "normal": [],
+                "hard": [{ "name": "solutiont1", "path": "hard/solutiont1", "type": "cargo_project", "score": 10 }]
+            }"#,
+        )
+        .unwrap();
+
+        let before = hash_sources(&config, &dir).unwrap();
+        // 构建产物不影响哈希
+        fs::write(dir.join("hard/solutiont1/target/out"), "binary").unwrap();
+        assert_eq!(hash_sources(&config, &dir).unwrap(), before);
+
+        fs::write(dir.join("easy/algorithm1.rs"), "fn a() { todo!() }\n").unwrap();
+        let after = hash_sources(&config, &dir).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        let signature = ReportSignature {
+            algorithm: HMAC_SHA256.to_string(),
+            sources: before,
+            value: String::new(),
+        };
+        assert_eq!(
+            changed_sources(&signature, &after),
+            vec!["easy/algorithm1.rs"]
+        );
+    }
+
+    #[test]
+    fn test_hex() {
+        assert_eq!(to_hex(&[0, 15, 255]), "000fff");
+        assert_eq!(from_hex("000fff"), Some(vec![0, 15, 255]));
+        assert_eq!(from_hex("0g"), None);
+        assert_eq!(from_hex("abc"), None);
+    }
+}
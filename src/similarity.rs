@@ -0,0 +1,745 @@
+use crate::config::{Exercise, ExerciseConfig};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+// 每个指纹覆盖的 token 数，短于该长度的重复片段视为噪声
+const KGRAM: usize = 10;
+// 取样窗口大小，长度不小于 KGRAM + WINDOW - 1 的重复片段一定会被发现
+const WINDOW: usize = 5;
+
+// 归一化时保留原样的关键字，其余标识符统一替换
+const KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern",
+    "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
+    "ref", "return", "self", "Self", "static", "struct", "super", "trait", "true", "type",
+    "unsafe", "use", "where", "while",
+];
+
+// 归一化后的 token 及其所在位置
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Token {
+    text: String,
+    file: usize,
+    line: usize,
+}
+
+// 一份提交（某个检出目录中的一道习题）的全部 token 与指纹
+#[derive(Debug)]
+pub struct Submission {
+    pub name: String,
+    files: Vec<String>,
+    tokens: Vec<Token>,
+    // (k-gram 哈希, 起始 token 下标)，按下标递增
+    fingerprints: Vec<(u64, usize)>,
+}
+
+// 代码片段在某个文件中的行范围
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct SourceSpan {
+    pub file: String,
+    pub start_line: usize,
+    pub end_line: usize,
+}
+
+// 两份提交之间的一段重复代码
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct OverlapRegion {
+    pub first: SourceSpan,
+    pub second: SourceSpan,
+    pub tokens: usize,
+}
+
+#[derive(Serialize, Debug)]
+pub struct SimilarPair {
+    pub first: String,
+    pub second: String,
+    // 共享指纹数占较小一方指纹数的比例
+    pub similarity: f64,
+    pub shared_fingerprints: usize,
+    pub regions: Vec<OverlapRegion>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct ExerciseSimilarity {
+    pub exercise: String,
+    pub submissions: usize,
+    pub pairs: Vec<SimilarPair>,
+}
+
+#[derive(Serialize, Debug, Default)]
+pub struct SimilarityReport {
+    pub exercises: Vec<ExerciseSimilarity>,
+}
+
+impl Submission {
+    // sources 为 (相对路径, 源码) 列表
+    pub fn from_sources(name: &str, sources: &[(String, String)]) -> Self {
+        let mut files = Vec::new();
+        let mut tokens = Vec::new();
+        let mut fingerprints = Vec::new();
+        for (file, (path, source)) in sources.iter().enumerate() {
+            let offset = tokens.len();
+            let file_tokens = tokenize(source, file);
+            // 指纹不跨越文件边界
+            let hashes: Vec<u64> = file_tokens.windows(KGRAM).map(hash_kgram).collect();
+            fingerprints.extend(
+                winnow(&hashes, WINDOW)
+                    .into_iter()
+                    .map(|(hash, pos)| (hash, pos + offset)),
+            );
+            files.push(path.clone());
+            tokens.extend(file_tokens);
+        }
+        Self {
+            name: name.to_string(),
+            files,
+            tokens,
+            fingerprints,
+        }
+    }
+
+    // 读取习题路径下的全部 .rs 文件（Cargo 项目忽略 target 目录）
+    pub fn load(name: &str, path: &Path) -> io::Result<Self> {
+        let mut sources = Vec::new();
+        read_rust_sources(path, path, &mut sources)?;
+        Ok(Self::from_sources(name, &sources))
+    }
+
+    // 读取某个检出目录下的习题，extra_files 以相对 exercises/ 的路径一并载入
+    pub fn load_exercise(
+        name: &str,
+        exercises_dir: &Path,
+        exercise: &Exercise,
+    ) -> io::Result<Self> {
+        let path = exercises_dir.join(&exercise.path);
+        let mut sources = Vec::new();
+        read_rust_sources(&path, &path, &mut sources)?;
+        for extra in &exercise.extra_files {
+            let extra = exercises_dir.join(extra);
+            if extra.exists() {
+                read_rust_sources(&extra, exercises_dir, &mut sources)?;
+            }
+        }
+        Ok(Self::from_sources(name, &sources))
+    }
+
+    fn hashes(&self) -> HashSet<u64> {
+        self.fingerprints.iter().map(|&(hash, _)| hash).collect()
+    }
+
+    // 将 token 区间 [start, end] 换算为文件行范围
+    fn span(&self, start: usize, end: usize) -> SourceSpan {
+        SourceSpan {
+            file: self.files[self.tokens[start].file].clone(),
+            start_line: self.tokens[start].line,
+            end_line: self.tokens[end].line,
+        }
+    }
+}
+
+// 比较两份提交，ignored 中的指纹（例如习题模板）不计入；没有共享指纹时返回 None
+pub fn compare(
+    first: &Submission,
+    second: &Submission,
+    ignored: &HashSet<u64>,
+) -> Option<SimilarPair> {
+    let first_hashes: HashSet<u64> = first.hashes().difference(ignored).copied().collect();
+    let second_hashes: HashSet<u64> = second.hashes().difference(ignored).copied().collect();
+    let shared: HashSet<u64> = first_hashes.intersection(&second_hashes).copied().collect();
+    if shared.is_empty() {
+        return None;
+    }
+
+    let mut second_positions: HashMap<u64, Vec<usize>> = HashMap::new();
+    for &(hash, pos) in &second.fingerprints {
+        second_positions.entry(hash).or_default().push(pos);
+    }
+    let matches: Vec<(usize, &[usize])> = first
+        .fingerprints
+        .iter()
+        .filter(|(hash, _)| shared.contains(hash))
+        .map(|(hash, pos)| (*pos, second_positions[hash].as_slice()))
+        .collect();
+
+    let smaller = first_hashes.len().min(second_hashes.len());
+    Some(SimilarPair {
+        first: first.name.clone(),
+        second: second.name.clone(),
+        similarity: shared.len() as f64 / smaller as f64,
+        shared_fingerprints: shared.len(),
+        regions: overlap_regions(first, second, &matches),
+    })
+}
+
+// 将相邻的匹配指纹合并为重复片段，按片段长度降序排列
+// matches 为 (first 中的位置, second 中哈希相同的全部位置)，按 first 中的位置递增
+fn overlap_regions(
+    first: &Submission,
+    second: &Submission,
+    matches: &[(usize, &[usize])],
+) -> Vec<OverlapRegion> {
+    // 两个指纹之间最多相隔一个窗口即视为连续
+    let max_gap = KGRAM + WINDOW;
+    let mut ranges: Vec<(usize, usize, usize, usize)> = Vec::new();
+    for &(a, candidates) in matches {
+        if let Some(last) = ranges.last_mut() {
+            let (_, a_end, _, b_end) = *last;
+            let continues = candidates.iter().find(|&&b| {
+                b > b_end
+                    && b <= b_end + max_gap
+                    && second.tokens[b].file == second.tokens[b_end].file
+            });
+            if let Some(&b) = continues {
+                if a - a_end <= max_gap && first.tokens[a].file == first.tokens[a_end].file {
+                    last.1 = a;
+                    last.3 = b;
+                    continue;
+                }
+            }
+        }
+        // 新片段优先选择位于上一片段之后的位置，保持两边顺序一致
+        let previous_end = ranges.last().map_or(0, |&(_, _, _, b_end)| b_end);
+        let b = candidates
+            .iter()
+            .copied()
+            .find(|&b| b > previous_end)
+            .unwrap_or(candidates[0]);
+        ranges.push((a, a, b, b));
+    }
+
+    let mut regions: Vec<OverlapRegion> = ranges
+        .into_iter()
+        .map(|(a_start, a_end, b_start, b_end)| {
+            let a_end = a_end + KGRAM - 1;
+            let b_end = b_end + KGRAM - 1;
+            OverlapRegion {
+                first: first.span(a_start, a_end),
+                second: second.span(b_start, b_end),
+                tokens: a_end - a_start + 1,
+            }
+        })
+        .collect();
+    regions.sort_by_key(|region| std::cmp::Reverse(region.tokens));
+    regions
+}
+
+// 对 checkouts_dir 下每个检出目录中的同一道习题两两比较
+// base 为习题模板所在的检出目录，其中出现过的指纹不计入相似度
+pub fn detect_similarity(
+    config: &ExerciseConfig,
+    checkouts_dir: &Path,
+    base: Option<&Path>,
+    min_similarity: f64,
+) -> io::Result<SimilarityReport> {
+    // 比较规范化后的路径，./checkouts/template 与绝对路径都能识别为模板
+    let base_dir = base.map(fs::canonicalize).transpose()?;
+    let mut checkouts: Vec<PathBuf> = fs::read_dir(checkouts_dir)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_dir() && fs::canonicalize(path).ok() != base_dir)
+        .collect();
+    checkouts.sort();
+
+    let mut report = SimilarityReport::default();
+    for exercise in config.exercises() {
+        let mut submissions = Vec::new();
+        for checkout in &checkouts {
+            let exercises_dir = checkout.join("exercises");
+            if !exercises_dir.join(&exercise.path).exists() {
+                continue;
+            }
+            let name = checkout.file_name().unwrap_or_default().to_string_lossy();
+            submissions.push(Submission::load_exercise(&name, &exercises_dir, exercise)?);
+        }
+        if submissions.len() < 2 {
+            continue;
+        }
+
+        let ignored = match base {
+            Some(base) => {
+                let exercises_dir = base.join("exercises");
+                if exercises_dir.join(&exercise.path).exists() {
+                    Submission::load_exercise("base", &exercises_dir, exercise)?.hashes()
+                } else {
+                    HashSet::new()
+                }
+            }
+            None => HashSet::new(),
+        };
+
+        let mut pairs = Vec::new();
+        for (i, first) in submissions.iter().enumerate() {
+            for second in &submissions[i + 1..] {
+                if let Some(pair) = compare(first, second, &ignored) {
+                    if pair.similarity >= min_similarity {
+                        pairs.push(pair);
+                    }
+                }
+            }
+        }
+        pairs.sort_by(|x, y| y.similarity.total_cmp(&x.similarity));
+        report.exercises.push(ExerciseSimilarity {
+            exercise: exercise.path.clone(),
+            submissions: submissions.len(),
+            pairs,
+        });
+    }
+    Ok(report)
+}
+
+// 以纯文本形式输出相似度报告
+pub fn print_report(report: &SimilarityReport) {
+    for exercise in &report.exercises {
+        println!(
+            "== {} ({} submissions, {} similar pairs)",
+            exercise.exercise,
+            exercise.submissions,
+            exercise.pairs.len()
+        );
+        for pair in &exercise.pairs {
+            println!(
+                "{:>6.1}%  {} <-> {}  ({} shared fingerprints)",
+                pair.similarity * 100.0,
+                pair.first,
+                pair.second,
+                pair.shared_fingerprints
+            );
+            for region in &pair.regions {
+                println!(
+                    "         {}:{}-{}  ~  {}:{}-{}  ({} tokens)",
+                    region.first.file,
+                    region.first.start_line,
+                    region.first.end_line,
+                    region.second.file,
+                    region.second.start_line,
+                    region.second.end_line,
+                    region.tokens
+                );
+            }
+        }
+    }
+}
+
+// 将 Rust 源码切分为 token：忽略空白与注释，标识符替换为 $id，字面量替换为 $str / $num
+fn tokenize(source: &str, file: usize) -> Vec<Token> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut line = 1;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let start_line = line;
+        let text = if c == '\n' {
+            line += 1;
+            i += 1;
+            continue;
+        } else if c.is_whitespace() {
+            i += 1;
+            continue;
+        } else if c == '/' && chars.get(i + 1) == Some(&'/') {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+            continue;
+        } else if c == '/' && chars.get(i + 1) == Some(&'*') {
+            // 块注释可以嵌套
+            let mut depth = 0;
+            while i < chars.len() {
+                if chars[i] == '/' && chars.get(i + 1) == Some(&'*') {
+                    depth += 1;
+                    i += 2;
+                } else if chars[i] == '*' && chars.get(i + 1) == Some(&'/') {
+                    depth -= 1;
+                    i += 2;
+                    if depth == 0 {
+                        break;
+                    }
+                } else {
+                    if chars[i] == '\n' {
+                        line += 1;
+                    }
+                    i += 1;
+                }
+            }
+            continue;
+        } else if let Some(end) = string_literal_end(&chars, i) {
+            line += chars[i..end].iter().filter(|&&ch| ch == '\n').count();
+            i = end;
+            "$str".to_string()
+        } else if c == '\'' {
+            // 字符字面量或生命周期
+            if chars.get(i + 1) == Some(&'\\') {
+                i += 2;
+                while i < chars.len() && chars[i] != '\'' {
+                    i += 1;
+                }
+                i += 1;
+                "$str".to_string()
+            } else if chars.get(i + 2) == Some(&'\'') {
+                i += 3;
+                "$str".to_string()
+            } else {
+                i += 1;
+                while i < chars.len() && is_ident_char(chars[i]) {
+                    i += 1;
+                }
+                "'$id".to_string()
+            }
+        } else if c.is_ascii_digit() {
+            while i < chars.len()
+                && (is_ident_char(chars[i])
+                    || (chars[i] == '.' && chars.get(i + 1).is_some_and(char::is_ascii_digit)))
+            {
+                i += 1;
+            }
+            "$num".to_string()
+        } else if c == '_' || c.is_alphabetic() {
+            let start = i;
+            while i < chars.len() && is_ident_char(chars[i]) {
+                i += 1;
+            }
+            let word: String = chars[start..i].iter().collect();
+            if KEYWORDS.contains(&word.as_str()) {
+                word
+            } else {
+                "$id".to_string()
+            }
+        } else {
+            i += 1;
+            c.to_string()
+        };
+        tokens.push(Token {
+            text,
+            file,
+            line: start_line,
+        });
+    }
+    tokens
+}
+
+fn is_ident_char(c: char) -> bool {
+    c == '_' || c.is_alphanumeric()
+}
+
+// 若 start 处是字符串字面量（含 b"" 与 r#""# 等形式），返回其结束位置
+fn string_literal_end(chars: &[char], start: usize) -> Option<usize> {
+    let mut i = start;
+    if matches!(chars.get(i), Some('b' | 'c')) {
+        i += 1;
+    }
+    let raw = chars.get(i) == Some(&'r');
+    if raw {
+        i += 1;
+    }
+    let mut hashes = 0;
+    while raw && chars.get(i) == Some(&'#') {
+        hashes += 1;
+        i += 1;
+    }
+    if chars.get(i) != Some(&'"') {
+        return None;
+    }
+    i += 1;
+    while i < chars.len() {
+        match chars[i] {
+            '\\' if !raw => i += 2,
+            '"' if chars[i + 1..]
+                .iter()
+                .take(hashes)
+                .filter(|&&c| c == '#')
+                .count()
+                == hashes =>
+            {
+                return Some(i + 1 + hashes);
+            }
+            _ => i += 1,
+        }
+    }
+    Some(chars.len())
+}
+
+// FNV-1a 哈希，保证不同平台与版本间结果一致
+fn hash_kgram(kgram: &[Token]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for token in kgram {
+        for byte in token.text.bytes().chain([0]) {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+    }
+    hash
+}
+
+// winnowing：在每个窗口中取最小哈希（相同时取最右侧），返回 (哈希, 位置)
+fn winnow(hashes: &[u64], window: usize) -> Vec<(u64, usize)> {
+    let mut selected: Vec<(u64, usize)> = Vec::new();
+    for (start, window) in hashes.windows(window.min(hashes.len()).max(1)).enumerate() {
+        let (offset, &hash) = window
+            .iter()
+            .enumerate()
+            .rev()
+            .min_by_key(|&(_, hash)| *hash)
+            .unwrap();
+        let pos = start + offset;
+        if selected.last().is_none_or(|&(_, last)| last != pos) {
+            selected.push((hash, pos));
+        }
+    }
+    selected
+}
+
+// 读取 path 下的 .rs 文件，文件名相对 base；path 本身是单个文件且等于 base 时使用文件名
+fn read_rust_sources(
+    path: &Path,
+    base: &Path,
+    sources: &mut Vec<(String, String)>,
+) -> io::Result<()> {
+    let mut paths = Vec::new();
+    collect_rust_files(path, &mut paths)?;
+    paths.sort();
+    for file in paths {
+        let relative = match file.strip_prefix(base) {
+            Ok(relative) if !relative.as_os_str().is_empty() => relative,
+            _ => Path::new(file.file_name().unwrap_or_default()),
+        };
+        sources.push((
+            relative.to_string_lossy().into_owned(),
+            fs::read_to_string(&file)?,
+        ));
+    }
+    Ok(())
+}
+
+fn collect_rust_files(path: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+    if path.is_dir() {
+        for entry in fs::read_dir(path)? {
+            let entry = entry?;
+            if entry.file_name() != "target" {
+                collect_rust_files(&entry.path(), files)?;
+            }
+        }
+    } else if path.extension().is_some_and(|ext| ext == "rs") {
+        files.push(path.to_path_buf());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ORIGINAL: &str = r#"
+use std::collections::HashMap;
+
+// 统计每个区县出现的次数
+pub fn count_districts(lines: &[&str]) -> HashMap<String, usize> {
+    let mut counts = HashMap::new();
+    for line in lines {
+        let name = line.trim().to_string();
+        if name.is_empty() {
+            continue;
+        }
+        *counts.entry(name).or_insert(0) += 1;
+    }
+    counts
+}
+"#;
+
+    // 改名、改注释、改字面量后的副本
+    const RENAMED: &str = r#"
+use std::collections::HashMap;
+
+/* counts how often each district appears */
+pub fn tally(input: &[&str]) -> HashMap<String, usize> {
+    let mut result = HashMap::new();
+    for row in input {
+        let key = row.trim().to_string();
+        if key.is_empty() { continue; }
+        *result.entry(key).or_insert(7) += 1;
+    }
+    result
+}
+"#;
+
+    const UNRELATED: &str = r#"
+fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+fn lcm(a: u64, b: u64) -> u64 {
+    a / gcd(a, b) * b
+}
+"#;
+
+    fn submission(name: &str, source: &str) -> Submission {
+        Submission::from_sources(name, &[("src/district.rs".to_string(), source.to_string())])
+    }
+
+    fn texts(source: &str) -> Vec<String> {
+        tokenize(source, 0)
+            .into_iter()
+            .map(|token| token.text)
+            .collect()
+    }
+
+    #[test]
+    fn test_tokenize_normalizes_identifiers_and_literals() {
+        assert_eq!(
+            texts("let x = \"a // b\"; // comment\nlet y = 'c' as u8 + 1_000;"),
+            vec![
+                "let", "$id", "=", "$str", ";", "let", "$id", "=", "$str", "as", "$id", "+",
+                "$num", ";"
+            ]
+        );
+        assert_eq!(
+            texts("fn f<'a>(s: &'a str) -> &'a str { r#\"x\"y\"# /* /* nested */ */ }"),
+            texts("fn g<'b>(t: &'b str) -> &'b str { b\"z\" }")
+        );
+        let lines: Vec<usize> = tokenize("a\n/*\n\n*/ b\n\"x\ny\" c", 0)
+            .iter()
+            .map(|token| token.line)
+            .collect();
+        assert_eq!(lines, vec![1, 4, 5, 6]);
+    }
+
+    #[test]
+    fn test_winnow_selects_window_minimum() {
+        let hashes = [
+            77, 72, 42, 17, 98, 50, 17, 98, 8, 88, 67, 39, 77, 72, 42, 17, 98,
+        ];
+        let selected: Vec<u64> = winnow(&hashes, 4)
+            .into_iter()
+            .map(|(hash, _)| hash)
+            .collect();
+        assert_eq!(selected, vec![17, 17, 8, 39, 17]);
+        assert_eq!(winnow(&[3, 1], 4), vec![(1, 1)]);
+        assert!(winnow(&[], 4).is_empty());
+    }
+
+    #[test]
+    fn test_renamed_copy_is_detected() {
+        let original = submission("alice", ORIGINAL);
+        let renamed = submission("bob", RENAMED);
+        let unrelated = submission("carol", UNRELATED);
+
+        let pair = compare(&original, &renamed, &HashSet::new()).unwrap();
+        assert!(pair.similarity > 0.9, "similarity {}", pair.similarity);
+        let region = &pair.regions[0];
+        assert_eq!(region.first.file, "src/district.rs");
+        assert_eq!(region.first.start_line, 2);
+        assert_eq!(region.first.end_line, 12);
+
+        let unrelated_similarity =
+            compare(&original, &unrelated, &HashSet::new()).map_or(0.0, |pair| pair.similarity);
+        assert!(unrelated_similarity < 0.2);
+    }
+
+    #[test]
+    fn test_base_fingerprints_are_ignored() {
+        let original = submission("alice", ORIGINAL);
+        let renamed = submission("bob", RENAMED);
+        // 两人都只提交了模板代码时不算相似
+        assert!(compare(&original, &renamed, &original.hashes()).is_none());
+    }
+
+    #[test]
+    fn test_detect_similarity_across_checkouts() {
+        let dir = std::env::temp_dir().join(format!("grader_similarity_{}", std::process::id()));
+        for (name, source) in [("alice", ORIGINAL), ("bob", RENAMED), ("carol", UNRELATED)] {
+            let src = dir.join(name).join("exercises/hard/solutiont3/src");
+            fs::create_dir_all(&src).unwrap();
+            fs::create_dir_all(src.join("../target")).unwrap();
+            fs::write(src.join("district.rs"), source).unwrap();
+            fs::write(src.join("../target/generated.rs"), ORIGINAL).unwrap();
+        }
+        let config: ExerciseConfig = serde_json::from_str(
+            r#"{
+                "easy": [{ "name": "algorithm1.rs", "path": "easy/algorithm1.rs", "type": "single_file", "score": 1 }],
+                "normal": [],
+                "hard": [{ "name": "solutiont3", "path": "hard/solutiont3", "type": "cargo_project", "score": 10 }]
+            }"#,
+        )
+        .unwrap();
+        let report = detect_similarity(&config, &dir, None, 0.5).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(report.exercises.len(), 1);
+        let exercise = &report.exercises[0];
+        assert_eq!(exercise.exercise, "hard/solutiont3");
+        assert_eq!(exercise.submissions, 3);
+        assert_eq!(exercise.pairs.len(), 1);
+        assert_eq!(exercise.pairs[0].first, "alice");
+        assert_eq!(exercise.pairs[0].second, "bob");
+        assert_eq!(exercise.pairs[0].regions[0].first.file, "src/district.rs");
+    }
+
+    #[test]
+    fn test_base_checkout_is_not_a_submission() {
+        let dir =
+            std::env::temp_dir().join(format!("grader_similarity_base_{}", std::process::id()));
+        for (name, source) in [
+            ("alice", RENAMED),
+            ("bob", UNRELATED),
+            ("template", ORIGINAL),
+        ] {
+            let file = dir.join(name).join("exercises/easy/algorithm1.rs");
+            fs::create_dir_all(file.parent().unwrap()).unwrap();
+            fs::write(file, source).unwrap();
+        }
+        let config: ExerciseConfig = serde_json::from_str(
+            r#"{
+                "easy": [{ "name": "algorithm1.rs", "path": "easy/algorithm1.rs", "type": "single_file", "score": 1 }],
+                "normal": [],
+                "hard": []
+            }"#,
+        )
+        .unwrap();
+        // 与 read_dir 得到的路径写法不同的模板目录
+        let base = dir.join("alice/../template");
+        let report = detect_similarity(&config, &dir, Some(&base), 0.0).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(report.exercises.len(), 1);
+        assert_eq!(report.exercises[0].submissions, 2);
+        assert!(report.exercises[0]
+            .pairs
+            .iter()
+            .all(|pair| pair.first != "template" && pair.second != "template"));
+    }
+    #[test]
+    fn test_extra_files_are_compared() {
+        let dir =
+            std::env::temp_dir().join(format!("grader_similarity_extra_{}", std::process::id()));
+        for (name, exercise, graph) in [("alice", UNRELATED, ORIGINAL), ("bob", "", RENAMED)] {
+            let exercises = dir.join(name).join("exercises/easy");
+            fs::create_dir_all(&exercises).unwrap();
+            fs::write(exercises.join("algorithm5.rs"), exercise).unwrap();
+            fs::write(exercises.join("graph.rs"), graph).unwrap();
+        }
+        let config: ExerciseConfig = serde_json::from_str(
+            r#"{
+                "easy": [{ "name": "algorithm5.rs", "path": "easy/algorithm5.rs", "type": "single_file", "score": 1, "extra_files": ["easy/graph.rs"] }],
+                "normal": [],
+                "hard": []
+            }"#,
+        )
+        .unwrap();
+        let report = detect_similarity(&config, &dir, None, 0.0).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        let pairs = &report.exercises[0].pairs;
+        assert_eq!(pairs.len(), 1);
+        assert!(pairs[0]
+            .regions
+            .iter()
+            .any(|region| region.first.file == "easy/graph.rs"
+                && region.second.file == "easy/graph.rs"));
+    }
+}
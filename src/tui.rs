@@ -0,0 +1,580 @@
+use crate::config::{Exercise, ExerciseConfig};
+use crate::evaluator::Grader;
+use crate::list::{collect_statuses, ExerciseStatus, NOT_DONE_MARKER};
+use crate::report::Report;
+use crate::runner::{CommandRunner, RecordingRunner};
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+// 左侧习题列表的最大宽度
+const LIST_WIDTH: usize = 34;
+
+// 终端界面支持的按键
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    Up,
+    Down,
+    PageUp,
+    PageDown,
+    Char(char),
+    Other,
+}
+
+// 将一次读取到的字节解析为按键
+pub fn decode_key(bytes: &[u8]) -> Key {
+    match bytes {
+        b"\x1b[A" | b"\x1bOA" => Key::Up,
+        b"\x1b[B" | b"\x1bOB" => Key::Down,
+        b"\x1b[5~" => Key::PageUp,
+        b"\x1b[6~" => Key::PageDown,
+        [byte] if byte.is_ascii() && *byte != 0x1b => Key::Char(*byte as char),
+        _ => Key::Other,
+    }
+}
+
+// 界面状态
+struct App<'a> {
+    config: &'a ExerciseConfig,
+    statuses: Vec<ExerciseStatus>,
+    // 本次会话中每道习题最近一次评测的输出
+    outputs: Vec<Option<String>>,
+    selected: usize,
+    // 右侧窗格的滚动行数
+    scroll: usize,
+    show_hint: bool,
+    message: String,
+}
+
+impl App<'_> {
+    fn exercise(&self) -> &Exercise {
+        self.config
+            .exercises()
+            .nth(self.selected)
+            .expect("selected exercise exists")
+    }
+
+    fn select(&mut self, index: usize) {
+        if index != self.selected {
+            self.selected = index;
+            self.scroll = 0;
+            self.show_hint = false;
+        }
+    }
+
+    // 从当前位置向后查找下一道未通过（或尚未评测）的习题
+    fn next_failure(&self) -> Option<usize> {
+        let count = self.statuses.len();
+        (1..=count)
+            .map(|offset| (self.selected + offset) % count)
+            .find(|&index| self.statuses[index].last_result != Some(true))
+    }
+
+    // 右侧窗格的内容：题目、提示与最近一次输出
+    fn detail_lines(&self, exercises_dir: &Path) -> Vec<String> {
+        let exercise = self.exercise();
+        let status = &self.statuses[self.selected];
+        let mut lines = vec![
+            format!(
+                "{} ({}, {}, {} points)",
+                exercise.name, status.level, exercise.exercise_type, exercise.score
+            ),
+            format!("Path: exercises/{}", exercise.path),
+            String::new(),
+        ];
+        lines.extend(
+            statement(exercise, exercises_dir)
+                .lines()
+                .map(str::to_string),
+        );
+        if self.show_hint {
+            lines.push(String::new());
+            lines.push("── Hint ──".to_string());
+            match &exercise.hint {
+                Some(hint) => lines.extend(hint.lines().map(str::to_string)),
+                None => lines.push("No hint for this exercise.".to_string()),
+            }
+        }
+        lines.push(String::new());
+        lines.push("── Last output ──".to_string());
+        match &self.outputs[self.selected] {
+            Some(output) => lines.extend(output.lines().map(strip_ansi)),
+            None => lines.push("Not run in this session, press r to run.".to_string()),
+        }
+        lines
+    }
+
+    // 渲染整个屏幕，返回每一行（含颜色控制序列）
+    fn render(&self, exercises_dir: &Path, rows: usize, cols: usize) -> Vec<String> {
+        let body_rows = rows.saturating_sub(2);
+        let list_width = LIST_WIDTH.min(cols / 3);
+        let detail_width = cols.saturating_sub(list_width + 1);
+
+        let passed = self
+            .statuses
+            .iter()
+            .filter(|status| status.last_result == Some(true))
+            .count();
+        let score: i32 = self.statuses.iter().filter_map(|s| s.last_score).sum();
+        let title = format!(
+            " Rust-Professional  {}/{} passed  score {}",
+            passed,
+            self.statuses.len(),
+            score
+        );
+        let mut screen = vec![format!("\x1b[7m{}\x1b[0m", pad(&title, cols))];
+
+        // 选中项超出可见范围时滚动列表
+        let list_offset = (self.selected + 1).saturating_sub(body_rows);
+        let details: Vec<String> = self
+            .detail_lines(exercises_dir)
+            .iter()
+            .flat_map(|line| wrap(line, detail_width))
+            .collect();
+        for row in 0..body_rows {
+            let index = list_offset + row;
+            let item = match self.statuses.get(index) {
+                Some(status) => {
+                    let (icon, color) = match status.last_result {
+                        Some(true) => ("✔", "32"),
+                        Some(false) => ("✘", "31"),
+                        None => ("○", "90"),
+                    };
+                    let marker = if status.not_done { " *" } else { "" };
+                    let text = pad(&format!(" {} {}{}", icon, status.name, marker), list_width);
+                    if index == self.selected {
+                        format!("\x1b[7m{}\x1b[0m", text)
+                    } else {
+                        format!("\x1b[{}m{}\x1b[0m", color, text)
+                    }
+                }
+                None => pad("", list_width),
+            };
+            let detail = details
+                .get(self.scroll + row)
+                .map_or(String::new(), |line| pad(line, detail_width));
+            screen.push(format!("{}│{}", item, detail));
+        }
+
+        let footer = if self.message.is_empty() {
+            " ↑/↓ select  r run  h hint  e edit  n next failure  PgUp/PgDn scroll  q quit"
+        } else {
+            &self.message
+        };
+        screen.push(format!("\x1b[7m{}\x1b[0m", pad(footer, cols)));
+        screen
+    }
+}
+
+// 进入全屏界面，直到按下 q；重新评测的结果记录到 report 中
+pub fn run<R: CommandRunner>(
+    config: &ExerciseConfig,
+    grader: &Grader<RecordingRunner<R>>,
+    report: &mut Report,
+    exercises_dir: &Path,
+) -> io::Result<()> {
+    let mut app = App {
+        config,
+        statuses: collect_statuses(config, Some(report), exercises_dir),
+        outputs: vec![None; config.exercises().count()],
+        selected: 0,
+        scroll: 0,
+        show_hint: false,
+        message: String::new(),
+    };
+    if app.statuses.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "no exercises configured",
+        ));
+    }
+
+    let mut terminal = Terminal::enter()?;
+    let mut stdin = io::stdin();
+    let mut buffer = [0u8; 8];
+    loop {
+        let (rows, cols) = terminal_size();
+        terminal.draw(&app.render(exercises_dir, rows, cols))?;
+        let read = stdin.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        app.message.clear();
+        let page = rows.saturating_sub(3).max(1);
+        match decode_key(&buffer[..read]) {
+            Key::Char('q') => break,
+            Key::Up | Key::Char('k') => app.select(app.selected.saturating_sub(1)),
+            Key::Down | Key::Char('j') => {
+                app.select((app.selected + 1).min(app.statuses.len() - 1))
+            }
+            Key::PageUp | Key::Char('u') => app.scroll = app.scroll.saturating_sub(page),
+            Key::PageDown | Key::Char('d') => app.scroll += page,
+            Key::Char('h') => app.show_hint = !app.show_hint,
+            Key::Char('n') => match app.next_failure() {
+                Some(index) => app.select(index),
+                None => app.message = " All exercises passed!".to_string(),
+            },
+            Key::Char('r') => {
+                app.message = format!(" Running {}...", app.exercise().name);
+                terminal.draw(&app.render(exercises_dir, rows, cols))?;
+                let result = grader.evaluate(app.exercise());
+                app.message = format!(
+                    " {}: {}",
+                    result.name,
+                    if result.result { "PASSED" } else { "FAILED" }
+                );
+                let status = &mut app.statuses[app.selected];
+                status.last_result = Some(result.result);
+                status.last_score = Some(result.score);
+                app.outputs[app.selected] = Some(grader.runner().take_log());
+                app.scroll = 0;
+                report.record(result);
+            }
+            Key::Char('e') => {
+                let path = primary_source(&exercises_dir.join(&app.exercise().path));
+                terminal.leave()?;
+                let edited = open_in_editor(&path);
+                terminal = Terminal::enter()?;
+                if let Err(e) = edited {
+                    app.message = format!(" Failed to open editor: {}", e);
+                }
+                app.statuses = collect_statuses(config, Some(report), exercises_dir);
+            }
+            _ => {}
+        }
+    }
+    terminal.leave()
+}
+
+// 进入备用屏幕并关闭行缓冲与回显，退出时恢复终端设置
+struct Terminal {
+    saved: Option<String>,
+}
+
+impl Terminal {
+    fn enter() -> io::Result<Self> {
+        let saved = stty(&["-g"])?;
+        stty(&["-icanon", "-echo", "min", "1"])?;
+        print!("\x1b[?1049h\x1b[?25l");
+        io::stdout().flush()?;
+        Ok(Self {
+            saved: Some(saved.trim().to_string()),
+        })
+    }
+
+    fn draw(&mut self, screen: &[String]) -> io::Result<()> {
+        let mut frame = String::new();
+        for (row, line) in screen.iter().enumerate() {
+            frame.push_str(&format!("\x1b[{};1H{}\x1b[K", row + 1, line));
+        }
+        // 评测期间子进程可能输出到屏幕，每次都清掉多余内容
+        frame.push_str("\x1b[J");
+        let mut stdout = io::stdout().lock();
+        stdout.write_all(frame.as_bytes())?;
+        stdout.flush()
+    }
+
+    fn leave(&mut self) -> io::Result<()> {
+        if let Some(saved) = self.saved.take() {
+            print!("\x1b[?25h\x1b[?1049l");
+            io::stdout().flush()?;
+            stty(&[saved.as_str()])?;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for Terminal {
+    fn drop(&mut self) {
+        let _ = self.leave();
+    }
+}
+
+// 通过 stty 调整终端设置（stty 需要从标准输入读取终端）
+fn stty(args: &[&str]) -> io::Result<String> {
+    let output = Command::new("stty")
+        .args(args)
+        .stdin(Stdio::inherit())
+        .output()?;
+    if !output.status.success() {
+        return Err(io::Error::other(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+// 终端的行数与列数，获取失败时按 24x80 处理
+fn terminal_size() -> (usize, usize) {
+    stty(&["size"])
+        .ok()
+        .and_then(|size| {
+            let mut parts = size.split_whitespace().map(str::parse::<usize>);
+            match (parts.next(), parts.next()) {
+                (Some(Ok(rows)), Some(Ok(cols))) if rows > 0 && cols > 0 => Some((rows, cols)),
+                _ => None,
+            }
+        })
+        .unwrap_or((24, 80))
+}
+
+// 用 $VISUAL 或 $EDITOR 打开文件，二者都未设置时使用 vi
+fn open_in_editor(path: &Path) -> io::Result<()> {
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".to_string());
+    let mut parts = editor.split_whitespace();
+    let program = parts.next().unwrap_or("vi");
+    let status = Command::new(program).args(parts).arg(path).status()?;
+    if !status.success() {
+        return Err(io::Error::other(format!(
+            "{} exited with {}",
+            program, status
+        )));
+    }
+    Ok(())
+}
+
+// 学生需要编辑的主要源文件：单文件习题为其本身，Cargo 项目为 main.rs 与 tests.rs 之外的第一个源文件
+fn primary_source(path: &Path) -> PathBuf {
+    if !path.is_dir() {
+        return path.to_path_buf();
+    }
+    let src = path.join("src");
+    let mut files: Vec<PathBuf> = fs::read_dir(&src)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| entry.path())
+                .filter(|file| file.extension().is_some_and(|ext| ext == "rs"))
+                .collect()
+        })
+        .unwrap_or_default();
+    files.sort();
+    files
+        .into_iter()
+        .find(|file| !file.ends_with("main.rs") && !file.ends_with("tests.rs"))
+        .unwrap_or_else(|| src.join("main.rs"))
+}
+
+// 题目说明：取主要源文件开头的注释；没有时指向同目录下的题目 PDF
+fn statement(exercise: &Exercise, exercises_dir: &Path) -> String {
+    let path = exercises_dir.join(&exercise.path);
+    let comment = fs::read_to_string(primary_source(&path))
+        .map(|source| leading_comment(&source))
+        .unwrap_or_default();
+    if !comment.is_empty() {
+        return comment;
+    }
+    let level_dir = path.parent().unwrap_or(exercises_dir);
+    let pdfs: Vec<String> = fs::read_dir(level_dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| entry.path())
+                .filter(|file| file.extension().is_some_and(|ext| ext == "pdf"))
+                .map(|file| file.display().to_string())
+                .collect()
+        })
+        .unwrap_or_default();
+    if pdfs.is_empty() {
+        "No problem statement found.".to_string()
+    } else {
+        format!("See {}", pdfs.join(", "))
+    }
+}
+
+// 提取源码开头的 // 与 /* */ 注释（忽略未完成标记），遇到第一行代码为止
+pub fn leading_comment(source: &str) -> String {
+    let mut lines = Vec::new();
+    let mut in_block = false;
+    for line in source.lines() {
+        let trimmed = line.trim();
+        let text = if in_block {
+            match trimmed.strip_suffix("*/") {
+                Some(rest) => {
+                    in_block = false;
+                    rest
+                }
+                None => trimmed,
+            }
+        } else if let Some(rest) = trimmed.strip_prefix("/*") {
+            match rest.strip_suffix("*/") {
+                Some(rest) => rest,
+                None => {
+                    in_block = true;
+                    rest
+                }
+            }
+        } else if let Some(rest) = trimmed.strip_prefix("//") {
+            rest.trim_start_matches(['/', '!'])
+        } else if trimmed.is_empty() {
+            ""
+        } else {
+            break;
+        };
+        let text = text.trim();
+        // 连续的空行只保留一行
+        let blank_after_blank = text.is_empty() && lines.last().is_none_or(String::is_empty);
+        if text != NOT_DONE_MARKER && !blank_after_blank {
+            lines.push(text.to_string());
+        }
+    }
+    while lines.last().is_some_and(|line| line.is_empty()) {
+        lines.pop();
+    }
+    lines.join("\n")
+}
+
+// 字符的显示宽度，中日韩字符与全角符号占两列
+fn char_width(c: char) -> usize {
+    match c as u32 {
+        0x1100..=0x115F
+        | 0x2E80..=0xA4CF
+        | 0xAC00..=0xD7A3
+        | 0xF900..=0xFAFF
+        | 0xFE30..=0xFE4F
+        | 0xFF00..=0xFF60
+        | 0xFFE0..=0xFFE6 => 2,
+        _ => 1,
+    }
+}
+
+// 截断或补齐到指定显示宽度
+fn pad(text: &str, width: usize) -> String {
+    let mut result = String::new();
+    let mut used = 0;
+    for c in text.chars() {
+        let c = if c == '\t' { ' ' } else { c };
+        let w = char_width(c);
+        if used + w > width {
+            break;
+        }
+        result.push(c);
+        used += w;
+    }
+    result.push_str(&" ".repeat(width - used));
+    result
+}
+
+// 按显示宽度折行
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    if width == 0 {
+        return Vec::new();
+    }
+    let mut lines = vec![String::new()];
+    let mut used = 0;
+    for c in text.chars() {
+        let c = if c == '\t' { ' ' } else { c };
+        let w = char_width(c);
+        if used + w > width {
+            lines.push(String::new());
+            used = 0;
+        }
+        lines.last_mut().unwrap().push(c);
+        used += w;
+    }
+    lines
+}
+
+// 去掉输出中的颜色等控制序列，避免打乱界面
+fn strip_ansi(text: &str) -> String {
+    let mut result = String::new();
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            if chars.next() == Some('[') {
+                for c in chars.by_ref() {
+                    if c.is_ascii_alphabetic() {
+                        break;
+                    }
+                }
+            }
+        } else if c != '\r' {
+            result.push(c);
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_key() {
+        assert_eq!(decode_key(b"\x1b[A"), Key::Up);
+        assert_eq!(decode_key(b"\x1b[B"), Key::Down);
+        assert_eq!(decode_key(b"\x1b[6~"), Key::PageDown);
+        assert_eq!(decode_key(b"r"), Key::Char('r'));
+        assert_eq!(decode_key(b"\x1b"), Key::Other);
+    }
+
+    #[test]
+    fn test_leading_comment() {
+        let source = "/*\n    single linked list merge\n*/\n/*\n    单链表合并\n*/\n\nuse std::fmt;\n// not part of the statement\n";
+        assert_eq!(
+            leading_comment(source),
+            "single linked list merge\n\n单链表合并"
+        );
+        assert_eq!(leading_comment("// I AM NOT DONE\n\nmod district;\n"), "");
+        assert_eq!(
+            leading_comment("//! Sum of squares\nfn main() {}"),
+            "Sum of squares"
+        );
+    }
+
+    #[test]
+    fn test_pad_and_wrap_use_display_width() {
+        assert_eq!(pad("单链表", 5), "单链 ");
+        assert_eq!(pad("ab", 4), "ab  ");
+        assert_eq!(wrap("单链表合并", 4), vec!["单链", "表合", "并"]);
+        assert_eq!(strip_ansi("\x1b[32mok\x1b[0m\r"), "ok");
+    }
+
+    #[test]
+    fn test_render_and_next_failure() {
+        let config: ExerciseConfig = serde_json::from_str(
+            r#"{
+                "easy": [
+                    { "name": "algorithm1.rs", "path": "easy/algorithm1.rs", "type": "single_file", "score": 1 },
+                    { "name": "algorithm2.rs", "path": "easy/algorithm2.rs", "type": "single_file", "score": 1, "hint": "Use a cursor." }
+                ],
+                "normal": [],
+                "hard": []
+            }"#,
+        )
+        .unwrap();
+        let dir = Path::new("/nonexistent");
+        let mut app = App {
+            config: &config,
+            statuses: collect_statuses(&config, None, dir),
+            outputs: vec![Some("\x1b[31merror\x1b[0m".to_string()), None],
+            selected: 0,
+            scroll: 0,
+            show_hint: false,
+            message: String::new(),
+        };
+        app.statuses[0].last_result = Some(true);
+        app.statuses[0].last_score = Some(1);
+
+        let screen = app.render(dir, 10, 90);
+        assert_eq!(screen.len(), 10);
+        assert!(screen[0].contains("1/2 passed  score 1"));
+        assert!(screen[1].contains("algorithm1.rs (easy, single_file, 1 points)"));
+        assert!(screen.iter().any(|line| line.contains("│error")));
+
+        assert_eq!(app.next_failure(), Some(1));
+        app.select(1);
+        app.show_hint = true;
+        let screen = app.render(dir, 12, 90);
+        assert!(screen.iter().any(|line| line.contains("Use a cursor.")));
+        assert!(screen
+            .iter()
+            .any(|line| line.contains("Not run in this session")));
+
+        app.statuses[1].last_result = Some(true);
+        assert_eq!(app.next_failure(), None);
+    }
+}
@@ -0,0 +1,144 @@
+use crate::config::ExerciseConfig;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+// 生成的工作区清单的首行，用于识别上次异常退出时遗留的文件
+const GENERATED_HEADER: &str =
+    "# Generated by the grader for shared workspace builds, do not edit.";
+
+// 评测期间临时生成的 Cargo 工作区，包含全部 cargo_project 习题并共享一个 target 目录
+// 清单写在 exercises/ 下（成员必须位于工作区根目录之下），离开作用域时删除
+#[derive(Debug)]
+pub struct SharedWorkspace {
+    manifest_path: PathBuf,
+    target_dir: PathBuf,
+}
+
+impl SharedWorkspace {
+    pub fn create(
+        config: &ExerciseConfig,
+        exercises_dir: &Path,
+        target_dir: &Path,
+    ) -> io::Result<Self> {
+        let manifest_path = exercises_dir.join("Cargo.toml");
+        if let Ok(existing) = fs::read_to_string(&manifest_path) {
+            if !existing.starts_with(GENERATED_HEADER) {
+                return Err(io::Error::new(
+                    io::ErrorKind::AlreadyExists,
+                    format!("{} already exists", manifest_path.display()),
+                ));
+            }
+        }
+
+        let members: Vec<String> = config
+            .exercises()
+            .filter(|exercise| exercise.exercise_type == "cargo_project")
+            .map(|exercise| format!("    {:?},", exercise.path))
+            .collect();
+        let manifest = format!(
+            "{}\n[workspace]\nresolver = \"2\"\nmembers = [\n{}\n]\n",
+            GENERATED_HEADER,
+            members.join("\n")
+        );
+        fs::write(&manifest_path, manifest)?;
+        fs::create_dir_all(target_dir)?;
+
+        Ok(Self {
+            manifest_path: fs::canonicalize(&manifest_path)?,
+            target_dir: fs::canonicalize(target_dir)?,
+        })
+    }
+
+    pub fn manifest_path(&self) -> &Path {
+        &self.manifest_path
+    }
+
+    pub fn target_dir(&self) -> &Path {
+        &self.target_dir
+    }
+}
+
+impl Drop for SharedWorkspace {
+    // 删除生成的清单与 Cargo.lock，习题目录恢复为各自独立的 crate
+    fn drop(&mut self) {
+        if let Err(e) = fs::remove_file(&self.manifest_path) {
+            eprintln!("Failed to remove {}: {}", self.manifest_path.display(), e);
+        }
+        let _ = fs::remove_file(self.manifest_path.with_file_name("Cargo.lock"));
+    }
+}
+
+// 读取 Cargo 项目 [package] 中的 name，用于 cargo -p
+pub fn package_name(project_dir: &Path) -> Option<String> {
+    let manifest = fs::read_to_string(project_dir.join("Cargo.toml")).ok()?;
+    let mut in_package = false;
+    for line in manifest.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_package = line == "[package]";
+        } else if in_package {
+            if let Some(value) = line
+                .strip_prefix("name")
+                .and_then(|rest| rest.trim_start().strip_prefix('='))
+            {
+                return Some(value.trim().trim_matches('"').to_string());
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> ExerciseConfig {
+        serde_json::from_str(
+            r#"{
+                "easy": [{ "name": "algorithm1.rs", "path": "easy/algorithm1.rs", "type": "single_file", "score": 1 }],
+                "normal": [{ "name": "solution1", "path": "normal/solution1", "type": "cargo_project", "score": 6 }],
+                "hard": [{ "name": "solutiont1", "path": "hard/solutiont1", "type": "cargo_project", "score": 10 }]
+            }"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_workspace_lists_cargo_projects_and_is_removed() {
+        let dir = std::env::temp_dir().join(format!("grader_workspace_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let workspace = SharedWorkspace::create(&config(), &dir, &dir.join("target")).unwrap();
+        let manifest = fs::read_to_string(workspace.manifest_path()).unwrap();
+        assert!(manifest.contains("\"normal/solution1\","));
+        assert!(manifest.contains("\"hard/solutiont1\","));
+        assert!(!manifest.contains("algorithm1"));
+        assert!(workspace.target_dir().ends_with("target"));
+
+        // 模拟异常退出后遗留的生成文件，再次生成时可以覆盖
+        std::mem::forget(workspace);
+        let again = SharedWorkspace::create(&config(), &dir, &dir.join("target")).unwrap();
+        drop(again);
+        assert!(!dir.join("Cargo.toml").exists());
+
+        // 不覆盖他人的 Cargo.toml
+        fs::write(dir.join("Cargo.toml"), "[package]\n").unwrap();
+        let err = SharedWorkspace::create(&config(), &dir, &dir.join("target")).unwrap_err();
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::AlreadyExists);
+    }
+
+    #[test]
+    fn test_package_name() {
+        let dir = std::env::temp_dir().join(format!("grader_package_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("Cargo.toml"),
+            "[package]\nname = \"solutiont1\"\nversion = \"0.1.0\"\n\n[[test]]\nname = \"tests\"\n",
+        )
+        .unwrap();
+        let name = package_name(&dir);
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(name.as_deref(), Some("solutiont1"));
+        assert_eq!(package_name(Path::new("/nonexistent")), None);
+    }
+}